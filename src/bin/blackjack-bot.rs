@@ -0,0 +1,9 @@
+use blackjack::game::bot::run_reference_bot;
+use std::io::{stdin, stdout};
+
+///blackjack-bot is the reference bot for the stdio bot protocol: it bets 10 and plays basic strategy
+fn main() {
+    if let Err(error) = run_reference_bot(stdin().lock(), stdout().lock()) {
+        eprintln!("blackjack-bot: {error}");
+    }
+}
@@ -0,0 +1,50 @@
+use blackjack::game::components::Deck;
+use blackjack::game::constants::MAX_SEATS;
+use blackjack::game::protocol::{DEFAULT_SERVER_ADDRESS, PROTOCOL_VERSION};
+use blackjack::game::rules::Rules;
+use blackjack::game::server::serve;
+use blackjack::game::table::Table;
+use std::net::TcpListener;
+use std::process::exit;
+
+static USAGE: &str = "usage: blackjack-server [--bind <address>] [--seats <1-7>] [--balance <amount>] [--seed <number>]";
+
+///blackjack-server hosts a blackjack table on the network, it deals, keeps every bankroll and settles every round
+fn main() {
+    let mut address = DEFAULT_SERVER_ADDRESS.to_string();
+    let mut seats = MAX_SEATS;
+    let mut balance = 1000;
+    let mut seed = None;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let value = match arg.as_str() {
+            "--help" | "-h" => {
+                println!("{USAGE}");
+                return;
+            }
+            _ => args.next().unwrap_or_else(|| fail(&format!("{arg} needs a value"))),
+        };
+        match arg.as_str() {
+            "--bind" => address = value,
+            "--seats" => seats = value.parse().ok().filter(|seats| (1..=MAX_SEATS).contains(seats))
+                .unwrap_or_else(|| fail("--seats must be between 1 and 7")),
+            "--balance" => balance = value.parse().ok().filter(|balance| *balance > 0)
+                .unwrap_or_else(|| fail("--balance must be a positive number")),
+            "--seed" => seed = Some(value.parse().unwrap_or_else(|_| fail("--seed must be a number"))),
+            _ => fail(&format!("unknown argument {arg}")),
+        }
+    }
+
+    //the server logs its dropped connections through bevy's log macros, without a bevy app to set them up
+    bevy::log::tracing_subscriber::fmt().with_writer(std::io::stderr).init();
+    let deck = seed.map_or_else(Deck::default, Deck::with_seed);
+    let listener = TcpListener::bind(&address).unwrap_or_else(|error| fail(&format!("could not listen on {address}: {error}")));
+    println!("Blackjack server (protocol version {PROTOCOL_VERSION}) listening on {address} with {seats} seats, shoe seed {}", deck.seed);
+    serve(listener, Table::new(deck, Rules::default(), seats, balance));
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("{message}\n{USAGE}");
+    exit(2);
+}
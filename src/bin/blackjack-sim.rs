@@ -0,0 +1,51 @@
+use blackjack::game::bot::BotProcess;
+use blackjack::game::components::Deck;
+use blackjack::game::constants::BOT_REPLY_TIMEOUT_MS;
+use blackjack::game::rules::Rules;
+use blackjack::game::simulator::simulate;
+use std::process::exit;
+use std::time::Duration;
+
+static USAGE: &str = "usage: blackjack-sim --bot \"<command>\" [--rounds <number>] [--balance <amount>] [--seed <number>] [--timeout-ms <milliseconds>]";
+
+///blackjack-sim has a bot play rounds without a window, as fast as it answers, and prints how it did
+fn main() {
+    let mut command = None;
+    let mut rounds = 1000;
+    let mut balance = 1000;
+    let mut seed = None;
+    let mut timeout = BOT_REPLY_TIMEOUT_MS;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let value = match arg.as_str() {
+            "--help" | "-h" => {
+                println!("{USAGE}");
+                return;
+            }
+            _ => args.next().unwrap_or_else(|| fail(&format!("{arg} needs a value"))),
+        };
+        match arg.as_str() {
+            "--bot" => command = Some(value),
+            "--rounds" => rounds = value.parse().unwrap_or_else(|_| fail("--rounds must be a number")),
+            "--balance" => balance = value.parse().ok().filter(|balance| *balance > 0)
+                .unwrap_or_else(|| fail("--balance must be a positive number")),
+            "--seed" => seed = Some(value.parse().unwrap_or_else(|_| fail("--seed must be a number"))),
+            "--timeout-ms" => timeout = value.parse().unwrap_or_else(|_| fail("--timeout-ms must be a number")),
+            _ => fail(&format!("unknown argument {arg}")),
+        }
+    }
+    let command = command.unwrap_or_else(|| fail("--bot is required"));
+
+    let rules = Rules::default();
+    let deck = seed.map_or_else(Deck::default, Deck::with_seed);
+    println!("Simulating {rounds} rounds of `{command}`, shoe seed {}", deck.seed);
+    let mut bot = BotProcess::spawn(&command, &rules, Duration::from_millis(timeout))
+        .unwrap_or_else(|error| fail(&format!("could not start the bot: {error}")));
+    println!("{}", simulate(&mut bot, deck, rules, balance, rounds));
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("{message}\n{USAGE}");
+    exit(2);
+}
@@ -1,9 +1,11 @@
-///game module is the central core of the game where resources, systems, states, and plugins are added.
-/// this is also responsible for running specific systems under certain conditions when certain actions occur.
+///game module is the central core of the game where resources, systems, states, and plugins are declared. the plugins
+/// module adds them to an app and is responsible for running specific systems under certain conditions when certain actions occur.
 
 pub mod components;
 pub mod player_systems;
 pub mod dealer_systems;
+pub mod settlement;
+pub mod rules;
 pub mod plugins;
 pub mod setup;
 pub mod constants;
@@ -12,18 +14,48 @@ pub mod start_game_systems;
 pub mod in_game_systems;
 pub mod traits;
 pub mod resources;
+pub mod events;
+pub mod storage;
+pub mod statistics;
+pub mod stats_screen_systems;
+pub mod hand_history;
+pub mod strategy;
+pub mod replay;
+pub mod replay_screen_systems;
+pub mod companions;
+pub mod protocol;
+pub mod table;
+pub mod server;
+pub mod client;
+pub mod online;
+pub mod online_screen_systems;
+pub mod spectator;
+pub mod bot;
+pub mod simulator;
+pub mod bot_systems;
+pub mod scripting;
+pub mod autoplay;
+pub mod round_state;
+pub mod scenario;
+pub mod errors;
+pub mod logging;
+pub mod cli;
+pub mod audio;
+pub mod console;
+pub mod inspector;
+pub mod main_menu_systems;
+pub mod trainer;
+pub mod trainer_screen_systems;
+pub mod rules_screen_systems;
+pub mod pause_menu_systems;
+pub mod settings;
+pub mod settings_screen_systems;
 
+use std::path::PathBuf;
 use bevy::prelude::*;
-use components::Deck;
-use constants::{AppState, DeckState, GameRoundState};
-use in_game_systems::{chip_button_click_system, despawn_cards_and_reset, in_game_setup, player_button_system, print_all_dealer_cards, reset_game, track_app_state, track_game_state};
-use resources::{BalanceValue, BetValue, ParentNode};
-use setup::{ingame_screen_setup, reload_home_screen, start_setup};
-use start_game_systems::start_game;
-use plugins::StartupPlugin;
-
-use player_systems::{hit_player_hand, stand_player_hand, double_down_player_hand, initial_shuffle, spawn_player, spawn_test_player};
-use dealer_systems::{play_dealer_hand, reveal_dealer_hand, shuffle_dealer_decks, spawn_dealer, spawn_test_dealer};
+use constants::AppState;
+use plugins::{BlackjackConsolePlugin, BlackjackCorePlugin, BlackjackInspectorPlugin, BlackjackOnlinePlugin, BlackjackUiPlugin, HeadlessPlugin};
+use storage::DataDir;
 
 #[derive(SystemSet, Debug, Hash, PartialEq, Eq, Clone)]
 pub struct StartGameSystemSet;
@@ -34,69 +66,25 @@ pub struct DeckSystemSet;
 #[derive(SystemSet, Debug, Hash, PartialEq, Eq, Clone)]
 pub struct PlayerGameplaySet;
 
-pub fn run() {
-    App::new()
-        // Set system execution order
-        .configure_sets(Startup, StartGameSystemSet.before(SetupGameSystemSet))
-        .configure_sets(Startup, SetupGameSystemSet.before(DeckSystemSet))
-        .configure_sets(Update, PlayerGameplaySet.run_if(in_state(AppState::InGame)).run_if(in_state(GameRoundState::PlayerHand)))
-        
-        // Add startup systems
-        .add_systems(Startup, start_setup.in_set(StartGameSystemSet))
-        .add_systems(Startup, initial_shuffle)
-        .add_systems(Startup, spawn_player)
-        .add_systems(Startup, spawn_dealer)
-        
-        // Add resources
-        .insert_resource(BetValue { value: 0 })
-        .insert_resource(BalanceValue { value: 1000 })
-        .insert_resource(Deck::default())
-        .insert_resource(ParentNode::default())
-
-        // Add in-game systems
-        .add_systems(Update, start_game.in_set(StartGameSystemSet))
-        .add_systems(Update, ingame_screen_setup.in_set(SetupGameSystemSet).run_if(in_state(AppState::InGame).and_then(run_once())))
-        .add_systems(Update, in_game_setup.in_set(SetupGameSystemSet).run_if(in_state(AppState::InGame).and_then(run_once())))
-        .add_systems(Update, chip_button_click_system.in_set(SetupGameSystemSet).run_if(in_state(AppState::InGame)))
-        .add_systems(Update, player_button_system.in_set(SetupGameSystemSet).run_if(in_state(AppState::InGame)))
-        
-        // Add player gameplay systems
-        .add_systems(Update, hit_player_hand.in_set(PlayerGameplaySet).run_if(in_state(AppState::InGame)))
-        .add_systems(Update, stand_player_hand.in_set(PlayerGameplaySet).run_if(in_state(AppState::InGame)))
-        .add_systems(Update, double_down_player_hand.in_set(PlayerGameplaySet).run_if(in_state(AppState::InGame)))
-
-        // Add dealer systems
-        .add_systems(OnEnter(GameRoundState::DealerHand), play_dealer_hand)
-        
-        // Track game states on entry
-        .add_systems(OnEnter(GameRoundState::PlayerHand), track_game_state)
-        .add_systems(OnEnter(GameRoundState::DealerHand), track_game_state)
-        .add_systems(OnEnter(GameRoundState::RoundEnd), track_game_state)
-        .add_systems(OnEnter(GameRoundState::RoundStart), track_game_state)
-        .add_systems(OnEnter(GameRoundState::Betting), track_game_state)
-
-        .add_systems(OnEnter(GameRoundState::Betting), despawn_cards_and_reset)
-        .add_systems(OnEnter(GameRoundState::Betting), in_game_setup)
-        
-        // Track app states on entry
-        .add_systems(OnEnter(AppState::Start), track_app_state)
-        .add_systems(OnEnter(AppState::InGame), track_app_state)
-
-        .add_systems(OnEnter(AppState::Start), reset_game)
-
-        // Clean-up on exit
-        .add_systems(OnExit(AppState::InGame), reload_home_screen)
-        .add_systems(OnExit(AppState::Start), ingame_screen_setup)
-        .add_systems(OnExit(AppState::Start), in_game_setup)
-        .add_systems(OnExit(GameRoundState::PlayerHand), reveal_dealer_hand)
+///headless_app builds the game without a window, for tests and for playing without a screen: the table, its screens and
+/// the online table, the developer console and the inspector on top of HeadlessPlugin. it starts on the start screen
+pub fn headless_app() -> App {
+    headless_app_with_data_dir(storage::data_dir())
+}
 
-        // Add plugins and set initial states
-        .add_plugins(StartupPlugin)
-        .insert_state(AppState::Start)
-        .insert_state(DeckState::NotShuffled)
-        .insert_state(GameRoundState::RoundStart)
-        
-        .run();
+///headless_app_with_data_dir is headless_app saving its settings, statistics and hand history under the given directory
+pub fn headless_app_with_data_dir(data_dir: PathBuf) -> App {
+    let mut app = App::new();
+    app.insert_resource(DataDir(data_dir));
+    app.add_plugins((
+        HeadlessPlugin,
+        BlackjackCorePlugin::default().with_initial_state(AppState::Start),
+        BlackjackUiPlugin::default(),
+        BlackjackOnlinePlugin::default(),
+        BlackjackConsolePlugin,
+        BlackjackInspectorPlugin,
+    ));
+    app
 }
 
 
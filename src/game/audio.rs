@@ -0,0 +1,99 @@
+///audio module plays the sounds given to BlackjackAudioPlugin when something happens at the table: a card dealt, an action
+/// taken on one of the player's hands, a hand of the player's won, lost or pushed, and the shoe shuffled. the music, if one
+/// is given, loops in the background, and both follow the volumes of the settings as they are changed.
+
+use std::collections::HashMap;
+use bevy::audio::Volume;
+use bevy::prelude::*;
+use super::components::GameMusic;
+use super::events::{ActionTaken, CardDealt, HandSettled, RoundOutcome, ShoeShuffled};
+use super::settings::Settings;
+
+///enum GameSound is what a sound can be played for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GameSound {
+    Card,
+    ///an action on one of the player's hands
+    Action,
+    Win,
+    Loss,
+    Push,
+    Shuffle,
+}
+
+impl From<RoundOutcome> for GameSound {
+    fn from(outcome: RoundOutcome) -> Self {
+        match outcome {
+            RoundOutcome::Blackjack | RoundOutcome::Win => GameSound::Win,
+            RoundOutcome::Loss => GameSound::Loss,
+            RoundOutcome::Push => GameSound::Push,
+        }
+    }
+}
+
+///struct / resource GameSounds is the path of the audio asset played for each sound and of the music, and the volume they
+/// are played at before the volumes of the settings
+#[derive(Resource, Default)]
+pub struct GameSounds {
+    pub volume: f32,
+    pub sounds: HashMap<GameSound, String>,
+    pub music: Option<String>,
+}
+
+///play_game_sounds plays the sounds of what happened since the last frame, each sound at most once a frame so the cards
+/// dealt or the seats settled together are heard once
+pub fn play_game_sounds(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    game_sounds: Res<GameSounds>,
+    settings: Res<Settings>,
+    mut shoe_shuffled: EventReader<ShoeShuffled>,
+    mut card_dealt: EventReader<CardDealt>,
+    mut action_taken: EventReader<ActionTaken>,
+    mut hand_settled: EventReader<HandSettled>,
+) {
+    let mut to_play = Vec::new();
+    let sounds = shoe_shuffled.read().map(|_| GameSound::Shuffle)
+        .chain(card_dealt.read().map(|_| GameSound::Card))
+        .chain(action_taken.read().filter(|taken| !taken.companion).map(|_| GameSound::Action))
+        .chain(hand_settled.read().filter(|settled| !settled.companion).map(|settled| GameSound::from(settled.outcome)));
+    for sound in sounds {
+        if !to_play.contains(&sound) {
+            to_play.push(sound);
+        }
+    }
+    let volume = game_sounds.volume * settings.master_volume * settings.sfx_volume;
+    for sound in to_play {
+        if let Some(path) = game_sounds.sounds.get(&sound) {
+            commands.spawn(AudioBundle {
+                source: asset_server.load::<AudioSource>(path.clone()),
+                settings: PlaybackSettings::DESPAWN.with_volume(Volume::new(volume)),
+            });
+        }
+    }
+}
+
+///play_music starts the music looping at the volume of the settings
+pub fn play_music(mut commands: Commands, asset_server: Res<AssetServer>, game_sounds: Res<GameSounds>, settings: Res<Settings>) {
+    let Some(path) = &game_sounds.music else {
+        return;
+    };
+    commands.spawn((
+        AudioBundle {
+            source: asset_server.load::<AudioSource>(path.clone()),
+            settings: PlaybackSettings::LOOP.with_volume(Volume::new(game_sounds.volume * settings.master_volume * settings.music_volume)),
+        },
+        GameMusic,
+    ));
+}
+
+///apply_music_volume changes the volume of the music playing when the volumes of the settings are changed, the sound
+/// effects already playing end at the volume they started at
+pub fn apply_music_volume(game_sounds: Res<GameSounds>, settings: Res<Settings>, music_query: Query<&AudioSink, With<GameMusic>>) {
+    if !settings.is_changed() {
+        return;
+    }
+    for sink in music_query.iter() {
+        sink.set_volume(game_sounds.volume * settings.master_volume * settings.music_volume);
+    }
+}
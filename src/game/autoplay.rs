@@ -0,0 +1,339 @@
+///autoplay module plays the player's seats on its own: it bets with a betting progression, plays each hand with a strategy
+/// and deals the next round, by sending the same actions as the player. it stops on its own once the player is up or down
+/// by the chosen amounts, or after the chosen number of hands.
+
+use bevy::prelude::*;
+use super::bot::BotPlayer;
+use super::companions::Personality;
+use super::components::{Companion, DealerHand, Deck, PlayerButtonValues, PlayerHands, Seat, TextComponents};
+use super::dealer_systems::dealer_up_value;
+use super::constants::{GameRoundState, AUTOPLAY_BASE_BET, AUTOPLAY_DELAY, AUTOPLAY_HAND_LIMITS, AUTOPLAY_SPEEDS, AUTOPLAY_STOP_AMOUNTS};
+use super::errors::GameError;
+use super::events::{ActionKind, HandSettled, PlayerAction, RoundOutcome};
+use super::hand_history::HandAction;
+use super::player_systems::place_bet_on_first_player_seat;
+use super::resources::{ActiveSeat, BalanceValue, BetValue, LocalSpectator};
+use super::rules::Rules;
+use super::scripting::{BetInputs, BetProgress, CompanionScripts, HandInputs};
+use super::strategy::basic_strategy;
+use super::traits::Scorable;
+
+///enum AutoplayStrategy is how autoplay plays its hands: like one of the companion personalities, or with a script
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AutoplayStrategy {
+    Personality(Personality),
+    ///index into CompanionScripts, of a script defining an action function
+    Script(usize),
+}
+
+///enum AutoplayProgression is how autoplay sizes its bets: always the base bet, or with a script
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AutoplayProgression {
+    Flat,
+    ///index into CompanionScripts, of a script defining a bet function
+    Script(usize),
+}
+
+///struct / resource Autoplay holds whether autoplay is on and how it plays. speed, stop_win, stop_loss and hand_limit are
+/// indexes into AUTOPLAY_SPEEDS, AUTOPLAY_STOP_AMOUNTS and AUTOPLAY_HAND_LIMITS, a limit of 0 meaning no limit
+#[derive(Resource)]
+pub struct Autoplay {
+    pub enabled: bool,
+    pub strategy: AutoplayStrategy,
+    pub progression: AutoplayProgression,
+    pub speed: usize,
+    pub stop_win: usize,
+    pub stop_loss: usize,
+    pub hand_limit: usize,
+    pub hands_played: u32,
+    ///balance when autoplay was turned on, what the stop-win and stop-loss are measured from
+    pub starting_balance: i32,
+    pub progress: BetProgress,
+    ///why autoplay last turned itself off
+    pub stopped_because: Option<String>,
+    pub timer: Timer,
+}
+
+impl Default for Autoplay {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            strategy: AutoplayStrategy::Personality(Personality::BasicStrategy),
+            progression: AutoplayProgression::Flat,
+            speed: 1,
+            stop_win: 3,
+            stop_loss: 3,
+            hand_limit: 3,
+            hands_played: 0,
+            starting_balance: 0,
+            progress: BetProgress::default(),
+            stopped_because: None,
+            timer: Timer::from_seconds(AUTOPLAY_DELAY, TimerMode::Once),
+        }
+    }
+}
+
+impl Autoplay {
+    ///start turns autoplay on, counting hands and winnings from the current balance
+    pub fn start(&mut self, balance: i32) {
+        self.enabled = true;
+        self.hands_played = 0;
+        self.starting_balance = balance;
+        self.progress = BetProgress::default();
+        self.stopped_because = None;
+        self.timer = Timer::from_seconds(AUTOPLAY_DELAY / AUTOPLAY_SPEEDS[self.speed], TimerMode::Once);
+    }
+
+    ///stop turns autoplay off, reason being shown to the player
+    pub fn stop(&mut self, reason: &str) {
+        self.enabled = false;
+        self.stopped_because = Some(reason.to_string());
+    }
+
+    ///record adds a settled round and turns autoplay off once a stop is reached
+    pub fn record(&mut self, outcome: RoundOutcome, wager: i32, balance: i32) {
+        self.progress.record(outcome, wager);
+        self.hands_played += 1;
+        let net = balance - self.starting_balance;
+        let stop_win = AUTOPLAY_STOP_AMOUNTS[self.stop_win];
+        let stop_loss = AUTOPLAY_STOP_AMOUNTS[self.stop_loss];
+        let hand_limit = AUTOPLAY_HAND_LIMITS[self.hand_limit];
+        if stop_win > 0 && net >= stop_win {
+            self.stop(&format!("stop-win reached ({net:+})"));
+        } else if stop_loss > 0 && -net >= stop_loss {
+            self.stop(&format!("stop-loss reached ({net:+})"));
+        } else if hand_limit > 0 && self.hands_played >= hand_limit {
+            self.stop(&format!("played {hand_limit} hands ({net:+})"));
+        } else if balance <= 0 {
+            self.stop("out of money");
+        }
+    }
+
+    ///next_strategy cycles through the personalities and then the scripts that play hands
+    pub fn next_strategy(&mut self, companion_scripts: &CompanionScripts) {
+        let first_script_after = |index: Option<usize>| companion_scripts.scripts.iter().enumerate()
+            .find(|(i, script)| index.is_none_or(|index| *i > index) && script.plays())
+            .map(|(i, _)| AutoplayStrategy::Script(i));
+        let first_personality = AutoplayStrategy::Personality(Personality::ALL[0]);
+        self.strategy = match self.strategy {
+            AutoplayStrategy::Personality(personality) => match personality.next() {
+                Some(personality) => AutoplayStrategy::Personality(personality),
+                None => first_script_after(None).unwrap_or(first_personality),
+            },
+            AutoplayStrategy::Script(index) => first_script_after(Some(index)).unwrap_or(first_personality),
+        };
+    }
+
+    ///next_progression cycles from flat betting through the scripts that size bets
+    pub fn next_progression(&mut self, companion_scripts: &CompanionScripts) {
+        let after = match self.progression {
+            AutoplayProgression::Flat => None,
+            AutoplayProgression::Script(index) => Some(index),
+        };
+        self.progression = companion_scripts.scripts.iter().enumerate()
+            .find(|(i, script)| after.is_none_or(|after| *i > after) && script.bets())
+            .map_or(AutoplayProgression::Flat, |(i, _)| AutoplayProgression::Script(i));
+    }
+
+    ///summary returns the lines shown in the autoplay panel
+    pub fn summary(&self, companion_scripts: &CompanionScripts) -> String {
+        let script_name = |index: usize| companion_scripts.scripts.get(index).map_or("?", |script| script.name.as_str());
+        let strategy = match self.strategy {
+            AutoplayStrategy::Personality(personality) => personality.label(),
+            AutoplayStrategy::Script(index) => script_name(index),
+        };
+        let progression = match self.progression {
+            AutoplayProgression::Flat => "Flat",
+            AutoplayProgression::Script(index) => script_name(index),
+        };
+        let limit = |amount: i32| if amount > 0 { amount.to_string() } else { String::from("off") };
+        let state = if self.enabled {
+            format!("ON - hand {}", self.hands_played + 1)
+        } else {
+            self.stopped_because.as_ref().map_or_else(|| String::from("off"), |reason| format!("off, {reason}"))
+        };
+        format!(
+            "[A] Autoplay: {state}\n[T] strategy: {strategy}  [P] bets: {progression}  [F] speed: {}x\n[W] stop-win: {}  [L] stop-loss: {}  [N] hands: {}",
+            AUTOPLAY_SPEEDS[self.speed],
+            limit(AUTOPLAY_STOP_AMOUNTS[self.stop_win]),
+            limit(AUTOPLAY_STOP_AMOUNTS[self.stop_loss]),
+            limit(AUTOPLAY_HAND_LIMITS[self.hand_limit] as i32),
+        )
+    }
+}
+
+///autoplay_controls turns autoplay on and off with the autoplay button or A, and changes how it plays with T, P, F, W, L and N.
+/// autoplay stays off while a bot plays the seats or a table is only watched
+pub fn autoplay_controls(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut autoplay: ResMut<Autoplay>,
+    balance_value: Res<BalanceValue>,
+    companion_scripts: Res<CompanionScripts>,
+    bot_player: Res<BotPlayer>,
+    spectator: Res<LocalSpectator>,
+    mut button_query: Query<(&mut Interaction, &PlayerButtonValues)>,
+) {
+    let mut toggle = keyboard_input.just_pressed(KeyCode::KeyA);
+    for (mut interaction, value) in button_query.iter_mut() {
+        if *value == PlayerButtonValues::Autoplay && *interaction == Interaction::Pressed {
+            toggle = true;
+            *interaction = Interaction::None;
+        }
+    }
+    if toggle {
+        if autoplay.enabled {
+            autoplay.stop("turned off");
+        } else if bot_player.process.is_some() || spectator.watching {
+            info!("Autoplay is not available while a bot plays or the table is only watched");
+        } else {
+            autoplay.start(balance_value.value);
+        }
+    }
+    if keyboard_input.just_pressed(KeyCode::KeyT) {
+        autoplay.next_strategy(&companion_scripts);
+    }
+    if keyboard_input.just_pressed(KeyCode::KeyP) {
+        autoplay.next_progression(&companion_scripts);
+    }
+    if keyboard_input.just_pressed(KeyCode::KeyF) {
+        autoplay.speed = (autoplay.speed + 1) % AUTOPLAY_SPEEDS.len();
+        let delay = AUTOPLAY_DELAY / AUTOPLAY_SPEEDS[autoplay.speed];
+        autoplay.timer = Timer::from_seconds(delay, TimerMode::Once);
+    }
+    if keyboard_input.just_pressed(KeyCode::KeyW) {
+        autoplay.stop_win = (autoplay.stop_win + 1) % AUTOPLAY_STOP_AMOUNTS.len();
+    }
+    if keyboard_input.just_pressed(KeyCode::KeyL) {
+        autoplay.stop_loss = (autoplay.stop_loss + 1) % AUTOPLAY_STOP_AMOUNTS.len();
+    }
+    if keyboard_input.just_pressed(KeyCode::KeyN) {
+        autoplay.hand_limit = (autoplay.hand_limit + 1) % AUTOPLAY_HAND_LIMITS.len();
+    }
+}
+
+///drive_autoplay bets, plays and deals the next round each time the autoplay timer finishes, and counts every settled
+/// round towards the stops once it is over
+pub fn drive_autoplay(
+    time: Res<Time>,
+    mut autoplay: ResMut<Autoplay>,
+    game_state: Res<State<GameRoundState>>,
+    rules: Res<Rules>,
+    deck: Res<Deck>,
+    active_seat: Res<ActiveSeat>,
+    mut companion_scripts: ResMut<CompanionScripts>,
+    mut balance_value: ResMut<BalanceValue>,
+    mut bet_value: ResMut<BetValue>,
+    dealer_query: Query<&DealerHand>,
+    mut player_query: Query<(&Seat, &mut PlayerHands, Has<Companion>)>,
+    mut round_settled: EventReader<HandSettled>,
+    mut player_actions: EventWriter<PlayerAction>,
+    mut settled_this_round: Local<Vec<HandSettled>>,
+) -> Result<(), GameError> {
+
+    if !autoplay.enabled {
+        round_settled.clear();
+        settled_this_round.clear();
+        return Ok(());
+    }
+
+    //a bust seat is settled before the others, the hands are kept until the round is over. the seats of the player's are
+    //then added up into a single hand: the round is recorded once, for the progression and the hand limit alike
+    settled_this_round.extend(round_settled.read().filter(|settled| !settled.companion).cloned());
+    if *game_state.get() == GameRoundState::RoundEnd && !settled_this_round.is_empty() {
+        let settled = std::mem::take(&mut *settled_this_round);
+        let wager = settled.iter().map(|settled| settled.wager).sum();
+        let net: i32 = settled.iter().map(|settled| settled.net).sum();
+        let outcome = match net {
+            net if net > 0 => RoundOutcome::Win,
+            net if net < 0 => RoundOutcome::Loss,
+            _ => RoundOutcome::Push,
+        };
+        autoplay.record(outcome, wager, balance_value.value);
+        if !autoplay.enabled {
+            return Ok(());
+        }
+    }
+
+    if !autoplay.timer.tick(time.delta()).finished() {
+        return Ok(());
+    }
+    autoplay.timer.reset();
+
+    match game_state.get() {
+        GameRoundState::Betting => {
+            if bet_value.value == 0 {
+                let amount = match autoplay.progression {
+                    AutoplayProgression::Flat => AUTOPLAY_BASE_BET,
+                    AutoplayProgression::Script(index) => {
+                        let inputs = BetInputs {
+                            bankroll: balance_value.value,
+                            running_count: deck.running_count(),
+                            base_bet: AUTOPLAY_BASE_BET,
+                            progress: autoplay.progress.clone(),
+                        };
+                        match companion_scripts.scripts.get(index).map(|script| script.bet(&inputs)) {
+                            Some(Ok(amount)) => amount,
+                            Some(Err(error)) => {
+                                companion_scripts.report(error);
+                                AUTOPLAY_BASE_BET
+                            }
+                            None => AUTOPLAY_BASE_BET,
+                        }
+                    }
+                }.min(balance_value.value);
+
+                if amount <= 0 {
+                    autoplay.stop("out of money");
+                    return Ok(());
+                }
+                if !place_bet_on_first_player_seat(&mut player_query, amount, &mut balance_value, &mut bet_value)? {
+                    return Ok(());
+                }
+            }
+            player_actions.send(PlayerAction::new(ActionKind::Deal));
+        }
+        GameRoundState::PlayerHand => {
+            let player_turn = player_query.iter()
+                .find(|(seat, player_hands, is_companion)| seat.0 == active_seat.0 && !is_companion && player_hands.0.first().is_some_and(|hand| !hand.cards.is_empty()));
+            let dealer_up_card = dealer_up_value(&dealer_query);
+            let Some((seat, player_hands, _)) = player_turn else {
+                return Ok(());
+            };
+            let player_hand = player_hands.0.first().ok_or(GameError::MissingHand(seat.0))?;
+            let can_double = rules.can_double(player_hand) && balance_value.value >= player_hand.bet as i32;
+            let action = match autoplay.strategy {
+                AutoplayStrategy::Personality(personality) => personality.choose_action(player_hand, dealer_up_card, &rules),
+                AutoplayStrategy::Script(index) => {
+                    let inputs = HandInputs::from_hand(player_hand, dealer_up_card, deck.running_count(), balance_value.value, can_double);
+                    companion_scripts.choose_action(index, &inputs)
+                        .unwrap_or_else(|| basic_strategy(inputs.total, inputs.soft, dealer_up_card, can_double))
+                }
+            };
+            //a double the balance does not cover would be turned down, the hand is played as if it could not double
+            let action = match action {
+                HandAction::DoubleDown if !can_double => basic_strategy(player_hand.best_total(), player_hand.is_soft(), dealer_up_card, false),
+                action => action,
+            };
+            player_actions.send(PlayerAction::new(action.into()));
+        }
+        GameRoundState::RoundEnd => {
+            player_actions.send(PlayerAction::new(ActionKind::KeepPlaying));
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+///update_autoplay_text shows how autoplay is set up and how far along it is
+pub fn update_autoplay_text(
+    autoplay: Res<Autoplay>,
+    companion_scripts: Res<CompanionScripts>,
+    mut text_query: Query<(&TextComponents, &mut Text)>,
+) {
+    let summary = autoplay.summary(&companion_scripts);
+    for (text_component, mut text) in text_query.iter_mut() {
+        if matches!(text_component, TextComponents::Autoplay) && text.sections[0].value != summary {
+            text.sections[0].value = summary.clone();
+        }
+    }
+}
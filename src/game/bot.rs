@@ -0,0 +1,354 @@
+///bot module lets an external program play the player's seat. the program is started as a child process and speaks
+/// line-delimited json over its stdin and stdout: it is sent the table and the legal actions, and answers each request
+/// with the id it was sent. the same protocol drives the headless simulator and the game itself.
+
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use super::components::{Card, PlayerHand};
+use super::constants::{BOT_ACTION_DELAY, BOT_DEFAULT_BET};
+use super::errors::GameError;
+use super::events::RoundOutcome;
+use super::hand_history::HandAction;
+use super::protocol::{read_message, write_message};
+use super::rules::Rules;
+use super::strategy::basic_strategy;
+use super::traits::Scorable;
+
+///BOT_PROTOCOL_VERSION is sent in the hello, and bumped whenever a message changes in a way older bots cannot read
+pub const BOT_PROTOCOL_VERSION: u32 = 2;
+
+///struct BotEnvelope is one line of the protocol: a message and the id of the request it belongs to. requests carry an id
+/// the answer has to repeat, notifications (hello, result, error) carry the id of the request they are about, if any
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BotEnvelope<T> {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<u64>,
+    #[serde(flatten)]
+    pub message: T,
+}
+
+///enum BotRequest lists the messages sent to a bot, only Bet and Action expect an answer
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BotRequest {
+    ///first message sent to the bot, along with the rules of the table and how long it has to answer
+    Hello {
+        version: u32,
+        rules: Rules,
+        timeout_ms: u64,
+    },
+    ///asks how much to bet on the next round
+    Bet {
+        round: u64,
+        bankroll: i32,
+        min_bet: i32,
+        max_bet: i32,
+    },
+    ///asks what to do with the hand, the answer has to be one of legal_actions
+    Action {
+        round: u64,
+        hand: BotHand,
+        dealer_up_card: String,
+        ///value of the dealer's face up card, aces count as 11
+        dealer_up_value: u8,
+        bankroll: i32,
+        legal_actions: Vec<HandAction>,
+    },
+    ///how the round went, sent once it is settled
+    Result {
+        round: u64,
+        outcome: RoundOutcome,
+        wager: i32,
+        ///amount handed back to the bot, the wager included
+        payout: i32,
+        bankroll: i32,
+    },
+    ///the answer to a request was late, unreadable or not allowed, and the default was played instead
+    Error { message: String },
+}
+
+///struct BotHand is the bot's hand as it is sent in an action request, cards are written using Card::code
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BotHand {
+    pub cards: Vec<String>,
+    pub total: u8,
+    pub soft: bool,
+    pub bet: i32,
+}
+
+///enum BotReply lists the answers a bot sends back
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BotReply {
+    Bet { amount: i32 },
+    Action { action: HandAction },
+}
+
+///enum BotError lists why a request did not get a usable answer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BotError {
+    Timeout,
+    ///the bot closed its output or could not be written to
+    Exited,
+    InvalidReply(String),
+}
+
+impl fmt::Display for BotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BotError::Timeout => write!(f, "the bot did not answer in time"),
+            BotError::Exited => write!(f, "the bot exited"),
+            BotError::InvalidReply(reason) => write!(f, "the bot's answer could not be read: {reason}"),
+        }
+    }
+}
+
+///legal_actions returns what the hand can do: hit and stand, and double down on the first two cards when the rules
+/// allow it and the bankroll covers the bet again
+pub fn legal_actions(player_hand: &PlayerHand, bankroll: i32, rules: &Rules) -> Vec<HandAction> {
+    let mut actions = vec![HandAction::Hit, HandAction::Stand];
+    if rules.can_double(player_hand) && bankroll >= player_hand.bet as i32 {
+        actions.push(HandAction::DoubleDown);
+    }
+    actions
+}
+
+///action_request builds the request sent when it is the bot's turn to play the hand
+pub fn action_request(round: u64, player_hand: &PlayerHand, dealer_up_card: &Card, bankroll: i32, rules: &Rules) -> BotRequest {
+    BotRequest::Action {
+        round,
+        hand: BotHand {
+            cards: player_hand.cards.iter().map(Card::code).collect(),
+            total: player_hand.best_total(),
+            soft: player_hand.is_soft(),
+            bet: player_hand.bet as i32,
+        },
+        dealer_up_card: dealer_up_card.code(),
+        dealer_up_value: dealer_up_card.value.1,
+        bankroll,
+        legal_actions: legal_actions(player_hand, bankroll, rules),
+    }
+}
+
+///checked_bet returns the bet to place from the bot's answer, or why it was turned down
+pub fn checked_bet(reply: Result<BotReply, BotError>, bankroll: i32) -> Result<i32, String> {
+    match reply {
+        Ok(BotReply::Bet { amount }) if amount > 0 && amount <= bankroll => Ok(amount),
+        Ok(BotReply::Bet { amount }) => Err(format!("a bet of {amount} is not between 1 and {bankroll}")),
+        Ok(reply) => Err(format!("expected a bet, got {reply:?}")),
+        Err(error) => Err(error.to_string()),
+    }
+}
+
+///checked_action returns the action to play from the bot's answer, or why it was turned down
+pub fn checked_action(reply: Result<BotReply, BotError>, legal_actions: &[HandAction]) -> Result<HandAction, String> {
+    match reply {
+        Ok(BotReply::Action { action }) if legal_actions.contains(&action) => Ok(action),
+        Ok(BotReply::Action { action }) => Err(format!("{} is not allowed on this hand", action.label())),
+        Ok(reply) => Err(format!("expected an action, got {reply:?}")),
+        Err(error) => Err(error.to_string()),
+    }
+}
+
+///default_bet is what is bet when the bot's bet is missing or turned down
+pub fn default_bet(bankroll: i32) -> i32 {
+    BOT_DEFAULT_BET.min(bankroll)
+}
+
+///struct BotProcess is a running bot, its answers being read on a background thread so a request can time out
+pub struct BotProcess {
+    child: Child,
+    stdin: ChildStdin,
+    lines: Mutex<Receiver<Option<String>>>,
+    timeout: Duration,
+    next_id: u64,
+    ///id and deadline of the request waiting for an answer
+    pending: Option<(u64, Instant)>,
+}
+
+///split_command_line splits a bot command line into the program and its arguments the way a shell would: words are
+/// separated by spaces, and quotes or a backslash keep spaces inside a word
+pub fn split_command_line(command_line: &str) -> io::Result<Vec<String>> {
+    shlex::split(command_line)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "the bot command has an unclosed quote"))
+}
+
+impl BotProcess {
+    ///spawn starts the bot from a command line (the program followed by its arguments) and sends it the hello. the command
+    /// line is split the way a shell would, so a path or an argument with spaces can be quoted
+    pub fn spawn(command_line: &str, rules: &Rules, timeout: Duration) -> io::Result<BotProcess> {
+        let words = split_command_line(command_line)?;
+        let (program, args) = words.split_first()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "the bot command is empty"))?;
+        let mut child = Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn()?;
+        let stdin = child.stdin.take().ok_or_else(|| io::Error::new(io::ErrorKind::BrokenPipe, "no stdin for the bot"))?;
+        let mut stdout = BufReader::new(child.stdout.take().ok_or_else(|| io::Error::new(io::ErrorKind::BrokenPipe, "no stdout for the bot"))?);
+
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || loop {
+            let mut line = String::new();
+            let line = match stdout.read_line(&mut line) {
+                Ok(0) | Err(_) => None,
+                Ok(_) => Some(line),
+            };
+            let closed = line.is_none();
+            if sender.send(line).is_err() || closed {
+                break;
+            }
+        });
+
+        let mut bot = BotProcess { child, stdin, lines: Mutex::new(receiver), timeout, next_id: 1, pending: None };
+        let hello = BotRequest::Hello { version: BOT_PROTOCOL_VERSION, rules: rules.clone(), timeout_ms: timeout.as_millis() as u64 };
+        bot.notify(&hello, None).map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "the bot exited before the hello"))?;
+        Ok(bot)
+    }
+
+    ///is_waiting returns whether a request is still waiting on its answer
+    pub fn is_waiting(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    ///last_request_id is the id of the last request sent, which is also the id errors about it are sent with
+    pub fn last_request_id(&self) -> u64 {
+        self.next_id - 1
+    }
+
+    ///request sends a request, the answer is then picked up by try_reply or wait_reply
+    pub fn request(&mut self, request: &BotRequest) -> Result<(), BotError> {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.notify(request, Some(id))?;
+        self.pending = Some((id, Instant::now() + self.timeout));
+        Ok(())
+    }
+
+    ///notify sends a message that does not expect an answer, id being the request it is about
+    pub fn notify(&mut self, message: &BotRequest, id: Option<u64>) -> Result<(), BotError> {
+        write_message(&mut self.stdin, &BotEnvelope { id, message: message.clone() }).map_err(|_| BotError::Exited)
+    }
+
+    ///ask sends a request and waits for its answer, at most the bot's timeout
+    pub fn ask(&mut self, request: &BotRequest) -> Result<BotReply, BotError> {
+        self.request(request)?;
+        self.wait_reply()
+    }
+
+    ///try_reply returns the answer to the pending request if it arrived (or timed out) without waiting for it
+    pub fn try_reply(&mut self) -> Option<Result<BotReply, BotError>> {
+        self.next_reply(false)
+    }
+
+    ///wait_reply waits for the answer to the pending request, at most until its deadline
+    pub fn wait_reply(&mut self) -> Result<BotReply, BotError> {
+        self.next_reply(true).unwrap_or(Err(BotError::Timeout))
+    }
+
+    fn next_reply(&mut self, wait: bool) -> Option<Result<BotReply, BotError>> {
+        let (id, deadline) = self.pending?;
+        let result = loop {
+            let received = {
+                let Ok(lines) = self.lines.lock() else {
+                    break Err(BotError::Exited);
+                };
+                let remaining = if wait { deadline.saturating_duration_since(Instant::now()) } else { Duration::ZERO };
+                lines.recv_timeout(remaining)
+            };
+            match received {
+                Ok(Some(line)) => match serde_json::from_str::<BotEnvelope<BotReply>>(&line) {
+                    Ok(envelope) if envelope.id == Some(id) => break Ok(envelope.message),
+                    //answers to requests that already timed out are dropped
+                    Ok(envelope) if envelope.id.is_some_and(|reply_id| reply_id < id) => continue,
+                    Ok(envelope) => break Err(BotError::InvalidReply(format!("expected id {id}, got {:?}", envelope.id))),
+                    Err(error) => break Err(BotError::InvalidReply(error.to_string())),
+                },
+                Ok(None) | Err(RecvTimeoutError::Disconnected) => break Err(BotError::Exited),
+                Err(RecvTimeoutError::Timeout) if Instant::now() >= deadline => break Err(BotError::Timeout),
+                Err(RecvTimeoutError::Timeout) => return None,
+            }
+        };
+        self.pending = None;
+        Some(result)
+    }
+}
+
+impl Drop for BotProcess {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+///run_reference_bot is the reference bot: it bets BOT_DEFAULT_BET and plays basic strategy, answering every request
+/// until its input is closed. it is what the blackjack-bot binary runs, and a starting point for bots in other languages
+pub fn run_reference_bot(mut input: impl BufRead, mut output: impl Write) -> io::Result<()> {
+    while let Some(envelope) = read_message::<BotEnvelope<BotRequest>>(&mut input)? {
+        let reply = match envelope.message {
+            BotRequest::Bet { bankroll, .. } => BotReply::Bet { amount: default_bet(bankroll) },
+            BotRequest::Action { hand, dealer_up_value, legal_actions, .. } => {
+                let can_double = legal_actions.contains(&HandAction::DoubleDown);
+                BotReply::Action { action: basic_strategy(hand.total, hand.soft, dealer_up_value, can_double) }
+            }
+            BotRequest::Hello { .. } | BotRequest::Result { .. } | BotRequest::Error { .. } => continue,
+        };
+        write_message(&mut output, &BotEnvelope { id: envelope.id, message: reply })?;
+    }
+    Ok(())
+}
+
+///struct / resource BotPlayer is the bot playing the player's seat in the game, when one was given with `--bot`
+#[derive(Resource)]
+pub struct BotPlayer {
+    pub process: Option<BotProcess>,
+    ///what the pending request asked for, so its answer is checked against the right thing
+    pub awaiting: Option<BotRequest>,
+    pub round: u64,
+    ///paces the requests so every decision can be followed on screen
+    pub timer: Timer,
+    ///the command given with `--bot` and how long the bot has to answer, started by start_bot_player
+    pub command: Option<(String, Duration)>,
+}
+
+impl Default for BotPlayer {
+    fn default() -> Self {
+        Self {
+            process: None,
+            awaiting: None,
+            round: 0,
+            timer: Timer::from_seconds(BOT_ACTION_DELAY, TimerMode::Once),
+            command: None,
+        }
+    }
+}
+
+impl BotPlayer {
+    ///new builds the bot player for the bot given with `--bot "<command>"`, which has `timeout` to answer each request. the
+    /// bot is only started by start
+    pub fn new(command: &str, timeout: Duration) -> BotPlayer {
+        BotPlayer { command: Some((command.to_string(), timeout)), ..default() }
+    }
+
+    ///start starts the bot given with `--bot`, if any. a bot that cannot be started is returned as an error and the seats
+    /// are the player's
+    pub fn start(&mut self, rules: &Rules) -> Result<(), GameError> {
+        let Some((command, timeout)) = &self.command else {
+            return Ok(());
+        };
+        let process = BotProcess::spawn(command, rules, *timeout)
+            .map_err(|error| GameError::BotNotStarted(format!("`{command}`: {error}")))?;
+        info!("The bot `{command}` plays the player's seats");
+        self.process = Some(process);
+        Ok(())
+    }
+}
@@ -0,0 +1,140 @@
+///bot_systems module has the bot given with `--bot` play the player's seats in the game, so its decisions can be watched.
+/// the bot's bets go on the first seat of the player's, and its actions, dealing included, are sent as PlayerAction events
+/// like the player's.
+
+use bevy::prelude::*;
+use super::bot::{action_request, checked_action, checked_bet, default_bet, BotError, BotPlayer, BotProcess, BotRequest};
+use super::components::{Companion, DealerHand, PlayerHands, Seat};
+use super::constants::GameRoundState;
+use super::dealer_systems::dealer_up_card;
+use super::errors::GameError;
+use super::events::{ActionKind, HandSettled, PlayerAction};
+use super::hand_history::HandAction;
+use super::resources::{ActiveSeat, BalanceValue, BetValue};
+use super::player_systems::place_bet_on_first_player_seat;
+use super::rules::Rules;
+
+///start_bot_player starts the bot given with `--bot` once the app is up. a bot that cannot be started is a game error, and
+/// the seats are the player's
+pub fn start_bot_player(rules: Res<Rules>, mut bot_player: ResMut<BotPlayer>) -> Result<(), GameError> {
+    bot_player.start(&rules)
+}
+
+///drive_bot_player asks the bot for its bet while betting and for its action on each of the player's turns, one request each
+/// time the bot timer finishes, and tells it how every seat of the player's was settled
+pub fn drive_bot_player(
+    time: Res<Time>,
+    mut bot_player: ResMut<BotPlayer>,
+    game_state: Res<State<GameRoundState>>,
+    rules: Res<Rules>,
+    active_seat: Res<ActiveSeat>,
+    mut balance_value: ResMut<BalanceValue>,
+    mut bet_value: ResMut<BetValue>,
+    dealer_query: Query<&DealerHand>,
+    mut player_query: Query<(&Seat, &mut PlayerHands, Has<Companion>)>,
+    mut round_settled: EventReader<HandSettled>,
+    mut player_actions: EventWriter<PlayerAction>,
+) -> Result<(), GameError> {
+
+    let bot_player = &mut *bot_player;
+    let Some(bot) = &mut bot_player.process else {
+        round_settled.clear();
+        return Ok(());
+    };
+
+    for settled in round_settled.read().filter(|settled| !settled.companion) {
+        let _ = bot.notify(&BotRequest::Result {
+            round: bot_player.round,
+            outcome: settled.outcome,
+            wager: settled.wager,
+            payout: settled.payout(),
+            bankroll: balance_value.value,
+        }, None);
+    }
+
+    let mut table_action = None;
+    if bot.is_waiting() {
+        let Some(reply) = bot.try_reply() else {
+            return Ok(());
+        };
+        if reply == Err(BotError::Exited) {
+            warn!("The bot exited, the seats are the player's again");
+            bot_player.process = None;
+            return Ok(());
+        }
+        let timed_out = reply == Err(BotError::Timeout);
+        match bot_player.awaiting.take() {
+            Some(BotRequest::Bet { bankroll, .. }) => {
+                let amount = checked_bet(reply, bankroll).unwrap_or_else(|reason| {
+                    turn_down(bot, reason, timed_out);
+                    default_bet(bankroll)
+                });
+                if place_bet_on_first_player_seat(&mut player_query, amount, &mut balance_value, &mut bet_value)? {
+                    table_action = Some(ActionKind::Deal);
+                }
+            }
+            Some(BotRequest::Action { legal_actions, .. }) => {
+                let action = checked_action(reply, &legal_actions).unwrap_or_else(|reason| {
+                    turn_down(bot, reason, timed_out);
+                    HandAction::Stand
+                });
+                info!("Bot: {}", action.label());
+                player_actions.send(PlayerAction::new(action.into()));
+            }
+            _ => {}
+        }
+        bot_player.timer.reset();
+    } else {
+        if !bot_player.timer.tick(time.delta()).finished() {
+            return Ok(());
+        }
+        bot_player.timer.reset();
+
+        let player_turn = player_query.iter()
+            .find(|(seat, player_hands, is_companion)| seat.0 == active_seat.0 && !is_companion && player_hands.0.first().is_some_and(|hand| !hand.cards.is_empty()));
+        let request = match game_state.get() {
+            GameRoundState::Betting if bet_value.value > 0 => {
+                table_action = Some(ActionKind::Deal);
+                None
+            }
+            GameRoundState::Betting if balance_value.value > 0 => {
+                bot_player.round += 1;
+                Some(BotRequest::Bet { round: bot_player.round, bankroll: balance_value.value, min_bet: 1, max_bet: balance_value.value })
+            }
+            GameRoundState::PlayerHand => {
+                match (player_turn, dealer_up_card(&dealer_query)) {
+                    (Some((seat, player_hands, _)), Some(dealer_up_card)) => {
+                        let player_hand = player_hands.0.first().ok_or(GameError::MissingHand(seat.0))?;
+                        Some(action_request(bot_player.round, player_hand, dealer_up_card, balance_value.value, &rules))
+                    }
+                    _ => None,
+                }
+            }
+            GameRoundState::RoundEnd => {
+                table_action = Some(ActionKind::KeepPlaying);
+                None
+            }
+            _ => None,
+        };
+        if let Some(request) = request {
+            if bot.request(&request).is_err() {
+                warn!("The bot exited, the seats are the player's again");
+                bot_player.process = None;
+                return Ok(());
+            }
+            bot_player.awaiting = Some(request);
+        }
+    }
+
+    if let Some(table_action) = table_action {
+        player_actions.send(PlayerAction::new(table_action));
+    }
+    Ok(())
+}
+
+///turn_down tells the bot its answer was not played, the default being played instead
+fn turn_down(bot: &mut BotProcess, reason: String, timed_out: bool) {
+    warn!("Bot answer turned down{}: {reason}", if timed_out { " (timed out)" } else { "" });
+    let id = bot.last_request_id();
+    let _ = bot.notify(&BotRequest::Error { message: reason }, Some(id));
+}
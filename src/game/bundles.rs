@@ -1,11 +1,13 @@
 use bevy::prelude::*;
-use crate::game::components::{PlayerHands, PlayerName, PlayerBalance, DealerHand, Decks};
+use crate::game::components::{PlayerHands, PlayerName, PlayerBalance, DealerHand, Decks, Seat, SeatStatus};
 
 #[derive(Bundle)]
 pub struct PlayerBundle{
     pub player_name: PlayerName,
     pub player_balance: PlayerBalance,
-    pub player_hands: PlayerHands
+    pub player_hands: PlayerHands,
+    pub seat: Seat,
+    pub seat_status: SeatStatus,
 }
 
 #[derive(Bundle)]
@@ -0,0 +1,209 @@
+///cli module reads the command line of the game, so the game can be launched into a given table (a seed, rules, a
+/// balance, a scenario...) without touching the code. every argument is checked before anything starts, and an argument
+/// that cannot be used ends the game with the usage.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use super::constants::{AppState, BOT_REPLY_TIMEOUT_MS};
+use super::logging::LogSettings;
+use super::plugins::BlackjackOnlinePlugin;
+use super::rules::Rules;
+use super::scenario::Scenario;
+use super::storage::load_json;
+
+///USAGE is printed with `--help`, and after an argument that cannot be used
+pub static USAGE: &str = "usage: Blackjack-in-Rust [options]
+
+table:
+  --seed <number>            shuffle the shoe with this seed, the same seed deals the same cards
+  --rules <preset|file>      h17 (the default, the dealer hits soft 17), s17, no-double, or a json rules file
+  --balance <amount>         sit down with this balance instead of 1000
+  --scenario <file>          deal the cards of a scenario file in order instead of shuffling
+  --skip-title               go straight to the table
+  --profile <name>           keep statistics and hand history under this profile instead of the default one
+
+window:
+  --windowed                 play in a window (the default)
+  --fullscreen               play fullscreen
+  --headless                 run without a window, straight at the table (use it with --bot)
+
+players:
+  --bot <command>            have an external program play your seats
+  --bot-timeout <ms>         how long the bot has to answer, 2000 by default
+  --scripts <directory>      load the companion scripts from this directory instead of the data directory's scripts
+  --connect <address>        sit at a network table
+  --spectate <address>       watch a network table
+  --name <name>              the name to sit at a network table with
+
+logs:
+  --log-level <levels>       error, warn, info (the default), debug or trace, and module=level pairs, e.g. warn,bot=debug
+  --no-log-file              only log to the terminal
+
+developers:
+  --dev                      add the developer console (backtick) and the inspector (F3), always on in a debug build
+
+  -h, --help                 print this help";
+
+///enum WindowMode is how the game is shown
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum WindowMode {
+    #[default]
+    Windowed,
+    Fullscreen,
+    ///no window at all
+    Headless,
+}
+
+///struct GameArgs is what the command line asked for, each field left empty keeps the game's default
+#[derive(Default)]
+pub struct GameArgs {
+    pub help: bool,
+    pub seed: Option<u64>,
+    pub rules: Option<Rules>,
+    pub balance: Option<i32>,
+    pub scenario: Option<Scenario>,
+    pub skip_title: bool,
+    ///the profile whose statistics and hand history are used
+    pub profile: Option<String>,
+    pub window_mode: WindowMode,
+    ///the bot's command and how long it has to answer
+    pub bot: Option<(String, Duration)>,
+    ///the directory the companion scripts are loaded from
+    pub scripts: Option<PathBuf>,
+    ///the network table to sit at or watch
+    pub online: Option<BlackjackOnlinePlugin>,
+    pub log: LogSettings,
+    ///the developer console and the inspector were asked for
+    pub dev: bool,
+}
+
+impl GameArgs {
+    ///parse reads the arguments (without the program name), returning what cannot be used
+    pub fn parse(mut args: impl Iterator<Item = String>) -> Result<GameArgs, String> {
+        let mut game_args = GameArgs::default();
+        let mut window_mode = None;
+        let mut bot_timeout = None;
+        let mut name = None;
+        while let Some(arg) = args.next() {
+            //flags first, everything else takes a value
+            let window = match arg.as_str() {
+                "--help" | "-h" => {
+                    game_args.help = true;
+                    continue;
+                }
+                "--skip-title" => {
+                    game_args.skip_title = true;
+                    continue;
+                }
+                "--no-log-file" => {
+                    game_args.log.log_file = false;
+                    continue;
+                }
+                "--dev" => {
+                    game_args.dev = true;
+                    continue;
+                }
+                "--windowed" => Some(WindowMode::Windowed),
+                "--fullscreen" => Some(WindowMode::Fullscreen),
+                "--headless" => Some(WindowMode::Headless),
+                _ => None,
+            };
+            if let Some(window) = window {
+                if window_mode.is_some_and(|mode| mode != window) {
+                    return Err(String::from("only one of --windowed, --fullscreen and --headless can be given"));
+                }
+                window_mode = Some(window);
+                continue;
+            }
+
+            let value = match args.next() {
+                Some(value) if !value.starts_with("--") => value,
+                _ => return Err(format!("{arg} needs a value")),
+            };
+            match arg.as_str() {
+                "--seed" => game_args.seed = Some(value.parse().map_err(|_| String::from("--seed must be a number"))?),
+                "--rules" => game_args.rules = Some(parse_rules(&value)?),
+                "--balance" => game_args.balance = Some(value.parse().ok().filter(|balance| *balance > 0)
+                    .ok_or_else(|| String::from("--balance must be a positive number"))?),
+                "--scenario" => game_args.scenario = Some(Scenario::load(Path::new(&value))?),
+                "--profile" => game_args.profile = Some(parse_profile(&value)?),
+                "--bot" => game_args.bot = Some((value, Duration::from_millis(BOT_REPLY_TIMEOUT_MS))),
+                "--scripts" if !Path::new(&value).is_dir() => return Err(format!("--scripts {value} is not a directory")),
+                "--scripts" => game_args.scripts = Some(PathBuf::from(value)),
+                "--bot-timeout" => bot_timeout = Some(value.parse().map_err(|_| String::from("--bot-timeout must be a number"))?),
+                "--connect" | "--spectate" => {
+                    if game_args.online.is_some() {
+                        return Err(String::from("only one of --connect and --spectate can be given"));
+                    }
+                    game_args.online = Some(match arg.as_str() {
+                        "--spectate" => BlackjackOnlinePlugin::spectate(value),
+                        _ => BlackjackOnlinePlugin::connect(value),
+                    });
+                }
+                "--name" => name = Some(value),
+                "--log-level" => game_args.log.set_levels(&value)?,
+                _ => return Err(format!("unknown argument {arg}")),
+            }
+        }
+
+        game_args.window_mode = window_mode.unwrap_or_default();
+        if let (Some((_, timeout)), Some(milliseconds)) = (&mut game_args.bot, bot_timeout) {
+            *timeout = Duration::from_millis(milliseconds);
+        } else if bot_timeout.is_some() {
+            return Err(String::from("--bot-timeout needs --bot"));
+        }
+        match game_args.online.take() {
+            Some(online) => {
+                game_args.online = Some(match name {
+                    Some(name) => online.with_name(name),
+                    None => online,
+                });
+                if game_args.skip_title || game_args.scenario.is_some() || game_args.bot.is_some() || game_args.window_mode == WindowMode::Headless {
+                    return Err(String::from("--skip-title, --scenario, --bot and --headless cannot be used at a network table"));
+                }
+            }
+            None if name.is_some() => return Err(String::from("--name needs --connect or --spectate")),
+            None => {}
+        }
+        Ok(game_args)
+    }
+
+    ///dev_tools tells whether the developer console and the inspector are added, which a release build only does with --dev
+    pub fn dev_tools(&self) -> bool {
+        self.dev || cfg!(debug_assertions)
+    }
+
+    ///initial_app_state returns the screen the game starts on: the network table, the table when the title is skipped
+    /// (always the case without a window), or the title screen
+    pub fn initial_app_state(&self) -> AppState {
+        if self.online.is_some() {
+            AppState::Online
+        } else if self.skip_title || self.window_mode == WindowMode::Headless {
+            AppState::InGame
+        } else {
+            AppState::Start
+        }
+    }
+}
+
+//parse_profile reads the value of --profile, which names a directory under the data directory
+fn parse_profile(value: &str) -> Result<String, String> {
+    let usable = !value.is_empty() && value != "." && value != ".."
+        && value.chars().all(|character| character.is_alphanumeric() || "-_. ".contains(character));
+    if !usable {
+        return Err(format!("--profile {value} can only use letters, digits, spaces, '-', '_' and '.'"));
+    }
+    Ok(value.to_string())
+}
+
+//parse_rules reads the value of --rules, a preset or the path of a json rules file
+fn parse_rules(value: &str) -> Result<Rules, String> {
+    if let Some(rules) = Rules::preset(value) {
+        return Ok(rules);
+    }
+    match load_json::<Rules>(Path::new(value)) {
+        Ok(Some(rules)) => Ok(rules),
+        Ok(None) => Err(format!("--rules {value} is neither a preset ({}) nor a rules file", Rules::PRESETS.join(", "))),
+        Err(error) => Err(format!("{value}: {error}")),
+    }
+}
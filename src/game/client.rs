@@ -0,0 +1,67 @@
+///client module is the client side of the table protocol: it connects to a server, takes a seat (or just watches)
+/// and exchanges messages. it is used by the online mode of the game and by the loopback tests.
+
+use std::io::{self, BufReader};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::Duration;
+use super::protocol::{read_message, write_message, ClientMessage, ServerMessage, TableSnapshot, PROTOCOL_VERSION};
+
+static CONNECT_TIMEOUT: Duration = Duration::from_secs(3);
+
+///struct TableClient is a connection to a table server, either seated or spectating
+pub struct TableClient {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+    ///the seat played, None for a spectator
+    pub seat: Option<usize>,
+    ///sent back in the Hello of a later connection to take the same seat back
+    pub token: Option<String>,
+}
+
+impl TableClient {
+    ///connect opens a connection and sits down, or takes back the seat held for token. the snapshot returned is
+    /// the table as it was when the client sat down
+    pub fn connect(address: &str, name: &str, token: Option<String>) -> io::Result<(TableClient, TableSnapshot)> {
+        Self::open(address, &ClientMessage::Hello { version: PROTOCOL_VERSION, name: name.to_string(), token })
+    }
+
+    ///spectate opens a connection that watches the table without sitting down, the snapshot returned is the whole table
+    /// as it was when the spectator arrived
+    pub fn spectate(address: &str) -> io::Result<(TableClient, TableSnapshot)> {
+        Self::open(address, &ClientMessage::Spectate { version: PROTOCOL_VERSION })
+    }
+
+    fn open(address: &str, first_message: &ClientMessage) -> io::Result<(TableClient, TableSnapshot)> {
+        let socket_address = address.to_socket_addrs()?.next()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, format!("{address} is not a valid address")))?;
+        let stream = TcpStream::connect_timeout(&socket_address, CONNECT_TIMEOUT)?;
+        let mut writer = stream.try_clone()?;
+        let mut reader = BufReader::new(stream);
+
+        write_message(&mut writer, first_message)?;
+        match read_message(&mut reader)? {
+            Some(ServerMessage::Welcome { seat, token, snapshot, .. }) => Ok((TableClient { reader, writer, seat: Some(seat), token: Some(token) }, snapshot)),
+            Some(ServerMessage::Watching { snapshot, .. }) => Ok((TableClient { reader, writer, seat: None, token: None }, snapshot)),
+            Some(ServerMessage::Error { message }) => Err(io::Error::new(io::ErrorKind::PermissionDenied, message)),
+            Some(message) => Err(io::Error::new(io::ErrorKind::InvalidData, format!("expected a welcome, got {message:?}"))),
+            None => Err(io::Error::new(io::ErrorKind::UnexpectedEof, "the server closed the connection")),
+        }
+    }
+
+    ///send sends a message to the server
+    pub fn send(&mut self, message: &ClientMessage) -> io::Result<()> {
+        write_message(&mut self.writer, message)
+    }
+
+    ///recv waits for the next message from the server, timeout being how long to wait at most (None waits forever)
+    pub fn recv(&mut self, timeout: Option<Duration>) -> io::Result<ServerMessage> {
+        self.reader.get_ref().set_read_timeout(timeout)?;
+        read_message(&mut self.reader)?
+            .ok_or_else(|| io::Error::new(io::ErrorKind::UnexpectedEof, "the server closed the connection"))
+    }
+
+    ///split hands the connection over as a reader and a writer, so they can be used from different threads
+    pub fn split(self) -> (BufReader<TcpStream>, TcpStream) {
+        (self.reader, self.writer)
+    }
+}
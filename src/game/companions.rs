@@ -0,0 +1,248 @@
+///companions module holds the computer controlled players that can fill the empty seats of the table: how each personality
+/// bets and plays its hand, and the systems that play their seats one action at a time so the player can follow along.
+/// a companion can also play one of the scripts of the scripts directory.
+
+use bevy::prelude::*;
+use super::components::{Companion, DealerHand, Deck, PlayerHand, PlayerHands, Seat, SeatStatus};
+use super::constants::{GameRoundState, COMPANION_BASE_BET};
+use super::dealer_systems::{dealer_up_value, settle_hand};
+use super::errors::GameError;
+use super::events::{ActionTaken, CardDealt, HandSettled, RoundOutcome, ShoeShuffled};
+use super::hand_history::HandAction;
+use super::player_systems::{deal_card_to_seat, finish_seat_turn, seats_in_play};
+use super::round_state::RoundTransition;
+use super::resources::{ActiveSeat, BalanceValue, BetValue, CompanionTimer, LocalSpectator};
+use super::rules::Rules;
+use super::scripting::{BetInputs, CompanionScripts, HandInputs};
+use super::strategy::basic_strategy;
+use super::traits::Scorable;
+
+///enum Personality is the way a companion plays
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Personality {
+    ///plays perfect basic strategy
+    BasicStrategy,
+    ///stands on 12 or more so it never busts
+    NeverBust,
+    ///plays like the dealer, hitting below 17
+    MimicDealer,
+    ///hits until 18 and doubles on any small starting hand
+    Reckless,
+    ///plays basic strategy and raises its bet as the count goes up
+    CardCounter,
+}
+
+impl Personality {
+    pub const ALL: [Personality; 5] = [
+        Personality::BasicStrategy,
+        Personality::NeverBust,
+        Personality::MimicDealer,
+        Personality::Reckless,
+        Personality::CardCounter,
+    ];
+
+    ///label returns the short name shown on the seat marker
+    pub fn label(&self) -> &'static str {
+        match self {
+            Personality::BasicStrategy => "Basic",
+            Personality::NeverBust => "Never Bust",
+            Personality::MimicDealer => "Mimic",
+            Personality::Reckless => "Reckless",
+            Personality::CardCounter => "Counter",
+        }
+    }
+
+    ///next returns the personality after this one, None after the last one (the seat goes back to the player)
+    pub fn next(&self) -> Option<Personality> {
+        let index = Personality::ALL.iter().position(|personality| personality == self)?;
+        Personality::ALL.get(index + 1).copied()
+    }
+
+    ///choose_action decides what to do with a hand, dealer_up_card being the value of the dealer's face up card (aces are 11)
+    pub fn choose_action(&self, player_hand: &PlayerHand, dealer_up_card: u8, rules: &Rules) -> HandAction {
+        let total = player_hand.best_total();
+        let soft = player_hand.is_soft();
+        let can_double = rules.can_double(player_hand);
+        match self {
+            Personality::BasicStrategy | Personality::CardCounter => basic_strategy(total, soft, dealer_up_card, can_double),
+            Personality::NeverBust if total >= 12 => HandAction::Stand,
+            Personality::NeverBust => HandAction::Hit,
+            Personality::MimicDealer if total < 17 || (total == 17 && soft && rules.dealer_hits_soft_17) => HandAction::Hit,
+            Personality::MimicDealer => HandAction::Stand,
+            Personality::Reckless if can_double && !soft && (9..=12).contains(&total) => HandAction::DoubleDown,
+            Personality::Reckless if total < 18 => HandAction::Hit,
+            Personality::Reckless => HandAction::Stand,
+        }
+    }
+
+    ///bet returns how much the companion wants to bet on the next round. the card counter bets one unit per true count
+    /// (the running count per deck left), between one and eight units.
+    pub fn bet(&self, running_count: i32, cards_remaining: usize) -> i32 {
+        match self {
+            Personality::Reckless => COMPANION_BASE_BET * 5 / 2,
+            Personality::CardCounter => {
+                let decks_remaining = (cards_remaining as f32 / 52.0).max(0.25);
+                let true_count = (running_count as f32 / decks_remaining).floor() as i32;
+                COMPANION_BASE_BET * true_count.clamp(1, 8)
+            }
+            _ => COMPANION_BASE_BET,
+        }
+    }
+}
+
+///companion_label returns the name shown on a companion's seat marker, the script's name for a scripted companion
+pub fn companion_label(companion: &Companion, companion_scripts: &CompanionScripts) -> String {
+    companion.script
+        .and_then(|script| companion_scripts.scripts.get(script))
+        .map_or_else(|| companion.personality.label().to_string(), |script| script.name.clone())
+}
+
+///companion_selection_system lets the player sit a companion at the active seat while betting: C cycles through the
+/// personalities, then the scripts, and then back to an empty seat. at least one seat is always left to the player.
+pub fn companion_selection_system(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    game_state: Res<State<GameRoundState>>,
+    active_seat: Res<ActiveSeat>,
+    spectator: Res<LocalSpectator>,
+    companion_scripts: Res<CompanionScripts>,
+    mut balance_value: ResMut<BalanceValue>,
+    mut bet_value: ResMut<BetValue>,
+    mut player_query: Query<(Entity, &Seat, &mut PlayerHands, Option<&mut Companion>)>,
+) -> Result<(), GameError> {
+
+    if spectator.watching || !keyboard_input.just_pressed(KeyCode::KeyC) || !matches!(game_state.get(), GameRoundState::RoundStart | GameRoundState::Betting) {
+        return Ok(());
+    }
+    let player_seats = player_query.iter().filter(|(_, _, _, companion)| companion.is_none()).count();
+    let Some((entity, seat, mut player_hands, companion)) = player_query.iter_mut().find(|(_, seat, _, _)| seat.0 == active_seat.0) else {
+        return Ok(());
+    };
+    match companion {
+        Some(mut companion) => match (companion.script, companion.personality.next()) {
+            (None, Some(personality)) => companion.personality = personality,
+            //scripted companions fall back on basic strategy
+            (None, None) if !companion_scripts.scripts.is_empty() => {
+                companion.personality = Personality::BasicStrategy;
+                companion.script = Some(0);
+            }
+            (Some(script), _) if script + 1 < companion_scripts.scripts.len() => companion.script = Some(script + 1),
+            _ => {
+                commands.entity(entity).remove::<Companion>();
+            }
+        },
+        None if player_seats > 1 => {
+            //chips the player already put on the seat are handed back
+            let player_hand = player_hands.0.first_mut().ok_or(GameError::MissingHand(seat.0))?;
+            let refund = player_hand.bet as i32;
+            balance_value.value += refund;
+            bet_value.value -= refund;
+            player_hand.bet = 0;
+            commands.entity(entity).insert(Companion::new(Personality::ALL[0]));
+        }
+        None => info!("At least one seat has to be left for the player"),
+    }
+    Ok(())
+}
+
+///place_companion_bets has every companion put its bet down right before the cards are dealt, a companion that is out
+/// of money sits the round out
+pub fn place_companion_bets(
+    deck: Res<Deck>,
+    mut companion_scripts: ResMut<CompanionScripts>,
+    mut companion_query: Query<(&Seat, &mut PlayerHands, &mut Companion)>,
+) -> Result<(), GameError> {
+
+    for (seat, mut player_hands, mut companion) in companion_query.iter_mut() {
+        let personality_bet = companion.personality.bet(deck.running_count(), deck.cards_remaining());
+        let script = companion.script.and_then(|script| companion_scripts.scripts.get(script)).filter(|script| script.bets());
+        let scripted_bet = script.map(|script| script.bet(&BetInputs {
+            bankroll: companion.bankroll,
+            running_count: deck.running_count(),
+            base_bet: COMPANION_BASE_BET,
+            progress: companion.progress.clone(),
+        }));
+        let bet = match scripted_bet {
+            Some(Ok(bet)) => bet,
+            Some(Err(error)) => {
+                companion_scripts.report(error);
+                personality_bet
+            }
+            None => personality_bet,
+        }.min(companion.bankroll);
+        player_hands.0.first_mut().ok_or(GameError::MissingHand(seat.0))?.bet = bet as u64;
+        companion.bankroll -= bet;
+    }
+    Ok(())
+}
+
+///play_companion_turns plays the hand of the active seat when a companion sits there, one action every time the companion
+/// timer finishes, sending ActionTaken
+pub fn play_companion_turns(
+    time: Res<Time>,
+    mut timer: ResMut<CompanionTimer>,
+    mut deck: ResMut<Deck>,
+    rules: Res<Rules>,
+    mut companion_scripts: ResMut<CompanionScripts>,
+    mut active_seat: ResMut<ActiveSeat>,
+    mut round_transition: RoundTransition,
+    dealer_query: Query<&DealerHand>,
+    mut player_query: Query<(&Seat, &mut PlayerHands, &mut SeatStatus, Option<&mut Companion>)>,
+    mut card_dealt: EventWriter<CardDealt>,
+    mut shoe_shuffled: EventWriter<ShoeShuffled>,
+    mut action_taken: EventWriter<ActionTaken>,
+    mut hand_settled: EventWriter<HandSettled>,
+) -> Result<(), GameError> {
+
+    let seats = seats_in_play(player_query.iter().map(|(seat, player_hands, _, _)| (seat, player_hands)));
+    let Some((seat, mut player_hands, mut seat_status, Some(mut companion))) = player_query.iter_mut().find(|(seat, _, _, _)| seat.0 == active_seat.0) else {
+        //the delay starts over whenever it is the player's turn
+        timer.0.reset();
+        return Ok(());
+    };
+    if !timer.0.tick(time.delta()).finished() {
+        return Ok(());
+    }
+    timer.0.reset();
+
+    let dealer_up_card = dealer_up_value(&dealer_query);
+    let player_hand = player_hands.0.first_mut().ok_or(GameError::MissingHand(seat.0))?;
+    let mut action = companion.personality.choose_action(player_hand, dealer_up_card, &rules);
+    if let Some(script) = companion.script {
+        let can_double = rules.can_double(player_hand) && companion.bankroll >= player_hand.bet as i32;
+        let inputs = HandInputs::from_hand(player_hand, dealer_up_card, deck.running_count(), companion.bankroll, can_double);
+        if let Some(scripted_action) = companion_scripts.choose_action(script, &inputs) {
+            action = scripted_action;
+        }
+    }
+    if action == HandAction::DoubleDown && companion.bankroll < player_hand.bet as i32 {
+        action = HandAction::Hit;
+    }
+    let card = match action {
+        HandAction::Hit => {
+            let card = deal_card_to_seat(&mut deck, &mut card_dealt, &mut shoe_shuffled, *seat, 0, player_hand);
+            if player_hand.is_bust() {
+                finish_seat_turn(&mut active_seat, &seats, &mut round_transition);
+            }
+            Some(card)
+        }
+        HandAction::Stand => {
+            finish_seat_turn(&mut active_seat, &seats, &mut round_transition);
+            None
+        }
+        HandAction::DoubleDown => {
+            companion.bankroll -= player_hand.bet as i32;
+            player_hand.bet *= 2;
+            let card = deal_card_to_seat(&mut deck, &mut card_dealt, &mut shoe_shuffled, *seat, 0, player_hand);
+            finish_seat_turn(&mut active_seat, &seats, &mut round_transition);
+            Some(card)
+        }
+    };
+    action_taken.send(ActionTaken { seat: seat.0, hand: 0, action, card, companion: true });
+    //a bust hand is settled right away, like the player's
+    if player_hand.is_bust() {
+        let dealer_hand = dealer_query.get_single().map_err(|_| GameError::NoDealer)?;
+        hand_settled.send(settle_hand(seat, RoundOutcome::Loss, player_hand, dealer_hand, &mut seat_status, Some(&mut companion)));
+    }
+    Ok(())
+}
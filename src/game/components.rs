@@ -1,9 +1,12 @@
 ///components module is used to hold all of our components / structs / enums that we utilize from other modules in the game
 
 use bevy::prelude::*;
-use rand::Rng;
+use rand::{rngs::StdRng, Rng, SeedableRng};
 
-use crate::game::traits::{Shufflable, Dealable};
+use crate::game::companions::Personality;
+use crate::game::constants::COMPANION_STARTING_BANKROLL;
+use crate::game::scripting::BetProgress;
+use crate::game::traits::{Shufflable, Dealable, Scorable};
 
 // start screen ---------------
 
@@ -15,9 +18,26 @@ pub struct Background;
 #[derive(Component)]
 pub struct Logo;
 
-///struct for press enter to play text
+///struct / component for the root UI node of the main menu, its entries are children of it
 #[derive(Component)]
-pub struct PressEnterToPlay;
+pub struct MainMenu;
+
+///enum / component for the entries of the main menu, from top to bottom
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub enum MenuEntry {
+    NewGame,
+    ///sits back down with the balance the last table was left with
+    Continue,
+    Practice,
+    Statistics,
+    Settings,
+    Rules,
+    Quit,
+}
+
+///struct for the hint text listing the other keys on the start screen
+#[derive(Component)]
+pub struct StartScreenHint;
 
 // -----------------------------
 
@@ -55,6 +75,7 @@ pub enum PlayerButtonValues {
     Home,
     Deal,
     KeepPlaying,
+    Autoplay,
 }
 
 ///enum / component used to access the cards spawned in the screen in game
@@ -64,6 +85,14 @@ pub enum InGameCardAccess {
     PlayerCard(usize),
 }
 
+///struct / component for the button above each seat that shows its bet and selects it for betting, holds the seat index
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct SeatMarker(pub usize);
+
+///struct / component for the text inside a seat marker, holds the seat index
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct SeatMarkerText(pub usize);
+
 /// enum / component used to access and uniquely identify text components in the in game UI
 #[derive(Component, Debug, Clone, Copy, PartialEq)]
 pub enum TextComponents {
@@ -72,13 +101,169 @@ pub enum TextComponents {
     Instruction,
     NotChanged,
     ResultText,
+    ///the last error of a companion's script
+    ScriptError,
+    ///how autoplay is set up
+    Autoplay,
+    ///the banner of the last game error
+    ErrorBanner,
+    ///the basic strategy play of the hand being played, while hints are on
+    Hint,
+}
+// -----------------------------
+
+// statistics screen ---------------
+
+///struct / component for the root UI node of the statistics screen, everything on the screen is a child of it
+#[derive(Component)]
+pub struct StatsScreen;
+
+///enum / component used to access the text elements on the statistics screen that change
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub enum StatsScreenText {
+    Title,
+    Summary,
+    Breakdown,
+}
+
+// -----------------------------
+
+// practice, rules and settings screens ---------------
+
+///struct / component for the root UI node of the practice (basic strategy trainer) screen
+#[derive(Component)]
+pub struct TrainerScreen;
+
+///struct / component for the text of the practice screen: the hand to play, the answer to the last one and the score
+#[derive(Component)]
+pub struct TrainerText;
+
+///struct / component for the root UI node of the rules screen
+#[derive(Component)]
+pub struct RulesScreen;
+
+///struct / component for the root UI node of the settings screen, its rows are children of it tagged with their
+/// SettingsEntry
+#[derive(Component)]
+pub struct SettingsScreen;
+
+///struct / component for the root UI node of the pause menu, shown over the table
+#[derive(Component)]
+pub struct PauseOverlay;
+
+///enum / component for the entries of the pause menu, from top to bottom
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub enum PauseEntry {
+    Resume,
+    Settings,
+    Rules,
+    QuitToMenu,
+}
+
+///struct / component for the root UI node of the dialog asking whether to leave the table in the middle of a round
+#[derive(Component)]
+pub struct LeaveDialogOverlay;
+
+///enum / component for the buttons of the leave dialog
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq)]
+pub enum LeaveChoice {
+    #[default]
+    Stay,
+    Leave,
+}
+
+///struct / component for a text written in the language of the settings, holding its english text
+#[derive(Component)]
+pub struct Localized(pub &'static str);
+
+///struct / component for the music playing in the background
+#[derive(Component)]
+pub struct GameMusic;
+
+// -----------------------------
+
+// replay screen ---------------
+
+///struct / component for the root UI node of the replay screen, everything on the screen is a child of it
+#[derive(Component)]
+pub struct ReplayScreen;
+
+///struct / component for the node holding the cards of the replayed step, its children are respawned on every step
+#[derive(Component)]
+pub struct ReplayTable;
+
+///struct / component for the node holding the timeline scrubber buttons
+#[derive(Component)]
+pub struct ReplayTimeline;
+
+///struct / component for a timeline scrubber button, holds the step it jumps to
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct ReplayTimelineStep(pub usize);
+
+///enum / component used to access and uniquely identify the replay control buttons
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub enum ReplayControl {
+    PreviousRound,
+    StepBack,
+    StepForward,
+    NextRound,
+    ToggleOptimalAction,
+}
+
+///enum / component used to access the text elements on the replay screen that change
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub enum ReplayText {
+    Title,
+    Description,
+    OptimalAction,
+}
+
+// -----------------------------
+
+// online screen ---------------
+
+///struct / component for the root UI node of the online screen, everything on the screen is a child of it
+#[derive(Component)]
+pub struct OnlineScreen;
+
+///struct / component for the node holding the cards of the network table, its children are respawned on every snapshot
+#[derive(Component)]
+pub struct OnlineTable;
+
+///enum / component used to access the text elements on the online screen that change
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub enum OnlineText {
+    Title,
+    Status,
+    Help,
 }
+
+// -----------------------------
+
+// developer tools ---------------
+
+///struct / component for the root UI node of the developer console overlay, spawned while the console is open
+#[derive(Component)]
+pub struct ConsoleOverlay;
+
+///struct / component for the text of the developer console: its last lines and the command being typed
+#[derive(Component)]
+pub struct ConsoleText;
+
+///struct / component for the root UI node of the inspector overlay, spawned while the inspector is shown
+#[derive(Component)]
+pub struct InspectorOverlay;
+
+///struct / component for the text of the inspector overlay
+#[derive(Component)]
+pub struct InspectorText;
+
 // -----------------------------
 
 // card / deck components --------------
 
 ///struct / component used to create and access cards and maintain the data associated with each card
-#[derive(Component, Clone)]
+#[derive(Component, Clone, Debug, PartialEq)]
 pub struct Card{
     pub suite: String,
     pub face: String,
@@ -88,20 +273,75 @@ pub struct Card{
     pub back_asset_path: String,
 }
 
-/// struct / resource used to access and utilize the deck in various parts of the game
+impl Card {
+    ///code returns the short name of the card used in hand histories, e.g. "AS" for the ace of spades or "10H" for the ten of hearts
+    pub fn code(&self) -> String {
+        let rank = match self.face.as_str() {
+            "ace" => "A",
+            "jack" => "J",
+            "queen" => "Q",
+            "king" => "K",
+            number => number,
+        };
+        format!("{}{}", rank, self.suite[..1].to_uppercase())
+    }
+
+    ///from_code builds a card back from its short name (see code), returning None if the name is not a card
+    pub fn from_code(code: &str) -> Option<Card> {
+        let code = code.trim().to_uppercase();
+        if code.len() < 2 {
+            return None;
+        }
+        let (rank, suit) = code.split_at(code.len() - 1);
+        let (face, value) = match rank {
+            "A" => (String::from("ace"), (1, 11)),
+            "J" => (String::from("jack"), (10, 10)),
+            "Q" => (String::from("queen"), (10, 10)),
+            "K" => (String::from("king"), (10, 10)),
+            number => {
+                let number_value: u8 = number.parse().ok().filter(|value| (2..=10).contains(value))?;
+                (number_value.to_string(), (number_value, number_value))
+            }
+        };
+        let suite = match suit {
+            "S" => String::from("spades"),
+            "C" => String::from("clubs"),
+            "D" => String::from("diamonds"),
+            "H" => String::from("hearts"),
+            _ => return None,
+        };
+        Some(Card {
+            front_asset_path: format!("deck/{}_of_{}.png", face, suite),
+            back_asset_path: String::from("deck/card_back.png"),
+            suite,
+            face,
+            value,
+        })
+    }
+}
+
+/// struct / resource used to access and utilize the deck in various parts of the game.
+/// every shuffle is driven by the seed and the number of shuffles so far, so a shoe can be reproduced from the two.
 #[derive(Resource, Component, Clone)]
 pub struct Deck{
     pub cards: Vec<Card>,
-    pub last_dealt_index: usize
+    pub last_dealt_index: usize,
+    pub seed: u64,
+    pub shuffle_count: u32,
+    ///index of the last card dealt before the round being played, set by start_round. the cards dealt after it are on
+    /// the table and are kept out of a reshuffle in the middle of the round
+    pub round_start: Option<usize>,
 }
 
 //setting up ability for deck to be shuffled
 impl Shufflable for Deck {
     fn shuffle(&mut self){
+        let mut rng = StdRng::seed_from_u64(self.seed.wrapping_add(self.shuffle_count as u64));
+        self.shuffle_count += 1;
         for i in 0..self.cards.len(){
             //swap current card with random card
             let card1 = self.cards[i].clone();
-            let rand_index = rand::thread_rng().gen_range(0..self.cards.len());
+            let rand_index = rng.gen_range(0..self.cards.len());
             let card2 = self.cards[rand_index].clone();
             self.cards[i] = card2;
             self.cards[rand_index] = card1; 
@@ -110,22 +350,96 @@ impl Shufflable for Deck {
 }
 
 //implementing ability to deal from the deck
+//the top card of every shuffle is burned, the first card dealt is the second of the deck
 impl Dealable for Deck {
     fn deal(&mut self) -> Card{
-        if self.last_dealt_index == 51 {
-            println!("Shuffling");
-            self.shuffle();
-            self.last_dealt_index = 0;
-            return self.cards[self.last_dealt_index].clone()
+        if self.last_dealt_index == self.cards.len() - 1 {
+            debug!("Shuffling");
+            self.reshuffle();
         }
         self.last_dealt_index += 1;
         self.cards[self.last_dealt_index].clone()
     }
 }
 
-//setting up default values for the cards in the deck
+//setting up default values for the cards in the deck, shuffled with a random seed
 impl Default for Deck {
     fn default() -> Self {
+        Self::with_seed(rand::thread_rng().gen())
+    }
+}
+
+impl Deck {
+    ///running_count is the hi-lo count of the cards dealt since the last shuffle: +1 for 2 to 6, -1 for tens and aces
+    pub fn running_count(&self) -> i32 {
+        self.cards.iter().take(self.last_dealt_index + 1).skip(1).map(|card| match card.value.1 {
+            2..=6 => 1,
+            10 | 11 => -1,
+            _ => 0,
+        }).sum()
+    }
+
+    ///start_round marks the start of a round, the cards dealt from here on are kept out of a reshuffle until the next round
+    pub fn start_round(&mut self) {
+        self.round_start = Some(self.last_dealt_index);
+    }
+
+    //reshuffle shuffles the shoe once every card is dealt. the cards of the round being played are still on the table:
+    //they are taken out before the shuffle and put back right after the burned card, as cards already dealt
+    fn reshuffle(&mut self) {
+        let on_table: Vec<Card> = match self.round_start {
+            Some(start) if start > 0 => self.cards.drain(start + 1..).collect(),
+            //a round that went through the whole shoe takes every card back
+            _ => Vec::new(),
+        };
+        self.shuffle();
+        self.last_dealt_index = on_table.len();
+        self.cards.splice(1..1, on_table);
+        if self.round_start.is_some() {
+            self.round_start = Some(0);
+        }
+    }
+
+    ///cards_remaining is how many cards can still be dealt before the deck is reshuffled
+    pub fn cards_remaining(&self) -> usize {
+        self.cards.len().saturating_sub(self.last_dealt_index + 1)
+    }
+
+    ///cards_dealt is how many cards were dealt since the last shuffle, counting the cards of the round kept out of it
+    pub fn cards_dealt(&self) -> usize {
+        self.last_dealt_index
+    }
+
+    ///next_cards returns the next cards the deck deals, at most count of them and none past the next shuffle
+    pub fn next_cards(&self, count: usize) -> &[Card] {
+        let next = self.last_dealt_index + 1;
+        &self.cards[next..(next + count).min(self.cards.len())]
+    }
+
+    ///shoe_size is how many cards are dealt between two shuffles, every card but the burned one
+    pub fn shoe_size(&self) -> usize {
+        self.cards.len() - 1
+    }
+
+    ///rigged builds a deck that deals the given cards first, in order, followed by the rest of a deck shuffled with the seed.
+    /// each card is taken out of the rest of the deck, a card listed more often than a deck holds it is dealt anyway
+    pub fn rigged(cards: &[Card], seed: u64) -> Self {
+        let mut deck = Self::with_seed(seed);
+        for (i, card) in cards.iter().enumerate().take(deck.shoe_size()) {
+            //the top card is burned, the first card dealt is the second of the deck
+            let position = i + 1;
+            //the card is looked for in the rest of the deck and in the burned card, the cards before it are already placed
+            let found = (position..deck.cards.len()).chain([0]).find(|&other| deck.cards[other].code() == card.code());
+            match found {
+                Some(other) => deck.cards.swap(position, other),
+                None => deck.cards[position] = card.clone(),
+            }
+        }
+        deck
+    }
+
+    ///with_seed builds a full deck and shuffles it with the given seed
+    pub fn with_seed(seed: u64) -> Self {
         let mut cards: Vec<Card> = Vec::new();
         //We can probably extract suites and face_values into constants later. Fine for now
         let suites = [
@@ -164,7 +478,7 @@ impl Default for Deck {
                 )
             }
         } 
-        let mut deck = Self { cards: cards, last_dealt_index: 0};
+        let mut deck = Self { cards: cards, last_dealt_index: 0, seed, shuffle_count: 0, round_start: None };
 
         deck.shuffle(); 
         deck
@@ -189,11 +503,18 @@ impl Shufflable for Decks {
 
 impl Dealable for Decks{
     fn deal(&mut self) -> Card {
-        let mut deck_to_deal_from = self.decks[rand::thread_rng().gen_range(0..self.number_of_decks) as usize].clone();
+        let deck_to_deal_from = &mut self.decks[rand::thread_rng().gen_range(0..self.number_of_decks) as usize];
         deck_to_deal_from.deal()
     }
 }
 
+impl Decks {
+    ///cards_remaining is how many cards the decks can still deal before any of them is reshuffled
+    pub fn cards_remaining(&self) -> usize {
+        self.decks.iter().map(Deck::cards_remaining).sum()
+    }
+}
+
 impl Default for Decks{
     fn default() -> Self {
         Self { number_of_decks: 1, decks: vec![Deck::default()] }
@@ -212,21 +533,64 @@ pub struct PlayerName(pub String);
 pub struct PlayerBalance(pub f64);
 
 ///struct / component for the player hand, holds the current cards for player
-#[derive(Component)]
+#[derive(Component, Clone)]
 pub struct PlayerHand{
     pub cards: Vec<Card>,
     pub bet: u64
 }
 
+impl Scorable for PlayerHand {
+    fn hand_cards(&self) -> &[Card] {
+        &self.cards
+    }
+}
+
 ///struct / component for multiple player hands 
 #[derive(Component)]
 pub struct PlayerHands(pub Vec<PlayerHand>);
 
+///struct / component for the seat (spot) a player entity sits in, seat 0 is first base and is dealt first
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Seat(pub usize);
+
+///struct / component holding the result of the seat's last round, shown on its seat marker until the next round starts
+#[derive(Component, Default)]
+pub struct SeatStatus(pub Option<String>);
+
+///struct / component marking a seat played by the computer instead of the player, with how it plays and its own bankroll.
+/// a companion playing a script (an index into CompanionScripts) falls back on its personality for what the script leaves out
+#[derive(Component, Debug, Clone)]
+pub struct Companion {
+    pub personality: Personality,
+    pub bankroll: i32,
+    pub script: Option<usize>,
+    ///how its last rounds went, for scripted betting systems
+    pub progress: BetProgress,
+}
+
+impl Companion {
+    ///new sits a companion with the starting bankroll
+    pub fn new(personality: Personality) -> Self {
+        Self {
+            personality,
+            bankroll: COMPANION_STARTING_BANKROLL,
+            script: None,
+            progress: BetProgress::default(),
+        }
+    }
+}
+
 ///struct / component for the dealer hand, holds the current cards for dealer
 #[derive(Component)]
 pub struct DealerHand{
     pub cards: Vec<Card>
 }
 
+impl Scorable for DealerHand {
+    fn hand_cards(&self) -> &[Card] {
+        &self.cards
+    }
+}
+
 // -----------------------------
 
@@ -0,0 +1,309 @@
+///console module is the developer console, opened over the game with the backtick key to set the table up live while
+/// reproducing an edge case, without a scenario file or a rebuild. while it is open the keys go to the console and not to
+/// the game, a command is run with enter and its answer is printed above it.
+
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::input::ButtonState;
+use bevy::prelude::*;
+use super::components::{Card, ConsoleOverlay, ConsoleText, DealerHand, Deck, PlayerHands, Seat, TextComponents};
+use super::constants::{GameRoundState, CONSOLE_LINES};
+use super::events::{CardDealt, CardHolder};
+use super::resources::{ActiveSeat, BalanceValue};
+use super::rules::Rules;
+
+///CONSOLE_HELP is printed by `help`, and when the console is first opened
+pub static CONSOLE_HELP: &str = "give <card> [dealer|seat <n>], balance <n>, state <phase>, seed [<n>], deck remaining, rules [set h17|double true|false], clear";
+
+///enum ConsoleTarget is who `give` deals its card to
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConsoleTarget {
+    ///the seat whose turn it is, or that the chips go on while betting
+    ActiveSeat,
+    Seat(usize),
+    Dealer,
+}
+
+///enum ConsoleRule is a rule `rules set` can change
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConsoleRule {
+    ///the dealer hits soft 17
+    H17,
+    ///double down is allowed
+    Double,
+}
+
+///enum ConsoleCommand is a command of the console, as read from the line typed
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConsoleCommand {
+    ///deal a card, that is not taken from the shoe
+    Give { card: Card, target: ConsoleTarget },
+    Balance(i32),
+    ///move the round to a phase, even one the table would not go to from the current phase
+    State(GameRoundState),
+    ///print the seed of the shoe, or put in a new shoe shuffled with the given one
+    Seed(Option<u64>),
+    DeckRemaining,
+    Rules,
+    SetRule(ConsoleRule, bool),
+    Help,
+    Clear,
+}
+
+impl ConsoleCommand {
+    ///parse reads a command line, returning what is wrong with it otherwise
+    pub fn parse(line: &str) -> Result<ConsoleCommand, String> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        match words.as_slice() {
+            ["give", code, target @ ..] => {
+                let card = Card::from_code(code).ok_or_else(|| format!("{code} is not a card, e.g. AS or 10H"))?;
+                let target = match target {
+                    [] | ["player"] => ConsoleTarget::ActiveSeat,
+                    ["dealer"] => ConsoleTarget::Dealer,
+                    ["seat", seat] => ConsoleTarget::Seat(parse_number::<usize>(seat)?.checked_sub(1).ok_or("seats are numbered from 1")?),
+                    _ => return Err(String::from("give deals to the player, the dealer or seat <n>")),
+                };
+                Ok(ConsoleCommand::Give { card, target })
+            }
+            ["balance", amount] => Ok(ConsoleCommand::Balance(parse_number(amount)?)),
+            ["state", phase @ ..] if !phase.is_empty() => {
+                let name = phase.concat().replace('_', "").to_lowercase();
+                GameRoundState::ALL.into_iter()
+                    .find(|state| state.label().replace(' ', "").to_lowercase() == name)
+                    .map(ConsoleCommand::State)
+                    .ok_or_else(|| format!("{} is not a phase: {}", phase.join(" "), GameRoundState::ALL.map(|state| state.label()).join(", ")))
+            }
+            ["seed"] => Ok(ConsoleCommand::Seed(None)),
+            ["seed", seed] => Ok(ConsoleCommand::Seed(Some(parse_number(seed)?))),
+            ["deck", "remaining"] => Ok(ConsoleCommand::DeckRemaining),
+            ["rules"] => Ok(ConsoleCommand::Rules),
+            ["rules", "set", rule, value] => {
+                let rule = match *rule {
+                    "h17" => ConsoleRule::H17,
+                    "double" => ConsoleRule::Double,
+                    _ => return Err(format!("{rule} is not a rule, h17 or double")),
+                };
+                Ok(ConsoleCommand::SetRule(rule, parse_number(value)?))
+            }
+            ["help"] => Ok(ConsoleCommand::Help),
+            ["clear"] => Ok(ConsoleCommand::Clear),
+            [] => Err(String::from("type help for the commands")),
+            _ => Err(format!("unknown command {line}, type help for the commands")),
+        }
+    }
+}
+
+//parse_number reads the value of a command
+fn parse_number<T: std::str::FromStr>(value: &str) -> Result<T, String> {
+    value.parse().map_err(|_| format!("{value} cannot be used here"))
+}
+
+///struct / resource DevConsole is the console: whether it is open, the line being typed, the lines printed and the commands
+/// typed that are waiting to be run
+#[derive(Resource, Default)]
+pub struct DevConsole {
+    pub open: bool,
+    pub input: String,
+    pub lines: Vec<String>,
+    pub pending: Vec<String>,
+}
+
+impl DevConsole {
+    ///submit has a command run on the next frame, as if it was typed
+    pub fn submit(&mut self, line: &str) {
+        self.pending.push(line.to_string());
+    }
+
+    ///print adds a line to the console, only the last CONSOLE_LINES are kept
+    pub fn print(&mut self, line: impl Into<String>) {
+        self.lines.push(line.into());
+        let extra = self.lines.len().saturating_sub(CONSOLE_LINES);
+        self.lines.drain(..extra);
+    }
+}
+
+///console_input opens and closes the console with the backtick key and types into it while it is open. it runs right after
+/// the input is read, and takes the keys away from the game while the console is open
+pub fn console_input(
+    mut console: ResMut<DevConsole>,
+    mut keyboard_events: EventReader<KeyboardInput>,
+    mut keyboard_input: ResMut<ButtonInput<KeyCode>>,
+) {
+    let was_open = console.open;
+    for event in keyboard_events.read().filter(|event| event.state == ButtonState::Pressed) {
+        if event.key_code == KeyCode::Backquote {
+            console.open = !console.open;
+            if console.open && console.lines.is_empty() {
+                console.print(CONSOLE_HELP);
+            }
+            continue;
+        }
+        if !console.open {
+            continue;
+        }
+        match &event.logical_key {
+            Key::Enter => {
+                let line = std::mem::take(&mut console.input);
+                console.submit(&line);
+            }
+            Key::Backspace => {
+                console.input.pop();
+            }
+            Key::Escape => console.open = false,
+            Key::Space => console.input.push(' '),
+            Key::Character(characters) => console.input.push_str(characters),
+            _ => {}
+        }
+    }
+    if was_open || console.open {
+        keyboard_input.reset_all();
+    }
+}
+
+///run_console_commands runs the commands typed since the last frame and prints their answer
+pub fn run_console_commands(
+    mut console: ResMut<DevConsole>,
+    mut balance_value: ResMut<BalanceValue>,
+    mut deck: ResMut<Deck>,
+    mut rules: ResMut<Rules>,
+    game_state: Res<State<GameRoundState>>,
+    mut next_state: ResMut<NextState<GameRoundState>>,
+    active_seat: Res<ActiveSeat>,
+    mut player_query: Query<(&Seat, &mut PlayerHands)>,
+    mut dealer_query: Query<&mut DealerHand>,
+    mut text_query: Query<(&TextComponents, &mut Text)>,
+    mut card_dealt: EventWriter<CardDealt>,
+) {
+    for line in std::mem::take(&mut console.pending) {
+        console.print(format!("> {line}"));
+        let command = match ConsoleCommand::parse(&line) {
+            Ok(command) => command,
+            Err(error) => {
+                console.print(error);
+                continue;
+            }
+        };
+        info!("Console: {line}");
+        let answer = match command {
+            ConsoleCommand::Give { card, target } => {
+                let seat = match target {
+                    ConsoleTarget::ActiveSeat => Some(active_seat.0),
+                    ConsoleTarget::Seat(seat) => Some(seat),
+                    ConsoleTarget::Dealer => None,
+                };
+                let code = card.code();
+                match seat {
+                    Some(seat) => match player_query.iter_mut().find(|(player_seat, _)| player_seat.0 == seat) {
+                        Some((_, mut player_hands)) => match player_hands.0.first_mut() {
+                            Some(player_hand) => {
+                                let index = player_hand.cards.len();
+                                player_hand.cards.push(card.clone());
+                                card_dealt.send(CardDealt { holder: CardHolder::Seat(seat), hand: 0, index, card, face_up: true, shuffle: None });
+                                format!("{code} dealt to seat {}", seat + 1)
+                            }
+                            None => format!("seat {} has no hand", seat + 1),
+                        },
+                        None => format!("seat {} is not at the table", seat + 1),
+                    },
+                    None => match dealer_query.get_single_mut() {
+                        Ok(mut dealer_hand) => {
+                            let index = dealer_hand.cards.len();
+                            dealer_hand.cards.push(card.clone());
+                            //a first card is the dealer's hole card
+                            card_dealt.send(CardDealt { holder: CardHolder::Dealer, hand: 0, index, card, face_up: index != 0, shuffle: None });
+                            format!("{code} dealt to the dealer")
+                        }
+                        Err(_) => String::from("there is no dealer at the table"),
+                    },
+                }
+            }
+            ConsoleCommand::Balance(balance) => {
+                balance_value.value = balance;
+                for (text_component, mut text) in text_query.iter_mut() {
+                    if let TextComponents::Balance = text_component {
+                        text.sections[0].value = balance.to_string();
+                    }
+                }
+                format!("balance set to {balance}")
+            }
+            ConsoleCommand::State(state) => {
+                let current = *game_state.get();
+                next_state.set(state);
+                if current.can_transition_to(state) {
+                    format!("{} -> {}", current.label(), state.label())
+                } else {
+                    format!("{} -> {} (forced, the table has no such transition)", current.label(), state.label())
+                }
+            }
+            ConsoleCommand::Seed(None) => format!("seed {}, shuffled {} times", deck.seed, deck.shuffle_count),
+            ConsoleCommand::Seed(Some(seed)) => {
+                *deck = Deck::with_seed(seed);
+                format!("new shoe shuffled with seed {seed}")
+            }
+            ConsoleCommand::DeckRemaining => format!("{} of {} cards remaining, running count {:+}", deck.cards_remaining(), deck.shoe_size(), deck.running_count()),
+            ConsoleCommand::Rules => format!("h17 {}, double {}", rules.dealer_hits_soft_17, rules.allow_double_down),
+            ConsoleCommand::SetRule(rule, value) => {
+                match rule {
+                    ConsoleRule::H17 => rules.dealer_hits_soft_17 = value,
+                    ConsoleRule::Double => rules.allow_double_down = value,
+                }
+                format!("h17 {}, double {}", rules.dealer_hits_soft_17, rules.allow_double_down)
+            }
+            ConsoleCommand::Help => String::from(CONSOLE_HELP),
+            ConsoleCommand::Clear => {
+                console.lines.clear();
+                continue;
+            }
+        };
+        console.print(answer);
+    }
+}
+
+///show_console spawns the console over the game while it is open and keeps its text up to date
+pub fn show_console(
+    mut commands: Commands,
+    assets: Res<AssetServer>,
+    console: Res<DevConsole>,
+    overlay_query: Query<Entity, With<ConsoleOverlay>>,
+    mut text_query: Query<&mut Text, With<ConsoleText>>,
+) {
+    if !console.is_changed() {
+        return;
+    }
+    if !console.open {
+        for entity in overlay_query.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+        return;
+    }
+    let text = format!("{}\n> {}_", console.lines.join("\n"), console.input);
+    if let Ok(mut console_text) = text_query.get_single_mut() {
+        console_text.sections[0].value = text;
+        return;
+    }
+    commands.spawn((
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(50.0),
+                padding: UiRect::all(Val::Px(8.0)),
+                align_items: AlignItems::FlexEnd,
+                ..default()
+            },
+            background_color: BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.85)),
+            z_index: ZIndex::Global(10),
+            ..default()
+        },
+        ConsoleOverlay,
+    ))
+    .with_children(|parent| {
+        parent.spawn((
+            TextBundle::from_section(text, TextStyle {
+                font: assets.load("fonts/FiraSans-SemiBold.ttf"),
+                font_size: 14.0,
+                color: Color::srgb(0.6, 1.0, 0.6),
+            }),
+            ConsoleText,
+        ));
+    });
+}
@@ -11,13 +11,85 @@ pub static DEALER_CARDS_INITIAL_VERTICAL_POSITION: f32 = 50.;
 pub static PLAYER_CARDS_INITIAL_HORIZONTAL_POSITION: f32 = 10.;
 pub static PLAYER_CARDS_INITIAL_VERTICAL_POSITION: f32 = 50.;
 
-///enum / States AppState used to track whether the game is in the Start state or InGame  state.
+pub static MAX_SEATS: usize = 7;
+//seats are placed along an arc when there is more than one, first base on the right
+pub static SEAT_ARC_LEFT: f32 = 10.;
+pub static SEAT_ARC_RIGHT: f32 = 690.;
+pub static SEAT_ARC_TOP: f32 = 150.;
+pub static SEAT_ARC_DEPTH: f32 = 50.;
+
+pub static COMPANION_STARTING_BANKROLL: i32 = 1000;
+pub static COMPANION_BASE_BET: i32 = 10;
+//seconds a companion waits before each action, so the player can follow along
+pub static COMPANION_ACTION_DELAY: f32 = 0.8;
+
+//a watched table is filled with this many companions, and waits this many seconds between each round
+pub static SPECTATOR_SEATS: usize = 5;
+pub static SPECTATOR_ROUND_DELAY: f32 = 2.0;
+
+//a bot has this many milliseconds to answer, and bets this much whenever its bet is missing or turned down
+pub static BOT_REPLY_TIMEOUT_MS: u64 = 2000;
+pub static BOT_DEFAULT_BET: i32 = 10;
+//seconds the game waits before asking the bot for each decision, so its play can be followed on screen
+pub static BOT_ACTION_DELAY: f32 = 0.5;
+
+//autoplay waits AUTOPLAY_DELAY seconds divided by its speed before each step, the stops are in dollars and hands (0 is no limit)
+pub static AUTOPLAY_DELAY: f32 = 0.6;
+pub static AUTOPLAY_BASE_BET: i32 = 10;
+pub static AUTOPLAY_SPEEDS: [f32; 5] = [0.5, 1., 2., 4., 10.];
+pub static AUTOPLAY_STOP_AMOUNTS: [i32; 6] = [0, 50, 100, 250, 500, 1000];
+pub static AUTOPLAY_HAND_LIMITS: [u32; 6] = [0, 10, 50, 100, 500, 1000];
+
+//seconds the banner of a game error stays over the table
+pub static ERROR_BANNER_DURATION: f32 = 5.0;
+
+//frames per second of a game running without a window
+pub static HEADLESS_FRAME_RATE: f64 = 60.;
+
+//lines of output the developer console keeps on screen
+pub static CONSOLE_LINES: usize = 12;
+
+//cards of the shoe the inspector shows ahead, in debug builds
+pub static INSPECTOR_NEXT_CARDS: usize = 8;
+
+//size of the window the table is laid out for, everything is scaled from it
+pub static BASE_WINDOW_SIZE: (f32, f32) = (800., 500.);
+
+//window sizes and animation speeds the settings go through, and how much a volume changes in one step
+pub static WINDOW_SIZES: [(f32, f32); 3] = [(800., 500.), (1200., 750.), (1600., 1000.)];
+pub static ANIMATION_SPEEDS: [f32; 4] = [0.5, 1.0, 1.5, 2.0];
+pub static VOLUME_STEP: f32 = 0.1;
+
+///enum / States AppState used to track whether the game is on the Start screen (the main menu), InGame state, Online at a
+/// network table, or showing the Stats, Replay, Practice, Settings or Rules screens.
 /// also used to transition / set up UI elements based on certain actions (button presses)
 #[derive(States, Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
 pub enum AppState {
     #[default]
     Start,
     InGame,
+    Stats,
+    Replay,
+    Online,
+    ///the basic strategy trainer
+    Practice,
+    Settings,
+    Rules,
+}
+
+///enum / States PauseState used to track whether the table is being played or paused, and what the pause menu shows.
+/// the timers of the table (companions, autoplay, a watched table) are frozen in every state but Running
+#[derive(States, Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
+pub enum PauseState {
+    #[default]
+    Running,
+    Paused,
+    ///the settings screen, opened from the pause menu
+    Settings,
+    ///the rules screen, opened from the pause menu
+    Rules,
+    ///asking whether to leave the table in the middle of a round
+    ConfirmLeave,
 }
 
 ///enum / States DeckState used to track whether the deck is shuffled or not
@@ -28,13 +100,23 @@ pub enum DeckState {
     Shuffled
 }
 
-///enum / States GameRoundState is used to track game rounds, turns, and initiate systems / transitions based on actions in game
+///enum / States GameRoundState is used to track game rounds, turns, and initiate systems / transitions based on actions in game.
+/// the transitions between phases are listed in the round_state module
 #[derive(States, Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
 pub enum GameRoundState {
+    ///the table is being set up, left for Betting as soon as it is shown
     #[default]
     RoundStart,
+    ///chips go on the seats, left for Dealing when deal is pressed with a bet on the table
     Betting,
+    ///the cards are dealt on entry, left for PlayerHand (insurance is not offered, there is no phase for it yet)
+    Dealing,
+    ///each seat is played in turn, left for DealerHand once the last seat has played
     PlayerHand,
+    ///the hole card is revealed and the dealer draws on entry, left for Settlement
     DealerHand,
+    ///every seat is paid out or loses its bet on entry, left for RoundEnd
+    Settlement,
+    ///the result is shown, left for Betting when keep playing is pressed
     RoundEnd
 }
\ No newline at end of file
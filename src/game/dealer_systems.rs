@@ -3,13 +3,17 @@
 use bevy::prelude::*;
 use crate::game::components::{Decks, DealerHand, Card, PlayerHands};
 use crate::game::bundles::DealerBundle;
-use crate::game::constants::{DeckState, NO_CARD_VALUE};
-use crate::game::in_game_systems::spawn_result_text;
-use super::components::{Deck, InGameCardAccess, PlayerBalance, PlayerHand};
-use super::constants::{GameRoundState, CARD_HORIZONTAL_SPACING, CARD_VERTICAL_SPACING, DEALER_CARDS_INITIAL_HORIZONTAL_POSITION, DEALER_CARDS_INITIAL_VERTICAL_POSITION};
-use super::in_game_systems::spawn_dealer_card;
-use super::resources::{BalanceValue, BetValue, ParentNode};
-use super::traits::{Dealable, Shufflable};
+use crate::game::constants::DeckState;
+use super::components::{Companion, Deck, PlayerHand, Seat, SeatStatus};
+use super::constants::GameRoundState;
+use super::errors::GameError;
+use super::events::{CardDealt, CardHolder, DealerRevealed, HandSettled, RoundOutcome, RoundStarted, ShoeShuffled};
+use super::hand_history::HandHistory;
+use super::resources::{ActiveSeat, BalanceValue, BetValue, TableConfig};
+use super::rules::Rules;
+use super::round_state::RoundTransition;
+use super::settlement::{dealer_should_hit, determine_win};
+use super::traits::{Dealable, Scorable, Shufflable};
 
 
 ///spawn_dealer is used to spawn an instance of the dealer and initializing the hand for the dealer
@@ -36,6 +40,91 @@ pub fn spawn_dealer(mut commands: Commands, mut deck: ResMut<Deck>){
     });
 }
 
+///deal_new_round deals the round once the player hits deal: two cards to every seat with a bet and to the dealer, going
+/// around the table in order like a real deal (the dealer's first card is the face down hole card). the turn starts at the
+/// first seat dealt in, and the new round is also started in the hand history here. RoundStarted is sent, then CardDealt for
+/// every card in the order they are dealt. a table without a dealer cannot be dealt
+pub fn deal_new_round(
+    table: Res<TableConfig>,
+    mut active_seat: ResMut<ActiveSeat>,
+    mut deck: ResMut<Deck>,
+    rules: Res<Rules>,
+    balance: Res<BalanceValue>,
+    bet_value: Res<BetValue>,
+    mut hand_history: ResMut<HandHistory>,
+    mut player_query: Query<(&Seat, &mut PlayerHands, Has<Companion>)>,
+    mut dealer_query: Query<&mut DealerHand>,
+    mut round_started: EventWriter<RoundStarted>,
+    mut card_dealt: EventWriter<CardDealt>,
+    mut shoe_shuffled: EventWriter<ShoeShuffled>,
+) -> Result<(), GameError> {
+    let Ok(mut dealer_hand) = dealer_query.get_single_mut() else {
+        return Err(GameError::NoDealer);
+    };
+    let mut seats: Vec<(&Seat, Mut<PlayerHands>, bool)> = player_query.iter_mut()
+        .filter(|(_, player_hands, _)| player_hands.0.first().is_some_and(|hand| hand.bet > 0))
+        .collect();
+    seats.sort_by_key(|(seat, _, _)| **seat);
+    let Some((first_seat, _, _)) = seats.first() else {
+        info!("No bets placed, cannot deal a new round");
+        return Ok(());
+    };
+    active_seat.0 = first_seat.0;
+
+    let shoe_position = deck.last_dealt_index;
+    let shuffle_count = deck.shuffle_count;
+    deck.start_round();
+    round_started.send(RoundStarted { seats: seats.iter().map(|(seat, _, _)| seat.0).collect(), shoe_position });
+    dealer_hand.cards.clear();
+    for (seat, player_hands, _) in seats.iter_mut() {
+        player_hands.0.first_mut().ok_or(GameError::MissingHand(seat.0))?.cards.clear();
+    }
+    for index in 0..2{
+        for (seat, player_hands, _) in seats.iter_mut() {
+            let card = deal_from_shoe(&mut deck, &mut shoe_shuffled);
+            player_hands.0.first_mut().ok_or(GameError::MissingHand(seat.0))?.cards.push(card.clone());
+            card_dealt.send(CardDealt { holder: CardHolder::Seat(seat.0), hand: 0, index, card, face_up: true, shuffle: Some(deck.shuffle_count) });
+        }
+        let card = deal_from_shoe(&mut deck, &mut shoe_shuffled);
+        dealer_hand.cards.push(card.clone());
+        //the dealer's first card is the hole card
+        card_dealt.send(CardDealt { holder: CardHolder::Dealer, hand: 0, index, card, face_up: index != 0, shuffle: Some(deck.shuffle_count) });
+    }
+
+    //the hand history only follows the player's own seats
+    let dealt_seats: Vec<(usize, Vec<Card>, i32)> = seats.iter()
+        .filter(|(_, _, is_companion)| !is_companion)
+        .filter_map(|(seat, player_hands, _)| player_hands.0.first().map(|hand| (seat.0, hand.cards.clone(), hand.bet as i32)))
+        .collect();
+    hand_history.begin_round(&deck, shoe_position, shuffle_count, &rules, table.seats, balance.value + bet_value.value, &dealer_hand.cards, &dealt_seats);
+    Ok(())
+}
+
+///dealer_up_card returns the dealer's face up card, once it is dealt
+pub fn dealer_up_card<'a>(dealer_query: &'a Query<&DealerHand>) -> Option<&'a Card> {
+    dealer_query.get_single().ok().and_then(|dealer_hand| dealer_hand.cards.get(1))
+}
+
+///dealer_up_value returns the value of the dealer's face up card (aces count as 11), 0 before it is dealt
+pub fn dealer_up_value(dealer_query: &Query<&DealerHand>) -> u8 {
+    dealer_up_card(dealer_query).map_or(0, |card| card.value.1)
+}
+
+///finish_dealing moves on once the cards are dealt: to the turn of the first seat, or back to betting when no seat had a
+/// bet to be dealt in
+pub fn finish_dealing(
+    mut round_transition: RoundTransition,
+    player_query: Query<&PlayerHands>,
+    dealer_query: Query<&DealerHand>,
+){
+    let dealt_in = player_query.iter().any(|player_hands| player_hands.0.first().is_some_and(|hand| !hand.cards.is_empty()));
+    let next = match dealer_up_card(&dealer_query) {
+        Some(_) if dealt_in => GameRoundState::PlayerHand,
+        _ => GameRoundState::Betting,
+    };
+    round_transition.request(next);
+}
+
 ///shuffle_dealer_decks is used to randomize and shuffle decks associated with dealer
 pub fn shuffle_dealer_decks(mut query: Query<&mut Decks>,
     mut state: ResMut<State<DeckState>>,
@@ -48,112 +137,136 @@ pub fn shuffle_dealer_decks(mut query: Query<&mut Decks>,
 }
 
 
-///reveal_dealer_hand holds the functionality for turning the dealer cards in game when certain conditions are met
+///reveal_dealer_hand holds the functionality for turning the dealer's face down hole card over once the player's turn is over,
+/// sending DealerRevealed
 pub fn reveal_dealer_hand(
-    mut dealer_card_image_query: Query<(&InGameCardAccess, &mut UiImage)>,
     dealer_hand_query: Query<&DealerHand>,
-    assets: Res<AssetServer>,
-){
-    for (card_access, mut ui_image) in dealer_card_image_query.iter_mut() {
-        if let InGameCardAccess::DealerCard(card_index) = card_access {
-            if *card_index == 0 {
-                for dealer_hand in dealer_hand_query.iter(){
-                    let card = &dealer_hand.cards[0];
-                    let card_front_texture = assets.load(card.front_asset_path.clone());
-                    ui_image.texture = card_front_texture;
-                }
-                }
-              
-        }
+    mut dealer_revealed: EventWriter<DealerRevealed>,
+) -> Result<(), GameError> {
+    let dealer_hand = dealer_hand_query.get_single().map_err(|_| GameError::NoDealer)?;
+    let hole_card = dealer_hand.cards.first().ok_or(GameError::MissingDealerCard(0))?;
+    dealer_revealed.send(DealerRevealed { hole_card: hole_card.clone(), total: dealer_hand.best_total() });
+    Ok(())
+}
+
+///deal_from_shoe deals the next card from the shoe. the deck reshuffles itself when it runs out, in the middle of a deal,
+/// and ShoeShuffled is sent for that shuffle here
+pub fn deal_from_shoe(deck: &mut Deck, shoe_shuffled: &mut EventWriter<ShoeShuffled>) -> Card {
+    let shuffle_count = deck.shuffle_count;
+    let card = deck.deal();
+    if deck.shuffle_count != shuffle_count {
+        shoe_shuffled.send(ShoeShuffled { seed: deck.seed, shuffle_count: deck.shuffle_count });
     }
+    card
 }
 
-/// play_dealer_hand is responsible for the logic related to how the dealer should play his hand based on certain conditions
+/// play_dealer_hand is responsible for the logic related to how the dealer should play his hand based on certain conditions,
+/// the round is settled once the dealer is done drawing
 pub fn play_dealer_hand(
-    mut commands: Commands,
     mut deck: ResMut<Deck>,
-    assets: Res<AssetServer>, 
-    parent_node: Res<ParentNode>,
-    mut next_state: ResMut<NextState<GameRoundState>>,
+    mut round_transition: RoundTransition,
     mut query: Query<&mut DealerHand>,
-    mut balance: ResMut<BalanceValue>,
-    mut bet_amount: ResMut<BetValue>
+    player_query: Query<&PlayerHands>,
+    mut hand_history: ResMut<HandHistory>,
+    rules: Res<Rules>,
+    mut card_dealt: EventWriter<CardDealt>,
+    mut shoe_shuffled: EventWriter<ShoeShuffled>,
+) -> Result<(), GameError> {
+    let Ok(mut dealer_hand) = query.get_single_mut() else {
+        return Err(GameError::NoDealer);
+    };
+    //the dealer only draws if at least one seat is still standing
+    let any_seat_standing = player_query.iter()
+        .any(|player_hands| player_hands.0.first().is_some_and(|hand| !hand.cards.is_empty() && !hand.is_bust()));
+
+    //Hit on soft 17 unless the rules say otherwise
+    while any_seat_standing && dealer_should_hit(&dealer_hand, rules.dealer_hits_soft_17) {
+        let insert_index = dealer_hand.cards.len();
+        let card_to_insert = deal_from_shoe(&mut deck, &mut shoe_shuffled);
+        hand_history.record_dealer_draw(&card_to_insert);
+        dealer_hand.cards.push(card_to_insert.clone());
+        card_dealt.send(CardDealt { holder: CardHolder::Dealer, hand: 0, index: insert_index, card: card_to_insert, face_up: true, shuffle: Some(deck.shuffle_count) });
+
+        //Maybe add a small delay here
+    }
 
-){
-    
-    for mut dealer_hand in &mut query{
-        let mut totals: (u8, u8) = (0,0);
-    for card in &dealer_hand.cards{
-        let (card_total1, card_total2) = card.value;
-        totals.0 += card_total1;
-        totals.1 += card_total2;
+    let dealer_bust = determine_dealer_bust(&mut dealer_hand);
+    if dealer_bust {
+        info!("Dealer busts on {}", dealer_hand.best_total());
     }
-        //Hit on soft 17
-        while totals.0 < 17 || totals.1 < 18 {
-            let insert_index = dealer_hand.cards.len();
-            let card_to_insert = deck.deal();
-            dealer_hand.cards.push(card_to_insert.clone());
-            let position = Vec2 {
-                x: DEALER_CARDS_INITIAL_HORIZONTAL_POSITION + (insert_index as f32)*CARD_HORIZONTAL_SPACING,
-                y: DEALER_CARDS_INITIAL_VERTICAL_POSITION  + (insert_index as f32)*CARD_VERTICAL_SPACING};
-                commands.entity(parent_node.0).with_children(|parent|{
-                    spawn_dealer_card(
-                        parent,
-                        &assets, 
-                        &card_to_insert, 
-                        insert_index, 
-                        position,
-                        true,
-                        true
-                    );
-                });
-            let (card_value1, card_value2) = card_to_insert.value;
-            totals.0 += card_value1;
-            totals.1 += card_value2;
-
-            //Maybe add a small delay here
-        }
+    round_transition.request(GameRoundState::Settlement);
+    Ok(())
+}
 
-        if determine_dealer_bust(&mut dealer_hand) {
-            println!("Dealer Bust: Player Wins!");
+///settle_round settles every seat in play against the dealer's hand: the player's seats are paid out of (or into) the balance,
+/// companions out of their bankroll, and HandSettled is sent for each of them. the seats that went bust were settled when
+/// they did and are left out
+pub fn settle_round(
+    mut round_transition: RoundTransition,
+    mut query: Query<&mut DealerHand>,
+    mut player_query: Query<(&Seat, &PlayerHands, &mut SeatStatus, Option<&mut Companion>)>,
+    mut balance: ResMut<BalanceValue>,
+    mut bet_amount: ResMut<BetValue>,
+    mut settled_rounds: EventWriter<HandSettled>,
+) -> Result<(), GameError> {
+    let Ok(mut dealer_hand) = query.get_single_mut() else {
+        return Err(GameError::NoDealer);
+    };
+    let dealer_bust = determine_dealer_bust(&mut dealer_hand);
 
-            commands.entity(parent_node.0).with_children(|parent|{
-                let result = format!("You Win ${}! (Dealer Bust)", bet_amount.value);
-                spawn_result_text(
-                    parent,
-                    &assets,
-                    &result
-                );
-            });
+    let mut seats: Vec<_> = player_query.iter_mut()
+        .filter(|(_, player_hands, seat_status, _)| seat_status.0.is_none() && player_hands.0.first().is_some_and(|hand| !hand.cards.is_empty()))
+        .collect();
+    seats.sort_by_key(|(seat, _, _, _)| **seat);
 
-            balance.value += bet_amount.value * 2;
-            bet_amount.value = 0;
+    for (seat, player_hands, seat_status, companion) in seats.iter_mut() {
+        let player_hand = player_hands.0.first().ok_or(GameError::MissingHand(seat.0))?;
+        let outcome = if player_hand.is_bust() {
+            RoundOutcome::Loss
+        }
+        else if dealer_bust && !player_hand.is_blackjack() {
+            RoundOutcome::Win
+        }
+        else {
+            determine_win(player_hand, &dealer_hand)
+        };
 
-            next_state.set(GameRoundState::RoundEnd);  
-            return; 
+        let settled = settle_hand(seat, outcome, player_hand, &dealer_hand, seat_status, companion.as_deref_mut());
+        if !settled.companion {
+            balance.value += settled.payout();
         }
+        settled_rounds.send(settled);
+    }
 
-        next_state.set(GameRoundState::RoundEnd);
+    bet_amount.value = 0;
+    round_transition.request(GameRoundState::RoundEnd);
+    Ok(())
+}
 
+///settle_hand settles the first hand of a seat with the outcome, setting the seat's status. a companion is paid from its own
+/// bankroll, the payout of the player's seats is left to the caller. returns the HandSettled event to send
+pub fn settle_hand(
+    seat: &Seat,
+    outcome: RoundOutcome,
+    player_hand: &PlayerHand,
+    dealer_hand: &DealerHand,
+    seat_status: &mut SeatStatus,
+    companion: Option<&mut Companion>,
+) -> HandSettled {
+    let bet = player_hand.bet as i32;
+    let mut settled = HandSettled::new(seat.0, outcome, bet, player_hand, dealer_hand);
+    seat_status.0 = Some(format!("{:?} {:+}", outcome, settled.net));
+    if let Some(companion) = companion {
+        companion.bankroll += settled.payout();
+        companion.progress.record(outcome, bet);
+        settled.companion = true;
     }
+    settled
 }
 
-
 /// determine_dealer_bust is a helper function to determine when the dealers hand is a bust
 pub fn determine_dealer_bust(dealer_hand: &mut DealerHand)-> bool{
-    let mut totals: (u8, u8) = (0,0);
-    for card in &dealer_hand.cards{
-        let (card_total1, card_total2) = card.value;
-        totals.0 += card_total1;
-        totals.1 += card_total2;
-    }
-    if totals.1 > 21 && totals.0 > 21 {
-        return true;
-    }
-    else{
-        return false;
-    }
-
+    dealer_hand.is_bust()
 }
 
 ///spawn_test_dealer was and is used for command line testing , giving us a way to view and test the values associated with dealer
@@ -179,17 +292,17 @@ pub fn spawn_test_dealer(mut commands: Commands, mut deck: ResMut<Deck>){
         dealer_decks,
     });
 
-    println!("Dealer's hand: {} of {}, {} of {}", dealer_card1.face, dealer_card1.suite, dealer_card2.face, dealer_card2.suite);
+    debug!("Dealer's hand: {} of {}, {} of {}", dealer_card1.face, dealer_card1.suite, dealer_card2.face, dealer_card2.suite);
 }
 
 ///test_dealer_decks to test the ability for dealer to have or use multiple decks 
 pub fn test_dealer_decks(mut query: Query<&mut Decks>){
     for deck in &mut query{
         let num_decks = deck.number_of_decks;
-        println!("Number of Decks: {num_decks}");
+        debug!("Number of Decks: {num_decks}");
         let deck1 = &deck.decks[0];
         for card in &deck1.cards{
-            println!("Card: {} of {}", card.face, card.suite);
+            debug!("Card: {} of {}", card.face, card.suite);
         }
     }
 }
@@ -199,54 +312,6 @@ pub fn test_dealer_hand(mut query: Query<&mut DealerHand>){
     for dealer_hand in &mut query{
         let card1 = &dealer_hand.cards[0];
         let card2 = &dealer_hand.cards[1];
-        println!("Dealer Cards: {} of {}, {} of {}", card1.face, card1.suite, card2.face, card2.suite);
+        debug!("Dealer Cards: {} of {}, {} of {}", card1.face, card1.suite, card2.face, card2.suite);
     }
 }
-
-///determine_win will be used to determine who wins when neither player busts.
-/// first time trying to implement it , it ended up being trickier than anticipated and I got stuck and couldn't get it to work.
-/// the logic is laid out for when we can attempt to finish this implementation.
-pub fn determine_win() {
-
-    // Maybe replace bust function with this ?
-
-    // Retrieve the player's hand
-    
-    // Retrieve the dealer's hand
-    
-    // Calculate hand totals for both player and dealer into player_total and dealer_total
-
-    /*
-        Determine the outcome of the round
-        
-        if player_total > 21 {
-            // Player busts, dealer wins
-            println!("Player busts! Dealer wins.");
-            adjust_balance(&mut player_balance, -bet_value.value);
-            next_state.set(GameRoundState::RoundEnd);
-        } else if dealer_total > 21 {
-            // Dealer busts, player wins
-            println!("Dealer busts! Player wins.");
-            adjust_balance(&mut player_balance, bet_value.value);
-            next_state.set(GameRoundState::RoundEnd);
-        } else {
-            // No one busted, compare totals
-            if player_total > dealer_total {
-                // Player wins
-                println!("Player wins!");
-                adjust_balance(&mut player_balance, bet_value.value);
-                next_state.set(GameRoundState::RoundEnd);
-            } else if dealer_total > player_total {
-                // Dealer wins
-                println!("Dealer wins!");
-                adjust_balance(&mut player_balance, -bet_value.value);
-                next_state.set(GameRoundState::RoundEnd);
-            } else {
-                // It's a tie (push)
-                println!("It's a tie! Push.");
-                next_state.set(GameRoundState::RoundEnd);
-            }
-        }
-     */
-    
-}
\ No newline at end of file
@@ -0,0 +1,116 @@
+///errors module has the errors the game can run into while a round is played: something a system counts on that is
+/// missing from the table. the systems that can run into one return it instead of panicking and are piped into
+/// handle_game_error, which logs it, shows it on a banner over the table and calls the round off back to betting.
+
+use std::fmt;
+use bevy::prelude::*;
+use super::components::{Companion, PlayerHands, TextComponents};
+use super::constants::{GameRoundState, ERROR_BANNER_DURATION};
+use super::resources::{BalanceValue, BetValue};
+use super::round_state::RoundTransition;
+use super::traits::Scorable;
+
+///enum GameError is what a system found missing from the table
+#[derive(Debug, Clone, PartialEq)]
+pub enum GameError {
+    ///there is no dealer at the table, or more than one
+    NoDealer,
+    ///the seat whose turn it is has no one sitting in it
+    NoSeat(usize),
+    ///the dealer's hand is missing the card at this index
+    MissingDealerCard(usize),
+    ///the seat has no hand to bet on or play
+    MissingHand(usize),
+    ///the bot given with `--bot` could not be started, and why
+    BotNotStarted(String),
+}
+
+impl GameError {
+    ///calls_round_off returns whether the error was found at the table, in the middle of a round
+    pub fn calls_round_off(&self) -> bool {
+        !matches!(self, GameError::BotNotStarted(_))
+    }
+}
+
+impl fmt::Display for GameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GameError::NoDealer => write!(f, "there is no dealer at the table"),
+            GameError::NoSeat(seat) => write!(f, "seat {} is not at the table", seat + 1),
+            GameError::MissingDealerCard(index) => write!(f, "the dealer's hand has no card {}", index + 1),
+            GameError::MissingHand(seat) => write!(f, "seat {} has no hand", seat + 1),
+            GameError::BotNotStarted(reason) => write!(f, "the bot could not be started: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for GameError {}
+
+///struct / resource GameErrors holds the last error the game ran into, shown on the banner until its timer finishes
+#[derive(Resource)]
+pub struct GameErrors {
+    pub last_error: Option<GameError>,
+    pub banner_timer: Timer,
+}
+
+impl Default for GameErrors {
+    fn default() -> Self {
+        Self {
+            last_error: None,
+            banner_timer: Timer::from_seconds(ERROR_BANNER_DURATION, TimerMode::Once),
+        }
+    }
+}
+
+///handle_game_error is piped after the systems that can fail. an error is logged and shown on the banner. for an error found
+/// at the table the bets still on it are handed back and the round goes back to RoundStart, which sets the table up for
+/// betting again
+pub fn handle_game_error(
+    In(result): In<Result<(), GameError>>,
+    mut game_errors: ResMut<GameErrors>,
+    mut round_transition: RoundTransition,
+    mut balance_value: ResMut<BalanceValue>,
+    mut bet_value: ResMut<BetValue>,
+    mut companion_query: Query<(&PlayerHands, &mut Companion)>,
+) {
+    let Err(error) = result else {
+        return;
+    };
+    game_errors.banner_timer.reset();
+    if !error.calls_round_off() {
+        error!("Game error: {error}");
+        game_errors.last_error = Some(error);
+        return;
+    }
+    error!("Game error: {error}, the round is called off");
+
+    //bets are only still on the table until the round is settled
+    balance_value.value += bet_value.value;
+    bet_value.value = 0;
+    if round_transition.current() != GameRoundState::RoundEnd {
+        for (player_hands, mut companion) in companion_query.iter_mut() {
+            //a bust hand was settled when it went bust
+            companion.bankroll += player_hands.0.iter().filter(|hand| !hand.is_bust()).map(|hand| hand.bet as i32).sum::<i32>();
+        }
+    }
+
+    round_transition.request(GameRoundState::RoundStart);
+    game_errors.last_error = Some(error);
+}
+
+///show_game_errors shows the last game error on the banner over the table, and takes it down once its time is up
+pub fn show_game_errors(time: Res<Time>, mut game_errors: ResMut<GameErrors>, mut text_query: Query<(&TextComponents, &mut Text)>) {
+    if game_errors.last_error.is_some() && game_errors.banner_timer.tick(time.delta()).finished() {
+        game_errors.last_error = None;
+    }
+    let message = match &game_errors.last_error {
+        Some(error) if error.calls_round_off() => format!("Error: {error}, the round was called off"),
+        Some(error) => format!("Error: {error}"),
+        None => String::new(),
+    };
+    for (text_component, mut text) in text_query.iter_mut() {
+        if matches!(text_component, TextComponents::ErrorBanner) && text.sections[0].value != message {
+            text.sections[0].value = message.clone();
+        }
+    }
+}
@@ -0,0 +1,221 @@
+///events module holds the custom events that systems send to each other during a match. RoundStarted, CardDealt,
+/// ActionTaken, DealerRevealed, HandSettled and ShoeShuffled are sent by BlackjackCorePlugin as the round is played: the
+/// table is drawn from them, and an app embedding the game, the statistics or the sounds listen to them the same way
+/// instead of reading the hands off the table
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use super::components::{Card, DealerHand, PlayerHand};
+use super::hand_history::HandAction;
+use super::traits::Scorable;
+
+///enum RoundOutcome is the result of a settled round from the player's point of view
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RoundOutcome {
+    ///a win with a blackjack, paid 3 to 2
+    Blackjack,
+    Win,
+    Loss,
+    Push,
+}
+
+impl RoundOutcome {
+    ///net returns what a wager wins or loses with this outcome, a blackjack paying 3 to 2
+    pub fn net(&self, wager: i32) -> i32 {
+        match self {
+            RoundOutcome::Blackjack => wager * 3 / 2,
+            RoundOutcome::Win => wager,
+            RoundOutcome::Loss => -wager,
+            RoundOutcome::Push => 0,
+        }
+    }
+}
+
+///struct / event RoundStarted is sent when the cards of a round are about to be dealt
+#[derive(Event, Debug, Clone, PartialEq)]
+pub struct RoundStarted {
+    ///seats dealt in, in the order they are dealt
+    pub seats: Vec<usize>,
+    ///how many cards were dealt from the shoe before this round
+    pub shoe_position: usize,
+}
+
+///enum CardHolder is who a card is dealt to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CardHolder {
+    Seat(usize),
+    Dealer,
+}
+
+///struct / event CardDealt is sent for every card dealt from the shoe, in the order they are dealt
+#[derive(Event, Debug, Clone, PartialEq)]
+pub struct CardDealt {
+    pub holder: CardHolder,
+    ///index of the hand among the hands of the seat, 0 for the dealer
+    pub hand: usize,
+    ///index of the card in the hand
+    pub index: usize,
+    pub card: Card,
+    ///false for the dealer's hole card, until DealerRevealed
+    pub face_up: bool,
+    ///shuffle_count of the shoe the card was dealt from, none for a card given from the developer console
+    pub shuffle: Option<u32>,
+}
+
+///struct / event ActionTaken is sent for every action played on a hand, once it was checked. the card is the one drawn by
+/// a hit or a double down
+#[derive(Event, Debug, Clone, PartialEq)]
+pub struct ActionTaken {
+    pub seat: usize,
+    pub hand: usize,
+    pub action: HandAction,
+    pub card: Option<Card>,
+    ///whether a companion played it
+    pub companion: bool,
+}
+
+///struct / event DealerRevealed is sent when the dealer turns the hole card over, once the seats have played
+#[derive(Event, Debug, Clone, PartialEq)]
+pub struct DealerRevealed {
+    pub hole_card: Card,
+    ///best total of the dealer's two cards
+    pub total: u8,
+}
+
+///struct / event ShoeShuffled is sent each time the shoe runs out and is reshuffled in the middle of a deal
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShoeShuffled {
+    pub seed: u64,
+    ///how many times the shoe was shuffled with this seed
+    pub shuffle_count: u32,
+}
+
+///struct / event HandSettled is sent for every hand once a round has been paid out (or lost), and is what the statistics collector
+/// listens to
+#[derive(Event, Debug, Clone)]
+pub struct HandSettled {
+    ///seat whose hand was settled
+    pub seat: usize,
+    ///index of the hand among the hands of the seat
+    pub hand: usize,
+    pub outcome: RoundOutcome,
+    ///total amount wagered on the round, including any double down
+    pub wager: i32,
+    ///amount won (positive) or lost (negative) on the round
+    pub net: i32,
+    pub player_blackjack: bool,
+    ///best total of the player's first two cards
+    pub starting_total: u8,
+    ///value of the dealer's face up card, aces count as 11
+    pub dealer_up_card: u8,
+    ///whether a companion played the hand, the player's statistics and history leave those out
+    pub companion: bool,
+    ///best total of the player's hand at the end of the round
+    pub player_total: u8,
+    ///best total of the dealer's hand at the end of the round
+    pub dealer_total: u8,
+}
+
+impl HandSettled {
+    ///payout returns the amount handed back to the player, the wager included
+    pub fn payout(&self) -> i32 {
+        self.wager + self.net
+    }
+
+
+    ///new builds a HandSettled event for the first hand of a seat of the player's from the hands as they are at the end of
+    /// the round, wager being the total bet including any double down
+    pub fn new(seat: usize, outcome: RoundOutcome, wager: i32, player_hand: &PlayerHand, dealer_hand: &DealerHand) -> Self {
+        let starting_hand = PlayerHand {
+            cards: player_hand.cards.iter().take(2).cloned().collect(),
+            bet: player_hand.bet,
+        };
+        let net = outcome.net(wager);
+        Self {
+            seat,
+            hand: 0,
+            outcome,
+            wager,
+            net,
+            player_blackjack: starting_hand.is_blackjack(),
+            starting_total: starting_hand.best_total(),
+            //dealer card 0 is the hole card, card 1 is the one dealt face up
+            dealer_up_card: dealer_hand.cards.get(1).map_or(0, |card| card.value.1),
+            companion: false,
+            player_total: player_hand.best_total(),
+            dealer_total: dealer_hand.best_total(),
+        }
+    }
+}
+
+///enum ActionKind lists what can be done with a hand, not every table offers all of them, along with dealing the next
+/// round and clearing a settled one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActionKind {
+    Hit,
+    Stand,
+    Double,
+    Split,
+    Surrender,
+    ///not implemented, no insurance is offered and the action is always turned down
+    Insurance,
+    ///deals the round once the bets are placed
+    Deal,
+    ///clears a settled round for the next bets
+    KeepPlaying,
+}
+
+impl ActionKind {
+    ///hand_action returns the action as written in the hand history, none for the actions this table does not offer
+    pub fn hand_action(&self) -> Option<HandAction> {
+        match self {
+            ActionKind::Hit => Some(HandAction::Hit),
+            ActionKind::Stand => Some(HandAction::Stand),
+            ActionKind::Double => Some(HandAction::DoubleDown),
+            ActionKind::Split | ActionKind::Surrender | ActionKind::Insurance => None,
+            ActionKind::Deal | ActionKind::KeepPlaying => None,
+        }
+    }
+
+    ///label returns the name of the action as shown on screen
+    pub fn label(&self) -> &'static str {
+        match self {
+            ActionKind::Hit => "Hit",
+            ActionKind::Stand => "Stand",
+            ActionKind::Double => "Double Down",
+            ActionKind::Split => "Split",
+            ActionKind::Surrender => "Surrender",
+            ActionKind::Insurance => "Insurance",
+            ActionKind::Deal => "Deal",
+            ActionKind::KeepPlaying => "Keep Playing",
+        }
+    }
+}
+
+impl From<HandAction> for ActionKind {
+    fn from(action: HandAction) -> Self {
+        match action {
+            HandAction::Hit => ActionKind::Hit,
+            HandAction::Stand => ActionKind::Stand,
+            HandAction::DoubleDown => ActionKind::Double,
+        }
+    }
+}
+
+///struct / event PlayerAction is an action on one of the hands of the seat whose turn it is, or the deal or keep playing
+/// of the table. the buttons, the keyboard, gamepads, bots, autoplay and the spectator all send it, and apply_player_actions
+/// checks it before playing it
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PlayerAction {
+    pub kind: ActionKind,
+    ///index of the hand among the hands of the seat, 0 until a hand is split
+    pub hand: usize,
+}
+
+impl PlayerAction {
+    ///new builds an action on the first hand of the seat
+    pub fn new(kind: ActionKind) -> Self {
+        Self { kind, hand: 0 }
+    }
+}
@@ -0,0 +1,317 @@
+///hand history module records every round as it is played and appends it as one json line to the hand history file
+/// of the active profile once the round is settled, so results can be disputed or analysed afterwards.
+
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use super::components::{Card, Deck};
+use super::constants::GameRoundState;
+use super::events::{CardDealt, HandSettled, RoundOutcome};
+use super::resources::{ActiveProfile, BalanceValue};
+use super::rules::Rules;
+use super::storage::DataDir;
+
+///enum HandAction lists the actions a player can take on a hand, as they are written in the hand history
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HandAction {
+    Hit,
+    Stand,
+    DoubleDown,
+}
+
+impl HandAction {
+    ///label returns the name of the action as shown on screen
+    pub fn label(&self) -> &'static str {
+        match self {
+            HandAction::Hit => "Hit",
+            HandAction::Stand => "Stand",
+            HandAction::DoubleDown => "Double Down",
+        }
+    }
+}
+
+///struct ActionRecord is a single player action along with the card it drew, if any
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActionRecord {
+    pub action: HandAction,
+    pub card: Option<String>,
+}
+
+///struct SeatRecord is the part of a round played on one seat of the table
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SeatRecord {
+    pub seat: usize,
+    pub player_cards: Vec<String>,
+    pub bet: i32,
+    pub actions: Vec<ActionRecord>,
+    pub outcome: Option<RoundOutcome>,
+    ///total amount wagered, including any double down
+    pub wager: i32,
+    ///amount handed back to the player, the wager included
+    pub payout: i32,
+}
+
+///struct DealtCard is a card dealt from the shoe, along with the shuffle of the shoe it was dealt from
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DealtCard {
+    pub card: String,
+    pub shuffle_count: u32,
+}
+
+///HAND_RECORD_VERSION is written in every hand record, and bumped whenever a record changes in a way older lines cannot
+/// be read as
+pub const HAND_RECORD_VERSION: u32 = 2;
+
+///struct HandRecord is one line of the hand history file, cards are written using Card::code (e.g. "AS", "10H")
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HandRecord {
+    ///format of the record, the lines written before the table had seats have none and are read as version 0
+    #[serde(default)]
+    pub version: u32,
+    pub round_id: String,
+    ///seconds since the unix epoch when the round was dealt
+    pub timestamp: u64,
+    pub seed: u64,
+    ///number of times the deck had been shuffled before the round was dealt
+    pub shuffle_count: u32,
+    ///index of the last card dealt before the round was dealt
+    pub shoe_position: usize,
+    pub rules: Rules,
+    ///number of seats at the table when the round was dealt
+    pub table_seats: usize,
+    pub starting_balance: i32,
+    ///every seat dealt in, in table order
+    pub seats: Vec<SeatRecord>,
+    ///the dealer's first card is the hole card, the second is the up card
+    pub dealer_cards: Vec<String>,
+    pub dealer_draws: Vec<String>,
+    ///every card dealt from the shoe in the round, the companions' included, in the order it was dealt. a reshuffle in the
+    /// middle of the round shows as the shuffle count going up. lines of version 1 have none
+    #[serde(default)]
+    pub dealt: Vec<DealtCard>,
+    pub balance: i32,
+}
+
+///struct / resource HandHistory holds the record of the round currently being played
+#[derive(Resource)]
+pub struct HandHistory {
+    session_id: u64,
+    rounds_dealt: u64,
+    pub current: Option<HandRecord>,
+}
+
+impl Default for HandHistory {
+    fn default() -> Self {
+        Self {
+            session_id: unix_time().as_millis() as u64,
+            rounds_dealt: 0,
+            current: None,
+        }
+    }
+}
+
+impl HandHistory {
+    ///begin_round starts a new record for a freshly dealt round, shoe_position and shuffle_count being where the deck was
+    /// before dealing and seats holding the seat index, dealt cards and bet of every seat dealt in
+    #[allow(clippy::too_many_arguments)]
+    pub fn begin_round(
+        &mut self,
+        deck: &Deck,
+        shoe_position: usize,
+        shuffle_count: u32,
+        rules: &Rules,
+        table_seats: usize,
+        balance: i32,
+        dealer_cards: &[Card],
+        seats: &[(usize, Vec<Card>, i32)],
+    ) {
+        self.rounds_dealt += 1;
+        self.current = Some(HandRecord {
+            version: HAND_RECORD_VERSION,
+            round_id: format!("{}-{}", self.session_id, self.rounds_dealt),
+            timestamp: unix_time().as_secs(),
+            seed: deck.seed,
+            shuffle_count,
+            shoe_position,
+            rules: rules.clone(),
+            table_seats,
+            starting_balance: balance,
+            seats: seats.iter().map(|(seat, player_cards, bet)| SeatRecord {
+                seat: *seat,
+                player_cards: player_cards.iter().map(Card::code).collect(),
+                bet: *bet,
+                actions: Vec::new(),
+                outcome: None,
+                wager: 0,
+                payout: 0,
+            }).collect(),
+            dealer_cards: dealer_cards.iter().map(Card::code).collect(),
+            dealer_draws: Vec::new(),
+            dealt: Vec::new(),
+            balance,
+        });
+    }
+
+    ///record_action adds a player action taken on a seat to the current round, along with the card it drew
+    pub fn record_action(&mut self, seat: usize, action: HandAction, card: Option<&Card>) {
+        if let Some(seat_record) = self.seat_record(seat) {
+            seat_record.actions.push(ActionRecord { action, card: card.map(Card::code) });
+        }
+    }
+
+    fn seat_record(&mut self, seat: usize) -> Option<&mut SeatRecord> {
+        self.current.as_mut()?.seats.iter_mut().find(|seat_record| seat_record.seat == seat)
+    }
+
+    ///record_dealt adds a card dealt from the shoe to the current round, shuffle_count being the shuffle it was dealt from
+    pub fn record_dealt(&mut self, card: &Card, shuffle_count: u32) {
+        if let Some(record) = &mut self.current {
+            record.dealt.push(DealtCard { card: card.code(), shuffle_count });
+        }
+    }
+
+    ///record_settled fills in the result of a seat of the player's in the current round
+    pub fn record_settled(&mut self, settled: &HandSettled) {
+        if let Some(seat_record) = self.seat_record(settled.seat) {
+            seat_record.outcome = Some(settled.outcome);
+            seat_record.wager = settled.wager;
+            seat_record.payout = settled.payout();
+        }
+    }
+
+    ///finish_round appends the current round to the hand history file at path once every seat of it has been settled,
+    /// balance being the player's balance after the round
+    pub fn finish_round(&mut self, balance: i32, path: &Path) {
+        let all_settled = self.current.as_ref()
+            .is_some_and(|record| record.seats.iter().all(|seat_record| seat_record.outcome.is_some()));
+        if !all_settled {
+            return;
+        }
+        let Some(mut record) = self.current.take() else {
+            return;
+        };
+        record.balance = balance;
+
+        if let Err(error) = append_hand_record(path, &record) {
+            warn!("Could not write hand history for round {}: {error}", record.round_id);
+        }
+    }
+
+    ///record_dealer_draw adds a card drawn by the dealer to the current round
+    pub fn record_dealer_draw(&mut self, card: &Card) {
+        if let Some(record) = &mut self.current {
+            record.dealer_draws.push(card.code());
+        }
+    }
+}
+
+//HandRecordV0 is a line of version 0, written before the table had seats: the one seat's cards, bet, actions and result
+//were on the record itself
+#[derive(Deserialize)]
+struct HandRecordV0 {
+    round_id: String,
+    timestamp: u64,
+    seed: u64,
+    shuffle_count: u32,
+    shoe_position: usize,
+    rules: Rules,
+    starting_balance: i32,
+    player_cards: Vec<String>,
+    dealer_cards: Vec<String>,
+    bet: i32,
+    actions: Vec<ActionRecord>,
+    dealer_draws: Vec<String>,
+    outcome: Option<RoundOutcome>,
+    wager: i32,
+    payout: i32,
+    balance: i32,
+}
+
+impl From<HandRecordV0> for HandRecord {
+    fn from(record: HandRecordV0) -> Self {
+        HandRecord {
+            version: HAND_RECORD_VERSION,
+            round_id: record.round_id,
+            timestamp: record.timestamp,
+            seed: record.seed,
+            shuffle_count: record.shuffle_count,
+            shoe_position: record.shoe_position,
+            rules: record.rules,
+            table_seats: 1,
+            starting_balance: record.starting_balance,
+            seats: vec![SeatRecord {
+                seat: 0,
+                player_cards: record.player_cards,
+                bet: record.bet,
+                actions: record.actions,
+                outcome: record.outcome,
+                wager: record.wager,
+                payout: record.payout,
+            }],
+            dealer_cards: record.dealer_cards,
+            dealer_draws: record.dealer_draws,
+            dealt: Vec::new(),
+            balance: record.balance,
+        }
+    }
+}
+
+///parse_hand_record reads one line of the hand history file, the lines of an older version are brought up to the current one
+pub fn parse_hand_record(line: &str) -> serde_json::Result<HandRecord> {
+    let value: serde_json::Value = serde_json::from_str(line)?;
+    match value.get("version").and_then(serde_json::Value::as_u64) {
+        None | Some(0) => serde_json::from_value::<HandRecordV0>(value).map(HandRecord::from),
+        //a line of version 1 only lacks the dealt cards
+        Some(_) => serde_json::from_value(value).map(|record| HandRecord { version: HAND_RECORD_VERSION, ..record }),
+    }
+}
+
+fn unix_time() -> std::time::Duration {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default()
+}
+
+///hand_history_path returns the hand history file of a profile
+pub fn hand_history_path(data_dir: &DataDir, profile: &str) -> PathBuf {
+    data_dir.profile_dir(profile).join("hand_history.jsonl")
+}
+
+///append_hand_record writes a record as a single line at the end of a hand history file
+pub fn append_hand_record(path: &Path, record: &HandRecord) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let line = serde_json::to_string(record)
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{line}")
+}
+
+///write_hand_history fills in the cards dealt from the shoe and each seat of the current record as it is settled, and
+/// appends the record to the hand history file once every seat has been settled and the dealer has played, a seat that
+/// went bust being settled first
+pub fn write_hand_history(
+    mut cards_dealt: EventReader<CardDealt>,
+    mut settled_rounds: EventReader<HandSettled>,
+    mut hand_history: ResMut<HandHistory>,
+    game_state: Res<State<GameRoundState>>,
+    balance: Res<BalanceValue>,
+    data_dir: Res<DataDir>,
+    profile: Res<ActiveProfile>,
+) {
+    for card_dealt in cards_dealt.read() {
+        if let Some(shuffle_count) = card_dealt.shuffle {
+            hand_history.record_dealt(&card_dealt.card, shuffle_count);
+        }
+    }
+    for round in settled_rounds.read().filter(|round| !round.companion) {
+        hand_history.record_settled(round);
+    }
+
+    if matches!(game_state.get(), GameRoundState::Settlement | GameRoundState::RoundEnd) {
+        hand_history.finish_round(balance.value, &hand_history_path(&data_dir, &profile.0));
+    }
+}
@@ -1,37 +1,29 @@
 ///in game systems module is used and responsible for creation and handling of UI components such as the buttons, cards, text elements, etc
 
+use std::path::PathBuf;
 use bevy::prelude::*;
-use super::{components::{Card, ChipButtonValue, DealerHand, InGameCardAccess, PlayerBalance, PlayerButtonValues, PlayerHand, PlayerHands, TextComponents}, constants::{AppState, GameRoundState, CARD_HORIZONTAL_SPACING, CARD_VERTICAL_SPACING, DEALER_CARDS_INITIAL_HORIZONTAL_POSITION, DEALER_CARDS_INITIAL_VERTICAL_POSITION, PLAYER_CARDS_INITIAL_HORIZONTAL_POSITION, PLAYER_CARDS_INITIAL_VERTICAL_POSITION}, player_systems::{double_down_player_hand, hit_player_hand, stand_player_hand}, resources::{BalanceValue, BetValue, ParentNode}};
+use super::companions::companion_label;
+use super::dealer_systems::dealer_up_value;
+use super::scripting::CompanionScripts;
+use super::pause_menu_systems::LeaveTable;
+use super::round_state::RoundTransition;
+use super::settings::Settings;
+use super::storage::{load_json, save_json, DataDir};
+use super::strategy::basic_strategy;
+use super::traits::Scorable;
+use super::errors::GameError;
+use super::events::{CardDealt, CardHolder, DealerRevealed, HandSettled, RoundOutcome};
+use super::{components::{Card, ChipButtonValue, Companion, DealerHand, InGameCardAccess, Localized, PlayerButtonValues, PlayerHand, PlayerHands, Seat, SeatMarker, SeatMarkerText, SeatStatus, TextComponents}, constants::{AppState, GameRoundState, PauseState, CARD_HORIZONTAL_SPACING, CARD_VERTICAL_SPACING, DEALER_CARDS_INITIAL_HORIZONTAL_POSITION, DEALER_CARDS_INITIAL_VERTICAL_POSITION, MAX_SEATS, PLAYER_CARDS_INITIAL_HORIZONTAL_POSITION, PLAYER_CARDS_INITIAL_VERTICAL_POSITION, SEAT_ARC_DEPTH, SEAT_ARC_LEFT, SEAT_ARC_RIGHT, SEAT_ARC_TOP}, resources::{ActiveProfile, ActiveSeat, BalanceValue, BetValue, LastSession, LocalSpectator, ParentNode, StartingBalance, TableConfig}, rules::Rules};
 
 /// in_game_setup is the function used for setting up the base of our game once the start screen is bypassed.
-/// We use it to spawn the player and dealer hands, as well as spawn the parent entity that all of our UI 
-/// components are attached to. Finally, the buttons, chip buttons, text, and cards are spawned here with the help
-/// of helper functions.
+/// We use it to spawn the parent entity that all of our UI components are attached to. The buttons, chip buttons, 
+/// text, and seat markers are spawned here with the help of helper functions. Cards are spawned once they are dealt.
 pub fn in_game_setup(
     mut commands: Commands,
     assets: Res<AssetServer>,
     mut parent_node: ResMut<ParentNode>,
-    player_hands: Query<&PlayerHands>, 
-    dealer_hands: Query<&DealerHand>,
+    table: Res<TableConfig>,
     ) {
-    
-    // Safely fetch player hand
-    let player_hand = match player_hands.get_single() {
-        Ok(player_hands) => &player_hands.0[0],
-        Err(_) => {
-            println!("No player hands found, aborting setup");
-            return;
-        }
-    };
-
-    // Safely fetch dealer hand
-    let dealer_hand = match dealer_hands.get_single() {
-        Ok(dealer_hand) => dealer_hand,
-        Err(_) => {
-            println!("No dealer hands found, aborting setup");
-            return;
-        }
-    };
 
     // Spawn UI parent entity, all children will be built off of this
     let parent_entity = commands.spawn(NodeBundle {
@@ -51,7 +43,7 @@ pub fn in_game_setup(
 
         spawn_text_fields(parent, &assets);
 
-        spawn_cards(parent, &assets, player_hand, dealer_hand);
+        spawn_seat_markers(parent, &assets, table.seats);
     })
     .id();
     parent_node.0 = parent_entity;
@@ -158,6 +150,7 @@ fn spawn_buttons(parent: &mut ChildBuilder, assets: &Res<AssetServer>) {
         (Vec2::new(205.0, 350.0), "Double Down", 15.0, PlayerButtonValues::DoubleDown),
         (Vec2::new(305.0, 350.0), "Deal", 30.0, PlayerButtonValues::Deal),
         (Vec2::new(690.0, 10.0), "Home", 15.0, PlayerButtonValues::Home),
+        (Vec2::new(590.0, 10.0), "Autoplay", 15.0, PlayerButtonValues::Autoplay),
     ];
 
     //build the buttons listed above ^
@@ -182,20 +175,23 @@ fn spawn_buttons(parent: &mut ChildBuilder, assets: &Res<AssetServer>) {
 
         //hiding and ensuring visibility for necessary buttons
         button_bundle.visibility = match button_value {
-            PlayerButtonValues::Home => Visibility::Visible,
+            PlayerButtonValues::Home | PlayerButtonValues::Autoplay => Visibility::Visible,
             _ => Visibility::Hidden,
         };
 
         parent.spawn(button_bundle)
             .insert(button_value)
             .with_children(|parent| {
-                parent.spawn(TextBundle::from_section(
-                    label,
-                    TextStyle {
-                        font: assets.load("fonts/FiraSans-SemiBold.ttf"),
-                        font_size,
-                        color: Color::srgb(0.9, 0.9, 0.9),
-                    },
+                parent.spawn((
+                    TextBundle::from_section(
+                        label,
+                        TextStyle {
+                            font: assets.load("fonts/FiraSans-SemiBold.ttf"),
+                            font_size,
+                            color: Color::srgb(0.9, 0.9, 0.9),
+                        },
+                    ),
+                    Localized(label),
                 ));
             });
     }
@@ -219,6 +215,10 @@ fn spawn_text_fields(parent: &mut ChildBuilder, assets: &Res<AssetServer>) {
     spawn_text(parent, &assets, Vec2::new(255.0, 15.0), "x", 30.0, TextComponents::Balance);
     spawn_text(parent, &assets, Vec2::new(415.0, 15.0), "Dealer", 30.0, TextComponents::NotChanged);
     spawn_text(parent, &assets, Vec2::new(40.0, 200.0), "Please place a bet then hit deal", 30.0, TextComponents::Instruction);
+    spawn_text(parent, &assets, Vec2::new(15.0, 475.0), "", 14.0, TextComponents::ScriptError);
+    spawn_text(parent, &assets, Vec2::new(410.0, 420.0), "", 14.0, TextComponents::Autoplay);
+    spawn_text(parent, &assets, Vec2::new(15.0, 55.0), "", 20.0, TextComponents::ErrorBanner);
+    spawn_text(parent, &assets, Vec2::new(410.0, 300.0), "", 16.0, TextComponents::Hint);
 }
 
 ///spawn_result_text is uses to spawn win / loss statements once the round ends
@@ -242,6 +242,7 @@ pub fn spawn_player_card(
     parent: &mut ChildBuilder,
     assets: &Res<AssetServer>,
     card: &Card,
+    seat: Seat,
     card_index: usize,
     card_position: Vec2,
     is_visible: bool
@@ -266,22 +267,28 @@ pub fn spawn_player_card(
         },
         ..default()
     })
-    .insert(InGameCardAccess::PlayerCard(card_index));
+    .insert((InGameCardAccess::PlayerCard(card_index), seat));
 }
 
-///spawn_player_cards utilizes spawn_player_card to go through the players hand and spawn cards in the hand
-fn spawn_player_cards(parent: &mut ChildBuilder, assets: &Res<AssetServer>, player_hand: &PlayerHand) {
-    for (i, card) in player_hand.cards.iter().enumerate() {
-        spawn_player_card(
-            parent,
-            assets,
-            card,
-            i,
-            Vec2 {
-                x: PLAYER_CARDS_INITIAL_HORIZONTAL_POSITION + (i as f32)*CARD_HORIZONTAL_SPACING,
-                y: PLAYER_CARDS_INITIAL_VERTICAL_POSITION  + (i as f32)*CARD_VERTICAL_SPACING}, 
-            false
-        );
+///seat_position returns where the first card of a seat goes. a single seat keeps the original player position,
+/// otherwise the seats are spread along an arc from first base (seat 0) on the right to third base on the left.
+pub fn seat_position(seat: usize, seats: usize) -> Vec2 {
+    if seats <= 1 {
+        return Vec2::new(PLAYER_CARDS_INITIAL_HORIZONTAL_POSITION, PLAYER_CARDS_INITIAL_VERTICAL_POSITION);
+    }
+    let t = seat as f32 / (seats - 1) as f32;
+    Vec2 {
+        x: SEAT_ARC_RIGHT - t * (SEAT_ARC_RIGHT - SEAT_ARC_LEFT),
+        y: SEAT_ARC_TOP + SEAT_ARC_DEPTH * (std::f32::consts::PI * t).sin(),
+    }
+}
+
+///seat_card_position returns where a card of a seat goes, each card being stacked down and to the right of the last one
+pub fn seat_card_position(seat: usize, seats: usize, card_index: usize) -> Vec2 {
+    let position = seat_position(seat, seats);
+    Vec2 {
+        x: position.x + (card_index as f32)*CARD_HORIZONTAL_SPACING,
+        y: position.y + (card_index as f32)*CARD_VERTICAL_SPACING,
     }
 }
 
@@ -314,43 +321,6 @@ pub fn spawn_dealer_card(
     .insert(InGameCardAccess::DealerCard(card_index));
 }
 
-///spawn_dealer_cards utilizes spawn_dealer_card to go through the dealers hand and spawn cards in the hand
-fn spawn_dealer_cards(parent: &mut ChildBuilder, assets: &Res<AssetServer>, dealer_hand: &DealerHand) {
-    for (i, card) in dealer_hand.cards.iter().enumerate() {
-            if i == 0 {
-                spawn_dealer_card(
-                    parent, 
-                    assets,
-                    card,
-                    i,
-                    Vec2 {
-                        x: DEALER_CARDS_INITIAL_HORIZONTAL_POSITION + (i as f32)*CARD_HORIZONTAL_SPACING,
-                        y: DEALER_CARDS_INITIAL_VERTICAL_POSITION + (i as f32)*CARD_VERTICAL_SPACING }, 
-                    false,
-                    false
-                );
-            }
-            else {
-                spawn_dealer_card(
-                    parent,
-                    assets,
-                    card,
-                    i,
-                    Vec2 { 
-                        x: DEALER_CARDS_INITIAL_HORIZONTAL_POSITION + (i as f32)*CARD_HORIZONTAL_SPACING,
-                        y: DEALER_CARDS_INITIAL_VERTICAL_POSITION  + (i as f32)*CARD_VERTICAL_SPACING},
-                    true,
-                false);
-            }
-    }
-}
-
-///spawn_cards calls the spawn_player_cards and spawn_dealer_cards functions at once to spawn the appropriate cards in the appropriate places
-fn spawn_cards(parent: &mut ChildBuilder, assets: &Res<AssetServer>, player_hand: &PlayerHand, dealer_hand: &DealerHand) {
-    spawn_player_cards(parent, assets, player_hand);
-    spawn_dealer_cards(parent, assets, dealer_hand);    
-}
-
 ///spawn_keep_playing_button is used to spawn a button that allows the user to reset the match and keep playing once a round ends
 pub fn spawn_keep_playing_button(
     parent: &mut ChildBuilder,
@@ -374,53 +344,206 @@ pub fn spawn_keep_playing_button(
         ..default()
     })
     .with_children(|parent| {
-        parent.spawn(TextBundle::from_section(
-            "Keep Playing",
-            TextStyle {
-                font: assets.load("fonts/FiraSans-SemiBold.ttf"),
-                font_size: 15.0,
-                color: Color::WHITE,
-            },
+        parent.spawn((
+            TextBundle::from_section(
+                "Keep Playing",
+                TextStyle {
+                    font: assets.load("fonts/FiraSans-SemiBold.ttf"),
+                    font_size: 15.0,
+                    color: Color::WHITE,
+                },
+            ),
+            Localized("Keep Playing"),
         ));
     })
     .insert(PlayerButtonValues::KeepPlaying); 
 }
 
+///spawn_seat_markers spawns a small button above every seat showing its bet, clicking one selects the seat for betting.
+/// they are only spawned when there is more than one seat, a single seat is simply the player's hand.
+pub fn spawn_seat_markers(parent: &mut ChildBuilder, assets: &Res<AssetServer>, seats: usize) {
+    if seats <= 1 {
+        return;
+    }
+    for seat in 0..seats {
+        let position = seat_position(seat, seats);
+        parent.spawn(ButtonBundle {
+            style: Style {
+                width: Val::Px(90.0),
+                height: Val::Px(22.0),
+                position_type: PositionType::Absolute,
+                left: Val::Px(position.x),
+                top: Val::Px(position.y - 26.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            background_color: BackgroundColor(Color::srgb(0.15, 0.15, 0.15)),
+            ..default()
+        })
+        .insert(SeatMarker(seat))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "",
+                TextStyle {
+                    font: assets.load("fonts/FiraSans-SemiBold.ttf"),
+                    font_size: 14.0,
+                    color: Color::WHITE,
+                },
+            ))
+            .insert(SeatMarkerText(seat));
+        });
+    }
+}
+
 /// print_all_dealer_cards is a function available for testing to print the contents of the cards the dealer has in hand
 pub fn print_all_dealer_cards(
     dealer_hand_query: Query<&DealerHand>,
 ) {
     for dealer_hand in dealer_hand_query.iter() {
-        println!("Dealer has {} cards:", dealer_hand.cards.len());
+        debug!("Dealer has {} cards:", dealer_hand.cards.len());
         for (i, card) in dealer_hand.cards.iter().enumerate() {
-            println!("Dealer Card {}: {} of {}", i, card.face, card.suite);   
+            debug!("Dealer Card {}: {} of {}", i, card.face, card.suite);   
         }
     }
 }
 
+//table event functions below -----------------------
+
+///draw_dealt_cards spawns every card dealt since the last frame on the table, the dealer's hole card face down
+pub fn draw_dealt_cards(
+    mut commands: Commands,
+    assets: Res<AssetServer>,
+    parent_node: Res<ParentNode>,
+    table: Res<TableConfig>,
+    mut card_dealt: EventReader<CardDealt>,
+) {
+    if card_dealt.is_empty() {
+        return;
+    }
+    commands.entity(parent_node.0).with_children(|parent|{
+        for dealt in card_dealt.read() {
+            match dealt.holder {
+                CardHolder::Seat(seat) => {
+                    spawn_player_card(parent, &assets, &dealt.card, Seat(seat), dealt.index, seat_card_position(seat, table.seats, dealt.index), true);
+                }
+                CardHolder::Dealer => {
+                    let position = Vec2 {
+                        x: DEALER_CARDS_INITIAL_HORIZONTAL_POSITION + (dealt.index as f32)*CARD_HORIZONTAL_SPACING,
+                        y: DEALER_CARDS_INITIAL_VERTICAL_POSITION + (dealt.index as f32)*CARD_VERTICAL_SPACING};
+                    spawn_dealer_card(parent, &assets, &dealt.card, dealt.index, position, dealt.face_up, true);
+                }
+            }
+        }
+    });
+}
+
+///turn_hole_card shows the front of the dealer's hole card once it is revealed
+pub fn turn_hole_card(
+    assets: Res<AssetServer>,
+    mut dealer_revealed: EventReader<DealerRevealed>,
+    mut dealer_card_image_query: Query<(&InGameCardAccess, &mut UiImage)>,
+) {
+    let Some(revealed) = dealer_revealed.read().last() else {
+        return;
+    };
+    for (card_access, mut ui_image) in dealer_card_image_query.iter_mut() {
+        if let InGameCardAccess::DealerCard(0) = card_access {
+            ui_image.texture = assets.load(revealed.hole_card.front_asset_path.clone());
+        }
+    }
+}
+
+///show_round_result shows the result of a settled round with the keep playing button once the round is over. a single seat
+/// of the player's gets its full result text, several seats show theirs on the seat markers and the net result here. the
+/// hands are kept as they are settled, a bust one being settled before the rest of the table
+pub fn show_round_result(
+    mut commands: Commands,
+    assets: Res<AssetServer>,
+    parent_node: Res<ParentNode>,
+    game_state: Res<State<GameRoundState>>,
+    mut round_settled: Local<Vec<HandSettled>>,
+    mut hand_settled: EventReader<HandSettled>,
+) {
+    round_settled.extend(hand_settled.read().cloned());
+    match game_state.get() {
+        GameRoundState::RoundEnd if !round_settled.is_empty() => {}
+        //a round called off is not shown
+        GameRoundState::RoundStart | GameRoundState::Betting => {
+            round_settled.clear();
+            return;
+        }
+        _ => return,
+    }
+    let mut results: Vec<HandSettled> = round_settled.drain(..).filter(|settled| !settled.companion).collect();
+    let net_result: i32 = results.iter().map(|settled| settled.net).sum();
+    let result = if results.len() == 1 {
+        result_text(&results.remove(0))
+    } else if results.is_empty() {
+        //a watched table has no seat of the player's
+        String::from("Round over")
+    } else if net_result > 0 {
+        format!("You Win ${}!", net_result)
+    } else if net_result < 0 {
+        format!("You Lose ${}!", -net_result)
+    } else {
+        String::from("Push! Bets returned")
+    };
+
+    commands.entity(parent_node.0).with_children(|parent|{
+        spawn_result_text(
+            parent,
+            &assets,
+            &result
+        );
+        spawn_keep_playing_button(parent, &assets);
+    });
+}
+
+///result_text writes the result of a single settled hand, e.g. "You Win $50! (Dealer Bust)"
+pub fn result_text(settled: &HandSettled) -> String {
+    match settled.outcome {
+        RoundOutcome::Loss if settled.player_total > 21 => format!("You Lose ${}! (Bust)", settled.wager),
+        RoundOutcome::Blackjack => format!("Blackjack! You Win ${}!", settled.net),
+        RoundOutcome::Win if settled.dealer_total > 21 => format!("You Win ${}! (Dealer Bust)", settled.wager),
+        RoundOutcome::Win => format!("You Win ${}! ({} to {})", settled.net, settled.player_total, settled.dealer_total),
+        RoundOutcome::Loss => format!("You Lose ${}! ({} to {})", -settled.net, settled.player_total, settled.dealer_total),
+        RoundOutcome::Push => format!("Push! ${} returned", settled.wager),
+    }
+}
+
 //button click functions below -----------------------
 
-/// chip_button_click_system is used for handling chip button clicks, whether its adjusting the player balance or bet being placed
+/// chip_button_click_system is used for handling chip button clicks, whether its adjusting the player balance or bet being placed.
+/// the chips are placed on the active seat, the bet text shows the total bet across every seat.
 pub fn chip_button_click_system(
     mut bet_value: ResMut<BetValue>,
     mut balance_value: ResMut<BalanceValue>,
+    active_seat: Res<ActiveSeat>,
+    mut player_query: Query<(&Seat, &mut PlayerHands, Has<Companion>)>,
     mut interaction_query: Query<(&Button, &mut Interaction, &ChipButtonValue)>,
     mut text_query: Query<(&TextComponents, &mut Text)>,
     mut deal_button_query: Query<(&PlayerButtonValues, &mut Visibility), With<PlayerButtonValues>>,
 
-) {
+) -> Result<(), GameError> {
+
 
     //begin interaction query , if certain chip interacted with, do something related to that chip value
     for (_, mut interaction, value) in interaction_query.iter_mut() {
         match *interaction {
+            Interaction::Pressed if player_query.iter().any(|(seat, _, is_companion)| seat.0 == active_seat.0 && is_companion) => {
+                info!("A companion sits at seat {}, pick another seat to bet on", active_seat.0 + 1);
+                *interaction = Interaction::None;
+            }
             Interaction::Pressed => {
+                let bet_before = bet_value.value;
                 match *value {
                     ChipButtonValue::One => {
                         if balance_value.value >= 1{
                             bet_value.value += 1;
                             balance_value.value -= 1;
                         } else {
-                            println!("Insufficient value for bet of 1");
+                            info!("Insufficient value for bet of 1");
                         }
                     },
                     ChipButtonValue::Five => {
@@ -428,7 +551,7 @@ pub fn chip_button_click_system(
                             bet_value.value += 5;
                             balance_value.value -= 5;
                         } else {
-                            println!("Insufficient value for bet of 5");
+                            info!("Insufficient value for bet of 5");
                         }
                     },
                     ChipButtonValue::Ten => {
@@ -436,7 +559,7 @@ pub fn chip_button_click_system(
                             bet_value.value += 10;
                             balance_value.value -= 10;
                         } else {
-                            println!("Insufficient value for bet of 10");
+                            info!("Insufficient value for bet of 10");
                         }
                     },
                     ChipButtonValue::Fifty => {
@@ -444,12 +567,18 @@ pub fn chip_button_click_system(
                             bet_value.value += 50;
                             balance_value.value -= 50;
                         } else {
-                            println!("Insufficient value for bet of 50");
+                            info!("Insufficient value for bet of 50");
                         }
                     },
                 }
                 *interaction = Interaction::None;
 
+                //whatever was added to the bet goes on the active seat's hand
+                let placed = bet_value.value - bet_before;
+                if let Some((seat, mut player_hands, _)) = player_query.iter_mut().find(|(seat, _, _)| seat.0 == active_seat.0) {
+                    player_hands.0.first_mut().ok_or(GameError::MissingHand(seat.0))?.bet += placed as u64;
+                }
+
                 //query to find the deal button and set it to be visible once chip button is clicked
                 for (button_value, mut visibility) in deal_button_query.iter_mut() {
                     if let PlayerButtonValues::Deal = *button_value {
@@ -477,114 +606,80 @@ pub fn chip_button_click_system(
 
     }
 
+    Ok(())
 }
 
 
-/// player_button_system is used to handle clicks on the player system buttons such as hit, stand, double down, etc.
+/// player_button_system is used to handle clicks on the home button, the other player buttons are sent as PlayerAction by
+/// player_action_buttons. Home leaves the table through LeaveTable, which asks first when a bet is on the table.
 pub fn player_button_system(
-    mut next_state: ResMut<NextState<GameRoundState>>,
-    mut next_app_state: ResMut<NextState<AppState>>,
-    //param set created to allow us to avoid conflict while querying on visibility for multiple components 
-    mut param_set: ParamSet<(
-        Query<(&Button, &mut Interaction, &PlayerButtonValues, &mut Visibility), With<Button>>,
-        Query<(&InGameCardAccess, &mut Visibility)>,       
-        Query<(&TextComponents, &mut Visibility)>,      
-        Query<(&ChipButtonValue, &mut Visibility)>,
-        Query<(&PlayerButtonValues, &mut Visibility)>, 
-
-    )>,    
+    mut leave_table: LeaveTable,
+    mut button_query: Query<(&mut Interaction, &PlayerButtonValues), With<Button>>,
 ) {
-    let mut deal_button_pressed = false;
-    let mut keep_playing_button_pressed = false;
-    
-    //interaction query for the playerbutton values, do something in particular if certain button pressed...
-    for (_, mut interaction, value, _) in param_set.p0().iter_mut() {
-        match *interaction {
-            Interaction::Pressed => {
-                match *value {
-                    PlayerButtonValues::Home => {
-                        println!("Home");
-                        next_app_state.set(AppState::Start);
-                        *interaction = Interaction::None;
-                        
-                    },
-                    PlayerButtonValues::Deal => {
-                        deal_button_pressed = true;
-                        *interaction = Interaction::None;
-                    },   
-                    PlayerButtonValues::KeepPlaying => {
-                        println!("Keep Playing Button Pressed");
-                        keep_playing_button_pressed = true;
-                        *interaction = Interaction::None;
-                    },
-                    _ => {}
-                }
-            }
-            _ => {}
+    for (mut interaction, value) in button_query.iter_mut() {
+        if *interaction == Interaction::Pressed && *value == PlayerButtonValues::Home {
+            debug!("Home");
+            leave_table.request(PauseState::Running);
+            *interaction = Interaction::None;
         }
     }
+}
 
-    //flags used to allow us to break logic for button press outside of the query for loop , as ownership and access is compromised 
-    //if used in the query for loop.
-    if keep_playing_button_pressed {
-
-        //if keep playing button is pressed, we query for necessary components to be reset and visibility restored/revoked
-        for (value, mut visibility) in param_set.p4().iter_mut() {
-            match *value {
-                PlayerButtonValues::KeepPlaying => {
-                    *visibility = Visibility::Hidden;
-                }
-                _ => {}
+///show_dealt_table hides the deal button, the chips and the instructions once the round is dealt, and shows the cards and the
+/// buttons to play the hand with
+pub fn show_dealt_table(
+    //param set created to allow us to avoid conflict while querying on visibility for multiple components
+    mut param_set: ParamSet<(
+        Query<(&PlayerButtonValues, &mut Visibility)>,
+        Query<(&InGameCardAccess, &mut Visibility)>,
+        Query<(&TextComponents, &mut Visibility)>,
+        Query<(&ChipButtonValue, &mut Visibility)>,
+    )>,
+) {
+    for (value, mut visibility) in param_set.p0().iter_mut() {
+        match *value {
+            PlayerButtonValues::Deal => {
+                *visibility = Visibility::Hidden;
             }
-        }
-        
-        for (value, mut visibility) in param_set.p2().iter_mut() {
-            match *value {
-                TextComponents::ResultText => {
-                    *visibility = Visibility::Hidden;
-                }
-                _ => {}
+            PlayerButtonValues::Stand | PlayerButtonValues::Hit | PlayerButtonValues::DoubleDown => {
+                *visibility = Visibility::Visible;
             }
+            _ => {}
         }
+    }
 
-        next_state.set(GameRoundState::Betting);
+    for (_, mut visibility) in param_set.p3().iter_mut() {
+        *visibility = Visibility::Hidden;
     }
 
-    //if deal button pressed, toggle necessary components visibility
-    if deal_button_pressed {
-        for (_, _, value,  mut visibility) in param_set.p0().iter_mut() {
-            match *value {
-                PlayerButtonValues::Deal => {
-                    *visibility = Visibility::Hidden; 
-                }
-                PlayerButtonValues::Stand | PlayerButtonValues::Hit | PlayerButtonValues::DoubleDown => {
-                    *visibility = Visibility::Visible; 
-                }
-                _ => {}
-            }
-        }
+    for (_, mut card_visibility) in param_set.p1().iter_mut() {
+        *card_visibility = Visibility::Visible;
+    }
 
-        for(chip_value, mut visibility) in param_set.p3().iter_mut() {
-            match *chip_value {
-                ChipButtonValue::One | ChipButtonValue::Five | ChipButtonValue::Ten | ChipButtonValue::Fifty => {
-                    *visibility = Visibility::Hidden;
-                }
-            }
+    for (value, mut text_visibility) in param_set.p2().iter_mut() {
+        if let TextComponents::Instruction = *value {
+            *text_visibility = Visibility::Hidden;
         }
+    }
+}
 
-        for (_, mut card_visibility) in param_set.p1().iter_mut() {
-            *card_visibility = Visibility::Visible;
+///hide_round_result hides the keep playing button and the result text of the last round once the bets are taken again
+pub fn hide_round_result(
+    mut param_set: ParamSet<(
+        Query<(&PlayerButtonValues, &mut Visibility)>,
+        Query<(&TextComponents, &mut Visibility)>,
+    )>,
+) {
+    for (value, mut visibility) in param_set.p0().iter_mut() {
+        if let PlayerButtonValues::KeepPlaying = *value {
+            *visibility = Visibility::Hidden;
         }
+    }
 
-        for (value, mut text_visibility) in param_set.p2().iter_mut() {
-            match *value {
-                TextComponents::Instruction => {
-                    *text_visibility = Visibility::Hidden;
-                }
-                _ => {}
-            } 
+    for (value, mut visibility) in param_set.p1().iter_mut() {
+        if let TextComponents::ResultText = *value {
+            *visibility = Visibility::Hidden;
         }
-        next_state.set(GameRoundState::PlayerHand);
     }
 }
 
@@ -592,14 +687,7 @@ pub fn player_button_system(
 
 ///track_game_state used for testing purposes / to track game state as certain actions and turns are finished / started
 pub fn track_game_state(game_state: Res<State<GameRoundState>>){
-    let game_state_string = match game_state.get() {
-        GameRoundState::RoundStart => "Round Start",
-        GameRoundState::Betting => "Betting",
-        GameRoundState::PlayerHand => "Player Hand",
-        GameRoundState::DealerHand => "Dealer Hand",
-        GameRoundState::RoundEnd => "Round End",
-    };
-    println!("Game State: {game_state_string}");
+    info!("Game State: {}", game_state.get().label());
 }
 
 ///track_app_state used for testing purposes / to track app state being in the start menu or ingame UI
@@ -607,71 +695,220 @@ pub fn track_app_state(current_app_state: Res<State<AppState>>) {
     let app_state_string = match current_app_state.get(){
         AppState::Start => "Start",
         AppState::InGame => "In Game",
+        AppState::Stats => "Stats",
+        AppState::Replay => "Replay",
+        AppState::Online => "Online",
+        AppState::Practice => "Practice",
+        AppState::Settings => "Settings",
+        AppState::Rules => "Rules",
     };
-    println!("Current app state: {app_state_string}");
+    info!("Current app state: {app_state_string}");
+}
+
+///last_session_path returns the file the last session of a profile is saved to
+pub fn last_session_path(data_dir: &DataDir, profile: &str) -> PathBuf {
+    data_dir.profile_dir(profile).join("last_session.json")
+}
+
+///load_last_session reads the last session of the active profile when the game starts, so Continue is offered after a
+/// restart
+pub fn load_last_session(data_dir: Res<DataDir>, profile: Res<ActiveProfile>, mut last_session: ResMut<LastSession>) {
+    match load_json::<LastSession>(&last_session_path(&data_dir, &profile.0)) {
+        Ok(Some(saved)) => *last_session = saved,
+        Ok(None) => {}
+        Err(error) => warn!("Could not load the last session of profile {}: {error}", profile.0),
+    }
+}
+
+///save_last_session keeps the balance the player leaves the table with, for Continue on the main menu, and saves it to
+/// the active profile. a table of companions only watched is not kept, nor a table left broke
+pub fn save_last_session(
+    balance_value: Res<BalanceValue>,
+    spectator: Res<LocalSpectator>,
+    data_dir: Res<DataDir>,
+    profile: Res<ActiveProfile>,
+    mut last_session: ResMut<LastSession>,
+) {
+    if spectator.watching {
+        return;
+    }
+    last_session.balance = Some(balance_value.value).filter(|balance| *balance > 0);
+    if let Err(error) = save_json(&last_session_path(&data_dir, &profile.0), &*last_session) {
+        error!("Could not save the last session of profile {}: {error}", profile.0);
+    }
 }
 
-///reset_game is to be used for resetting values, hands, and UI components for when the user finishes a round and wants to play another.
-/// currently it is not fully functional, everything except for the previous player and dealer hands are being reset.
-/// attempted to reset player and dealer hands and did not find success, would love to give it a shot again when time allows.
+///reset_game is used for resetting values, hands, and UI components for when the user goes back to the start screen.
 pub fn reset_game(mut balance_value: ResMut<BalanceValue>, 
+    starting_balance: Res<StartingBalance>,
     mut bet_value: ResMut<BetValue>,
-    mut next_state: ResMut<NextState<GameRoundState>>,
-    // mut dealer_hand_query: Query<&mut DealerHand>,
-    // mut player_hands_query: Query<&mut PlayerHands>,
-    // mut cards_query: Query<Entity, With<InGameCardAccess>> ,
-    // game_round_state: Res<State<GameRoundState>>,
-    // mut commands: Commands,
-    // mut next_app_state: ResMut<NextState<AppState>>,
+    mut round_transition: RoundTransition,
+    mut active_seat: ResMut<ActiveSeat>,
+    mut player_query: Query<(&mut PlayerHands, &mut SeatStatus)>,
 
 ) {
-    balance_value.value = 1000;  
+    balance_value.value = starting_balance.0;
     bet_value.value = 0;         
-    println!("Player balance reset to 1000 and bet reset to 0");
+    info!("Player balance reset to {} and bet reset to 0", starting_balance.0);
 
-    next_state.set(GameRoundState::RoundStart);
-
-    // for mut dealer_hand in dealer_hand_query.iter_mut() {
-    //     dealer_hand.cards.clear();  
-    // }
-
-    // for mut player_hands in player_hands_query.iter_mut() {
-    //     player_hands.0.clear(); 
-    // }
-
-    // for card in cards_query.iter_mut() {
-    //     commands.entity(card).despawn()
-    // }
+    round_transition.request(GameRoundState::RoundStart);
+    clear_seats(&mut active_seat, &mut player_query);
 }
 
-/// despawn_cards_and_reset was another attempt to clear the player and dealer hands in between rounds.
-/// odd behavior when clearing the player and dealer hands in the commented out code below.
+/// despawn_cards_and_reset removes the card entities of the previous round from the screen and clears every seat's hand and bet,
+/// the next hands are dealt once the player hits deal.
 pub fn despawn_cards_and_reset(
     mut commands: Commands,
     cards_query: Query<Entity, With<InGameCardAccess>>,
-    // dealer_query: Query<Entity, With<InGameCardAccess>>,
-    // mut parent_node: ResMut<ParentNode>, 
-    // assets: Res<AssetServer>,
-    mut player_hands: Query<&mut PlayerHands>,
+    mut active_seat: ResMut<ActiveSeat>,
+    mut player_query: Query<(&mut PlayerHands, &mut SeatStatus)>,
     mut dealer_hands: Query<&mut DealerHand>,
     
 ) {
+    for entity in cards_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    clear_seats(&mut active_seat, &mut player_query);
+    for mut dealer_hand in dealer_hands.iter_mut() {
+        dealer_hand.cards.clear();
+    }
+}
 
-    // maybe an array instead of vector for player hand cards?
+///clear_seats empties the hand, bet and result of every seat and hands the turn back to first base
+fn clear_seats(active_seat: &mut ActiveSeat, player_query: &mut Query<(&mut PlayerHands, &mut SeatStatus)>) {
+    active_seat.0 = 0;
+    for (mut player_hands, mut seat_status) in player_query.iter_mut() {
+        player_hands.0 = vec![PlayerHand{
+            bet: 0,
+            cards: vec![],
+        }];
+        seat_status.0 = None;
+    }
+}
 
-    // if let Some(mut player_hands) = player_hands.iter_mut().next() {
-    //     player_hands.0.clear(); 
-    // }
+// seat systems below ------------------------------------
 
-    // if let Some(mut dealer_hands) = dealer_hands.iter_mut().next() {
-    //     dealer_hands.cards.clear(); 
-    // }
+///seat_selection_system lets the player pick which seat chips go on (number keys or clicking a seat marker)
+/// and add or remove seats with the +/- keys, both only while bets are being placed
+pub fn seat_selection_system(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    game_state: Res<State<GameRoundState>>,
+    mut table: ResMut<TableConfig>,
+    mut active_seat: ResMut<ActiveSeat>,
+    mut marker_query: Query<(&mut Interaction, &SeatMarker)>,
+) {
+    if !matches!(game_state.get(), GameRoundState::RoundStart | GameRoundState::Betting) {
+        return;
+    }
+    let number_keys = [KeyCode::Digit1, KeyCode::Digit2, KeyCode::Digit3, KeyCode::Digit4, KeyCode::Digit5, KeyCode::Digit6, KeyCode::Digit7];
+    for (seat, key) in number_keys.iter().enumerate() {
+        if keyboard_input.just_pressed(*key) && seat < table.seats {
+            active_seat.0 = seat;
+        }
+    }
+    for (mut interaction, marker) in marker_query.iter_mut() {
+        if *interaction == Interaction::Pressed {
+            active_seat.0 = marker.0;
+            *interaction = Interaction::None;
+        }
+    }
+    if (keyboard_input.just_pressed(KeyCode::Equal) || keyboard_input.just_pressed(KeyCode::NumpadAdd)) && table.seats < MAX_SEATS {
+        table.seats += 1;
+    }
+    if (keyboard_input.just_pressed(KeyCode::Minus) || keyboard_input.just_pressed(KeyCode::NumpadSubtract)) && table.seats > 1 {
+        table.seats -= 1;
+    }
+}
 
-    for entity in cards_query.iter() {
+///respawn_seat_markers rebuilds the seat markers whenever the number of seats changes
+pub fn respawn_seat_markers(
+    mut commands: Commands,
+    assets: Res<AssetServer>,
+    table: Res<TableConfig>,
+    parent_node: Res<ParentNode>,
+    marker_query: Query<Entity, With<SeatMarker>>,
+) {
+    if !table.is_changed() || table.is_added() || parent_node.0 == Entity::PLACEHOLDER {
+        return;
+    }
+    for entity in marker_query.iter() {
         commands.entity(entity).despawn_recursive();
     }
+    commands.entity(parent_node.0).with_children(|parent| {
+        spawn_seat_markers(parent, &assets, table.seats);
+    });
 }
 
+///update_seat_markers shows each seat's bet (or its result once the round is settled) and highlights the active seat
+pub fn update_seat_markers(
+    active_seat: Res<ActiveSeat>,
+    companion_scripts: Res<CompanionScripts>,
+    player_query: Query<(&Seat, &PlayerHands, &SeatStatus, Option<&Companion>)>,
+    mut marker_query: Query<(&SeatMarker, &mut BackgroundColor)>,
+    mut marker_text_query: Query<(&SeatMarkerText, &mut Text)>,
+) -> Result<(), GameError> {
+
+    for (marker, mut color) in marker_query.iter_mut() {
+        *color = if marker.0 == active_seat.0 {
+            BackgroundColor(Color::srgb(0.6, 0.45, 0.1))
+        } else {
+            BackgroundColor(Color::srgb(0.15, 0.15, 0.15))
+        };
+    }
+    for (marker_text, mut text) in marker_text_query.iter_mut() {
+        let Some((seat, player_hands, seat_status, companion)) = player_query.iter().find(|(seat, _, _, _)| seat.0 == marker_text.0) else {
+            continue;
+        };
+        let bet = player_hands.0.first().ok_or(GameError::MissingHand(seat.0))?.bet;
+        text.sections[0].value = match (&seat_status.0, companion) {
+            (Some(status), _) => format!("{}: {}", seat.0 + 1, status),
+            (None, Some(companion)) if bet == 0 => format!("{} ${}", companion_label(companion, &companion_scripts), companion.bankroll),
+            (None, Some(companion)) => format!("{}: ${}", companion_label(companion, &companion_scripts), bet),
+            (None, None) => format!("Seat {}: ${}", seat.0 + 1, bet),
+        };
+    }
+    Ok(())
+}
+
+///show_script_errors shows the last error a companion's script ran into at the bottom of the table
+pub fn show_script_errors(companion_scripts: Res<CompanionScripts>, mut text_query: Query<(&TextComponents, &mut Text)>) {
+    let message = companion_scripts.last_error.as_ref().map_or_else(String::new, |error| format!("Script error in {error}"));
+    for (text_component, mut text) in text_query.iter_mut() {
+        if matches!(text_component, TextComponents::ScriptError) && text.sections[0].value != message {
+            text.sections[0].value = message.clone();
+        }
+    }
+}
+
+///show_strategy_hint shows what basic strategy plays with the player's hand against the dealer's up card while it is being
+/// played, as long as hints are on
+pub fn show_strategy_hint(
+    settings: Res<Settings>,
+    rules: Res<Rules>,
+    game_state: Res<State<GameRoundState>>,
+    active_seat: Res<ActiveSeat>,
+    player_query: Query<(&Seat, &PlayerHands), Without<Companion>>,
+    dealer_query: Query<&DealerHand>,
+    mut text_query: Query<(&TextComponents, &mut Text)>,
+) {
+    let up_card = dealer_up_value(&dealer_query);
+    let player_hand = player_query.iter()
+        .find(|(seat, _)| seat.0 == active_seat.0)
+        .and_then(|(_, player_hands)| player_hands.0.first());
+    let message = match (up_card, player_hand) {
+        (1.., Some(player_hand)) if settings.hints && *game_state.get() == GameRoundState::PlayerHand && !player_hand.cards.is_empty() => {
+            let can_double = rules.can_double(player_hand);
+            let action = basic_strategy(player_hand.best_total(), player_hand.is_soft(), up_card, can_double);
+            format!("{}: {}", settings.language.translate("Basic strategy"), settings.language.translate(action.label()))
+        }
+        _ => String::new(),
+    };
+    for (text_component, mut text) in text_query.iter_mut() {
+        if matches!(text_component, TextComponents::Hint) && text.sections[0].value != message {
+            text.sections[0].value = message.clone();
+        }
+    }
+}
 
 // function attempts to be deleted / cleaned / reused below --------------------
 
@@ -0,0 +1,108 @@
+///inspector module is the F3 overlay showing what the game holds while it runs: the states, the deck, the card entities on
+/// screen, the balance and the bet, and how long the frames take. it replaces printing them from test systems.
+
+use bevy::prelude::*;
+use super::components::{Card, Deck, InGameCardAccess, InspectorOverlay, InspectorText, Seat};
+use super::constants::{AppState, DeckState, GameRoundState, INSPECTOR_NEXT_CARDS};
+use super::resources::{BalanceValue, BetValue};
+
+//how much of each new frame goes into the average frame time
+const FRAME_TIME_SMOOTHING: f32 = 0.1;
+
+///struct / resource Inspector is whether the inspector overlay is shown, and the average time of a frame while it is
+#[derive(Resource, Default)]
+pub struct Inspector {
+    pub open: bool,
+    pub frame_time: f32,
+}
+
+///toggle_inspector shows and hides the inspector with F3
+pub fn toggle_inspector(mut inspector: ResMut<Inspector>, keyboard_input: Res<ButtonInput<KeyCode>>) {
+    if keyboard_input.just_pressed(KeyCode::F3) {
+        inspector.open = !inspector.open;
+        inspector.frame_time = 0.0;
+    }
+}
+
+///show_inspector spawns the inspector over the game while it is shown and writes what the game holds into it every frame
+pub fn show_inspector(
+    mut commands: Commands,
+    assets: Res<AssetServer>,
+    mut inspector: ResMut<Inspector>,
+    time: Res<Time>,
+    app_state: Res<State<AppState>>,
+    game_state: Res<State<GameRoundState>>,
+    deck_state: Res<State<DeckState>>,
+    deck: Res<Deck>,
+    balance_value: Res<BalanceValue>,
+    bet_value: Res<BetValue>,
+    card_query: Query<(Entity, &InGameCardAccess, Option<&Seat>)>,
+    overlay_query: Query<Entity, With<InspectorOverlay>>,
+    mut text_query: Query<&mut Text, With<InspectorText>>,
+) {
+    if !inspector.open {
+        for entity in overlay_query.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+        return;
+    }
+    let delta = time.delta_seconds();
+    inspector.frame_time = if inspector.frame_time == 0.0 { delta } else { inspector.frame_time + (delta - inspector.frame_time) * FRAME_TIME_SMOOTHING };
+
+    let mut lines = vec![
+        format!("app {:?}, round {}, deck {:?}", app_state.get(), game_state.get().label(), deck_state.get()),
+        format!("last dealt index {}, {} of {} cards remaining", deck.last_dealt_index, deck.cards_remaining(), deck.shoe_size()),
+    ];
+    //the cards ahead are not shown in a release build, where they would give the game away
+    if cfg!(debug_assertions) {
+        let next_cards: Vec<String> = deck.next_cards(INSPECTOR_NEXT_CARDS).iter().map(Card::code).collect();
+        lines.push(format!("next {}", next_cards.join(" ")));
+    }
+    lines.push(format!("balance {}, bet {}", balance_value.value, bet_value.value));
+    let mut cards: Vec<_> = card_query.iter().collect();
+    //the dealer's cards first, then each seat's, in the order they were dealt
+    cards.sort_by_key(|(entity, card_access, seat)| match card_access {
+        InGameCardAccess::DealerCard(index) => (None, *index, *entity),
+        InGameCardAccess::PlayerCard(index) => (Some(seat.map_or(0, |seat| seat.0)), *index, *entity),
+    });
+    lines.push(format!("{} cards on screen", cards.len()));
+    for (entity, card_access, seat) in cards {
+        match seat {
+            Some(seat) => lines.push(format!("  {entity} {card_access:?} seat {}", seat.0)),
+            None => lines.push(format!("  {entity} {card_access:?}")),
+        }
+    }
+    let frame_time = inspector.frame_time * 1000.0;
+    lines.push(format!("frame {frame_time:.1} ms ({:.0} fps)", if frame_time > 0.0 { 1000.0 / frame_time } else { 0.0 }));
+
+    let text = lines.join("\n");
+    if let Ok(mut inspector_text) = text_query.get_single_mut() {
+        inspector_text.sections[0].value = text;
+        return;
+    }
+    commands.spawn((
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                top: Val::Px(0.0),
+                right: Val::Px(0.0),
+                padding: UiRect::all(Val::Px(8.0)),
+                ..default()
+            },
+            background_color: BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.7)),
+            z_index: ZIndex::Global(9),
+            ..default()
+        },
+        InspectorOverlay,
+    ))
+    .with_children(|parent| {
+        parent.spawn((
+            TextBundle::from_section(text, TextStyle {
+                font: assets.load("fonts/FiraSans-SemiBold.ttf"),
+                font_size: 14.0,
+                color: Color::srgb(1.0, 1.0, 0.6),
+            }),
+            InspectorText,
+        ));
+    });
+}
@@ -0,0 +1,156 @@
+///logging module sets up the game's logs on top of the LogPlugin. every module logs under its own target
+/// (blackjack::game::dealer_systems, blackjack::game::bot, ...), `--log-level` sets how much of it is kept, and the logs
+/// are also written to a file in the data directory, rotated each time the game starts, to be attached to bug reports.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use bevy::log::{tracing_subscriber::fmt, BoxedLayer, Level, LogPlugin};
+use bevy::prelude::*;
+use super::storage::DataDir;
+
+///LOGS_DIRECTORY is where the log files are kept, inside the data directory
+pub static LOGS_DIRECTORY: &str = "logs";
+///LOG_FILE_NAME is the log of the running game, the older ones are numbered (blackjack.1.log is the last one)
+pub static LOG_FILE_NAME: &str = "blackjack";
+
+//how many older log files are kept, and the size at which the running one is rotated
+const KEPT_LOG_FILES: usize = 5;
+const MAX_LOG_FILE_BYTES: u64 = 10 * 1024 * 1024;
+
+//the game's own modules are logged under this target, everything else (bevy, wgpu, ...) at info at most
+const GAME_TARGET: &str = "blackjack";
+
+///struct / resource LogSettings is how much is logged and where, inserted from the command line before the plugins are built
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct LogSettings {
+    ///level of the game's own targets
+    pub level: Level,
+    ///levels of single modules, e.g. ("bot", DEBUG) for blackjack::game::bot
+    pub modules: Vec<(String, Level)>,
+    ///whether the logs are also written to the log file
+    pub log_file: bool,
+}
+
+impl Default for LogSettings {
+    fn default() -> Self {
+        Self {
+            level: Level::INFO,
+            modules: Vec::new(),
+            log_file: true,
+        }
+    }
+}
+
+impl LogSettings {
+    ///set_levels reads the value of `--log-level`: a level for the whole game and/or module=level pairs separated by
+    /// commas, e.g. `warn,bot=debug`
+    pub fn set_levels(&mut self, levels: &str) -> Result<(), String> {
+        for directive in levels.split(',').filter(|directive| !directive.is_empty()) {
+            let (module, level) = match directive.split_once('=') {
+                Some((module, level)) => (Some(module), level),
+                None => (None, directive),
+            };
+            let level = level.parse::<Level>()
+                .map_err(|_| format!("unknown log level {level}, expected error, warn, info, debug or trace"))?;
+            match module {
+                Some(module) => self.modules.push((module.to_string(), level)),
+                None => self.level = level,
+            }
+        }
+        Ok(())
+    }
+
+    ///filter returns the LogPlugin filter for the settings, a module is named from blackjack::game or by its full path
+    pub fn filter(&self) -> String {
+        let mut filter = format!("wgpu=error,naga=warn,{GAME_TARGET}={}", self.level);
+        for (module, level) in &self.modules {
+            let target = if module.starts_with(GAME_TARGET) { module.clone() } else { format!("{GAME_TARGET}::game::{module}") };
+            filter.push_str(&format!(",{target}={level}"));
+        }
+        filter
+    }
+}
+
+///log_plugin returns the LogPlugin set up with the settings, writing to the log file when it is turned on. RUST_LOG is
+/// still read first and replaces the levels entirely
+pub fn log_plugin(settings: &LogSettings) -> LogPlugin {
+    LogPlugin {
+        //the engine is kept at info even when the game logs more
+        level: settings.level.min(Level::INFO),
+        filter: settings.filter(),
+        custom_layer: log_file_layer,
+    }
+}
+
+//log_file_layer writes the logs to the rotating log file when the settings turn it on
+fn log_file_layer(app: &mut App) -> Option<BoxedLayer> {
+    if !app.world().get_resource::<LogSettings>().is_some_and(|settings| settings.log_file) {
+        return None;
+    }
+    let data_dir = app.world().get_resource::<DataDir>().cloned().unwrap_or_default();
+    match RotatingLogFile::open(&data_dir.0.join(LOGS_DIRECTORY)) {
+        Ok(file) => Some(Box::new(fmt::layer().with_ansi(false).with_writer(Mutex::new(file)))),
+        Err(error) => {
+            eprintln!("Could not open the log file: {error}");
+            None
+        }
+    }
+}
+
+///struct RotatingLogFile is the log file of the running game. the last log files are kept numbered next to it, it is
+/// rotated each time the game starts and whenever it grows past MAX_LOG_FILE_BYTES
+pub struct RotatingLogFile {
+    directory: PathBuf,
+    file: File,
+    written: u64,
+}
+
+impl RotatingLogFile {
+    ///open rotates the log files of the directory and starts a new one
+    pub fn open(directory: &Path) -> io::Result<RotatingLogFile> {
+        fs::create_dir_all(directory)?;
+        rotate_log_files(directory)?;
+        let file = OpenOptions::new().create(true).write(true).truncate(true).open(log_file_path(directory, 0))?;
+        Ok(RotatingLogFile { directory: directory.to_path_buf(), file, written: 0 })
+    }
+}
+
+impl Write for RotatingLogFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.written + buf.len() as u64 > MAX_LOG_FILE_BYTES {
+            *self = RotatingLogFile::open(&self.directory)?;
+        }
+        let written = self.file.write(buf)?;
+        self.written += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+///log_file_path returns the path of a log file, 0 being the running one and 1 the one before it
+pub fn log_file_path(directory: &Path, index: usize) -> PathBuf {
+    match index {
+        0 => directory.join(format!("{LOG_FILE_NAME}.log")),
+        _ => directory.join(format!("{LOG_FILE_NAME}.{index}.log")),
+    }
+}
+
+//rotate_log_files numbers every log file one higher, the oldest one past KEPT_LOG_FILES being removed
+fn rotate_log_files(directory: &Path) -> io::Result<()> {
+    let oldest = log_file_path(directory, KEPT_LOG_FILES);
+    if oldest.exists() {
+        fs::remove_file(oldest)?;
+    }
+    for index in (0..KEPT_LOG_FILES).rev() {
+        let path = log_file_path(directory, index);
+        if path.exists() {
+            fs::rename(&path, log_file_path(directory, index + 1))?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,200 @@
+///main menu systems module builds the main menu of the start screen and moves through it with the mouse, the keyboard
+/// (arrows and enter) or a gamepad (d-pad and south), each entry going to its own screen
+
+use bevy::app::AppExit;
+use bevy::prelude::*;
+use super::components::{Localized, MainMenu, MenuEntry};
+use super::constants::AppState;
+use super::resources::{BalanceValue, LastSession, MenuFocus};
+use super::settings::Settings;
+
+///colors of the menu entries, also used by the rows of the settings screen
+pub static MENU_BUTTON_COLOR: Color = Color::srgba(0.0, 0.0, 0.0, 0.6);
+pub static MENU_FOCUS_COLOR: Color = Color::srgb(0.75, 0.55, 0.1);
+static MENU_DISABLED_TEXT_COLOR: Color = Color::srgb(0.45, 0.45, 0.45);
+
+impl MenuEntry {
+    ///ALL lists the entries in the order they are shown
+    pub const ALL: [MenuEntry; 7] = [
+        MenuEntry::NewGame,
+        MenuEntry::Continue,
+        MenuEntry::Practice,
+        MenuEntry::Statistics,
+        MenuEntry::Settings,
+        MenuEntry::Rules,
+        MenuEntry::Quit,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            MenuEntry::NewGame => "New Game",
+            MenuEntry::Continue => "Continue",
+            MenuEntry::Practice => "Practice",
+            MenuEntry::Statistics => "Statistics",
+            MenuEntry::Settings => "Settings",
+            MenuEntry::Rules => "Rules",
+            MenuEntry::Quit => "Quit",
+        }
+    }
+
+    ///app_state returns the screen the entry goes to, Quit closes the game instead
+    pub fn app_state(&self) -> Option<AppState> {
+        match self {
+            MenuEntry::NewGame | MenuEntry::Continue => Some(AppState::InGame),
+            MenuEntry::Practice => Some(AppState::Practice),
+            MenuEntry::Statistics => Some(AppState::Stats),
+            MenuEntry::Settings => Some(AppState::Settings),
+            MenuEntry::Rules => Some(AppState::Rules),
+            MenuEntry::Quit => None,
+        }
+    }
+
+    ///is_available returns whether the entry can be picked, Continue needs a table to go back to
+    pub fn is_available(&self, last_session: &LastSession) -> bool {
+        *self != MenuEntry::Continue || last_session.balance.is_some()
+    }
+}
+
+///next_menu_focus returns the entry the focus moves to from the focused one, skipping the entries that cannot be picked
+/// and wrapping around the ends of the menu
+pub fn next_menu_focus(focus: usize, step: isize, last_session: &LastSession) -> usize {
+    let count = MenuEntry::ALL.len() as isize;
+    let mut next = focus as isize;
+    for _ in 0..count {
+        next = (next + step).rem_euclid(count);
+        if MenuEntry::ALL[next as usize].is_available(last_session) {
+            return next as usize;
+        }
+    }
+    focus
+}
+
+///spawn_main_menu spawns the entries of the main menu under the logo of the start screen
+pub fn spawn_main_menu(
+    mut commands: Commands,
+    assets: Res<AssetServer>,
+    last_session: Res<LastSession>,
+    settings: Res<Settings>,
+    mut focus: ResMut<MenuFocus>,
+) {
+    if !MenuEntry::ALL[focus.0].is_available(&last_session) {
+        focus.0 = 0;
+    }
+    commands.spawn((
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                top: Val::Px(185.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(4.0),
+                ..default()
+            },
+            ..default()
+        },
+        MainMenu,
+    ))
+    .with_children(|parent| {
+        for entry in MenuEntry::ALL {
+            let text_color = if entry.is_available(&last_session) { Color::WHITE } else { MENU_DISABLED_TEXT_COLOR };
+            parent.spawn((
+                ButtonBundle {
+                    style: Style {
+                        width: Val::Px(220.0),
+                        height: Val::Px(26.0),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    background_color: BackgroundColor(MENU_BUTTON_COLOR),
+                    ..default()
+                },
+                entry,
+            ))
+            .with_children(|button| {
+                button.spawn((
+                    TextBundle::from_section(settings.language.translate(entry.label()), TextStyle {
+                        font: assets.load("fonts/FiraSans-SemiBold.ttf"),
+                        font_size: 18.0,
+                        color: text_color,
+                    }),
+                    Localized(entry.label()),
+                ));
+            });
+        }
+    });
+}
+
+///main_menu_input moves the focus with the arrow keys, the d-pad or the mouse, and picks the focused entry with enter,
+/// space or the gamepad's south button (or the entry clicked)
+pub fn main_menu_input(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    gamepads: Res<Gamepads>,
+    gamepad_buttons: Res<ButtonInput<GamepadButton>>,
+    mut focus: ResMut<MenuFocus>,
+    last_session: Res<LastSession>,
+    mut balance_value: ResMut<BalanceValue>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut app_exit: EventWriter<AppExit>,
+    entry_query: Query<(&Interaction, &MenuEntry), Changed<Interaction>>,
+) {
+    let gamepad_pressed = |button_type| gamepads.iter().any(|gamepad| gamepad_buttons.just_pressed(GamepadButton::new(gamepad, button_type)));
+    if keyboard_input.just_pressed(KeyCode::ArrowUp) || gamepad_pressed(GamepadButtonType::DPadUp) {
+        focus.0 = next_menu_focus(focus.0, -1, &last_session);
+    }
+    if keyboard_input.just_pressed(KeyCode::ArrowDown) || gamepad_pressed(GamepadButtonType::DPadDown) {
+        focus.0 = next_menu_focus(focus.0, 1, &last_session);
+    }
+
+    let mut picked = None;
+    if keyboard_input.any_just_pressed([KeyCode::Enter, KeyCode::NumpadEnter, KeyCode::Space]) || gamepad_pressed(GamepadButtonType::South) {
+        picked = Some(MenuEntry::ALL[focus.0]);
+    }
+    for (interaction, entry) in entry_query.iter().filter(|(_, entry)| entry.is_available(&last_session)) {
+        let index = MenuEntry::ALL.iter().position(|other| other == entry).unwrap_or_default();
+        match interaction {
+            Interaction::Hovered => focus.0 = index,
+            Interaction::Pressed => {
+                focus.0 = index;
+                picked = Some(*entry);
+            }
+            Interaction::None => {}
+        }
+    }
+
+    let Some(entry) = picked else {
+        return;
+    };
+    debug!("Main menu: {}", entry.label());
+    if let (MenuEntry::Continue, Some(balance)) = (entry, last_session.balance) {
+        balance_value.value = balance;
+    }
+    match entry.app_state() {
+        Some(state) => next_state.set(state),
+        None => {
+            app_exit.send(AppExit::Success);
+        }
+    }
+}
+
+///highlight_menu_focus colors the focused entry of the main menu
+pub fn highlight_menu_focus(
+    focus: Res<MenuFocus>,
+    mut entry_query: Query<(&MenuEntry, &mut BackgroundColor)>,
+    added_entries: Query<(), Added<MenuEntry>>,
+) {
+    if !focus.is_changed() && added_entries.is_empty() {
+        return;
+    }
+    for (entry, mut background_color) in entry_query.iter_mut() {
+        background_color.0 = if *entry == MenuEntry::ALL[focus.0] { MENU_FOCUS_COLOR } else { MENU_BUTTON_COLOR };
+    }
+}
+
+///despawn_main_menu removes the main menu when leaving the start screen
+pub fn despawn_main_menu(mut commands: Commands, query: Query<Entity, With<MainMenu>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
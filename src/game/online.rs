@@ -0,0 +1,141 @@
+///online module is the game's side of a network table: the OnlineSession resource holds the connection to the server,
+/// and a background thread reads the server's messages so the game never waits on the network.
+
+use std::net::{Shutdown, TcpStream};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::sync::Mutex;
+use std::thread;
+use bevy::prelude::*;
+use super::client::TableClient;
+use super::protocol::{read_message, write_message, ClientMessage, SeatResult, ServerMessage, TableSnapshot, DEFAULT_SERVER_ADDRESS};
+
+///struct OnlineConnection is an open connection, messages being fed by the reader thread (None once the connection is gone)
+struct OnlineConnection {
+    writer: TcpStream,
+    messages: Mutex<Receiver<Option<ServerMessage>>>,
+}
+
+///struct / resource OnlineSession is the connection to a table server and the last state of the table it sent
+#[derive(Resource)]
+pub struct OnlineSession {
+    pub address: String,
+    pub name: String,
+    ///handed out by the server when sitting down, used to take the same seat back after a dropped connection
+    pub token: Option<String>,
+    pub seat: Option<usize>,
+    pub snapshot: Option<TableSnapshot>,
+    pub last_results: Vec<SeatResult>,
+    ///last thing worth telling the player: errors from the server, a lost connection...
+    pub status: String,
+    ///amount sent with the next bet
+    pub bet_amount: i32,
+    ///watch the table without a seat instead of playing
+    pub spectating: bool,
+    connection: Option<OnlineConnection>,
+}
+
+impl Default for OnlineSession {
+    fn default() -> Self {
+        Self {
+            address: DEFAULT_SERVER_ADDRESS.to_string(),
+            name: String::from("Player"),
+            token: None,
+            seat: None,
+            snapshot: None,
+            last_results: Vec::new(),
+            status: String::new(),
+            bet_amount: 10,
+            spectating: false,
+            connection: None,
+        }
+    }
+}
+
+impl OnlineSession {
+    pub fn is_connected(&self) -> bool {
+        self.connection.is_some()
+    }
+
+    ///connect connects to the server and sits down, taking back the seat held for the token if there is one,
+    /// or only watches the table when spectating
+    pub fn connect(&mut self) {
+        self.disconnect();
+        let connected = if self.spectating {
+            TableClient::spectate(&self.address)
+        } else {
+            TableClient::connect(&self.address, &self.name, self.token.clone())
+        };
+        match connected {
+            Ok((client, snapshot)) => {
+                self.seat = client.seat;
+                self.token = client.token.clone();
+                self.snapshot = Some(snapshot);
+                self.status = if self.spectating {
+                    format!("Watching {}", self.address)
+                } else {
+                    format!("Connected to {}", self.address)
+                };
+
+                let (mut reader, writer) = client.split();
+                let (sender, receiver) = mpsc::channel();
+                thread::spawn(move || loop {
+                    let message: Option<ServerMessage> = read_message(&mut reader).ok().flatten();
+                    let closed = message.is_none();
+                    if sender.send(message).is_err() || closed {
+                        break;
+                    }
+                });
+                self.connection = Some(OnlineConnection { writer, messages: Mutex::new(receiver) });
+            }
+            Err(error) => self.status = format!("Could not connect to {}: {error}", self.address),
+        }
+    }
+
+    ///send sends a message to the server, a failed write counts as a lost connection
+    pub fn send(&mut self, message: ClientMessage) {
+        let Some(connection) = &mut self.connection else {
+            self.status = String::from("Not connected - press R to reconnect");
+            return;
+        };
+        if let Err(error) = write_message(&mut connection.writer, &message) {
+            self.status = format!("Connection lost: {error} - press R to reconnect");
+            self.connection = None;
+        }
+    }
+
+    ///poll applies every message received since the last poll, returning whether anything changed
+    pub fn poll(&mut self) -> bool {
+        let mut changed = false;
+        while let Some(connection) = &self.connection {
+            let received = connection.messages.lock()
+                .map_or(Err(TryRecvError::Disconnected), |messages| messages.try_recv());
+            match received {
+                Err(TryRecvError::Empty) => break,
+                Ok(Some(ServerMessage::Snapshot { snapshot })) => self.snapshot = Some(snapshot),
+                Ok(Some(ServerMessage::Settle { results })) => self.last_results = results,
+                Ok(Some(ServerMessage::Error { message })) => self.status = message,
+                Ok(Some(ServerMessage::Welcome { .. } | ServerMessage::Watching { .. })) => {}
+                Ok(None) | Err(TryRecvError::Disconnected) => {
+                    self.status = String::from("Connection lost - press R to reconnect");
+                    self.connection = None;
+                }
+            }
+            changed = true;
+        }
+        changed
+    }
+
+    ///disconnect closes the connection, the seat stays held by the server for the token
+    pub fn disconnect(&mut self) {
+        if let Some(connection) = self.connection.take() {
+            let _ = connection.writer.shutdown(Shutdown::Both);
+        }
+    }
+}
+
+impl Drop for OnlineSession {
+    fn drop(&mut self) {
+        self.disconnect();
+    }
+}
+
@@ -0,0 +1,228 @@
+///online screen systems module builds the screen used to play at a network table: it draws the table from the server's
+/// snapshots with the same card helpers used in game and sends the player's bets and actions to the server
+
+use bevy::prelude::*;
+use super::components::{Card, OnlineScreen, OnlineTable, OnlineText, Seat};
+use super::constants::{AppState, CARD_HORIZONTAL_SPACING, CARD_VERTICAL_SPACING, DEALER_CARDS_INITIAL_HORIZONTAL_POSITION, DEALER_CARDS_INITIAL_VERTICAL_POSITION};
+use super::events::PlayerAction;
+use super::in_game_systems::{seat_card_position, spawn_dealer_card, spawn_player_card};
+use super::online::OnlineSession;
+use super::protocol::{ClientMessage, TablePhase, HIDDEN_CARD};
+
+///online_screen_setup connects to the server and spawns the online screen
+pub fn online_screen_setup(
+    mut commands: Commands,
+    assets: Res<AssetServer>,
+    mut session: ResMut<OnlineSession>,
+) {
+    session.connect();
+
+    commands.spawn((
+        NodeBundle {
+            style: Style {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                ..default()
+            },
+            background_color: BackgroundColor(Color::srgb(0.05, 0.3, 0.15)),
+            ..default()
+        },
+        OnlineScreen,
+    ))
+    .with_children(|parent| {
+        parent.spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    ..default()
+                },
+                ..default()
+            },
+            OnlineTable,
+        ));
+
+        spawn_online_text(parent, &assets, Vec2::new(10.0, 10.0), 20.0, OnlineText::Title);
+        spawn_online_text(parent, &assets, Vec2::new(10.0, 290.0), 18.0, OnlineText::Status);
+        spawn_online_text(parent, &assets, Vec2::new(10.0, 460.0), 16.0, OnlineText::Help);
+    });
+}
+
+///spawn_online_text is a helper function for spawning one of the text elements on the online screen, the text itself is filled in by render_online_table
+fn spawn_online_text(
+    parent: &mut ChildBuilder,
+    assets: &Res<AssetServer>,
+    position: Vec2,
+    font_size: f32,
+    text_component: OnlineText,
+) {
+    parent.spawn(TextBundle {
+        text: Text::from_section(
+            "",
+            TextStyle {
+                font: assets.load("fonts/FiraSans-SemiBold.ttf"),
+                font_size,
+                color: Color::WHITE,
+            },
+        ),
+        style: Style {
+            position_type: PositionType::Absolute,
+            left: Val::Px(position.x),
+            top: Val::Px(position.y),
+            ..default()
+        },
+        ..default()
+    })
+    .insert(text_component);
+}
+
+///receive_server_messages applies whatever the server sent since the last frame to the session
+pub fn receive_server_messages(mut session: ResMut<OnlineSession>) {
+    //only mark the session changed when something arrived, so the table is not redrawn every frame
+    if session.bypass_change_detection().poll() {
+        session.set_changed();
+    }
+}
+
+///online_input sends the player's bets: up / down change the bet and B bets, R reconnects to the held seat and Esc leaves
+/// the table. a spectator can only reconnect and leave
+pub fn online_input(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut session: ResMut<OnlineSession>,
+) {
+    if keyboard_input.just_pressed(KeyCode::KeyR) && !session.is_connected() {
+        session.connect();
+    }
+    if keyboard_input.just_pressed(KeyCode::Escape) {
+        session.send(ClientMessage::Leave);
+        session.disconnect();
+        session.token = None;
+        next_state.set(AppState::Start);
+    }
+    if session.spectating {
+        return;
+    }
+
+    if keyboard_input.just_pressed(KeyCode::ArrowUp) {
+        session.bet_amount += 10;
+    }
+    if keyboard_input.just_pressed(KeyCode::ArrowDown) {
+        session.bet_amount = (session.bet_amount - 10).max(10);
+    }
+    if keyboard_input.just_pressed(KeyCode::KeyB) {
+        let amount = session.bet_amount;
+        session.send(ClientMessage::Bet { amount });
+    }
+}
+
+///send_player_actions sends the player's actions to the server, which checks them against the table it deals.
+/// the actions the server does not offer are turned down here
+pub fn send_player_actions(mut session: ResMut<OnlineSession>, mut player_actions: EventReader<PlayerAction>) {
+    for action in player_actions.read() {
+        if session.spectating {
+            continue;
+        }
+        match action.kind.hand_action() {
+            Some(action) => session.send(ClientMessage::Action { action }),
+            None => session.status = format!("{} is not offered at this table", action.kind.label()),
+        }
+    }
+}
+
+///render_online_table respawns the cards and refreshes the texts of the online screen whenever the session changes
+pub fn render_online_table(
+    mut commands: Commands,
+    assets: Res<AssetServer>,
+    session: Res<OnlineSession>,
+    table_query: Query<Entity, With<OnlineTable>>,
+    mut text_query: Query<(&OnlineText, &mut Text)>,
+    added_screen: Query<(), Added<OnlineScreen>>,
+) {
+    if !session.is_changed() && added_screen.is_empty() {
+        return;
+    }
+    let Ok(table) = table_query.get_single() else {
+        return;
+    };
+    commands.entity(table).despawn_descendants();
+
+    if let Some(snapshot) = &session.snapshot {
+        commands.entity(table).with_children(|parent| {
+            for player in &snapshot.players {
+                for (i, card) in player.cards.iter().filter_map(|code| Card::from_code(code)).enumerate() {
+                    let position = seat_card_position(player.seat, snapshot.seats, i);
+                    spawn_player_card(parent, &assets, &card, Seat(player.seat), i, position, true);
+                }
+            }
+            for (i, code) in snapshot.dealer_cards.iter().enumerate() {
+                //the hole card is not sent while it is hidden, any card will do to draw its back
+                let face_up = code != HIDDEN_CARD;
+                let Some(card) = Card::from_code(if face_up { code } else { "AS" }) else {
+                    continue;
+                };
+                let position = Vec2 {
+                    x: DEALER_CARDS_INITIAL_HORIZONTAL_POSITION + (i as f32)*CARD_HORIZONTAL_SPACING,
+                    y: DEALER_CARDS_INITIAL_VERTICAL_POSITION + (i as f32)*CARD_VERTICAL_SPACING};
+                spawn_dealer_card(parent, &assets, &card, i, position, face_up, true);
+            }
+        });
+    }
+
+    for (text_component, mut text) in text_query.iter_mut() {
+        text.sections[0].value = match text_component {
+            OnlineText::Title => online_title(&session),
+            OnlineText::Status => online_status(&session),
+            OnlineText::Help if session.spectating => String::from("Spectating   R: reconnect   Esc: stop watching"),
+            OnlineText::Help => format!(
+                "Bet ${} (up/down)   B: bet   H: hit   S: stand   D: double   R: reconnect   Esc: leave table",
+                session.bet_amount),
+        };
+    }
+}
+
+fn online_title(session: &OnlineSession) -> String {
+    let round = session.snapshot.as_ref().map_or(0, |snapshot| snapshot.round);
+    if session.spectating {
+        return format!("Watching {} - round {}", session.address, round);
+    }
+    let seat = session.seat.map_or(String::from("-"), |seat| (seat + 1).to_string());
+    format!("Online at {} - round {} - your seat: {}", session.address, round, seat)
+}
+
+///online_status lists the turn, every player at the table, the last results and the last message from the server
+fn online_status(session: &OnlineSession) -> String {
+    let Some(snapshot) = &session.snapshot else {
+        return session.status.clone();
+    };
+    let mut lines = Vec::new();
+    lines.push(match (snapshot.phase, snapshot.active_seat) {
+        (TablePhase::PlayerTurns, Some(seat)) if Some(seat) == session.seat => String::from("Your turn"),
+        (TablePhase::PlayerTurns, Some(seat)) => format!("Seat {} to act", seat + 1),
+        _ => String::from("Place your bets"),
+    });
+    for player in &snapshot.players {
+        let connection = if player.connected { "" } else { " (disconnected)" };
+        lines.push(format!("Seat {}: {}{} - bankroll ${} - bet ${} - {}",
+            player.seat + 1, player.name, connection, player.bankroll, player.bet, player.cards.join(" ")));
+    }
+    for result in &session.last_results {
+        lines.push(format!("Seat {} {:?}: paid ${} on ${}", result.seat + 1, result.outcome, result.payout, result.wager));
+    }
+    lines.push(session.status.clone());
+    lines.join("\n")
+}
+
+///despawn_online_screen removes the online screen and closes the connection when leaving it, the seat stays held
+/// by the server unless the player left the table
+pub fn despawn_online_screen(
+    mut commands: Commands,
+    mut session: ResMut<OnlineSession>,
+    query: Query<Entity, With<OnlineScreen>>,
+) {
+    session.disconnect();
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
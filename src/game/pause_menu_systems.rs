@@ -0,0 +1,389 @@
+///pause menu systems module pauses the table with Esc (or a gamepad's start button): the timers of the table are frozen
+/// and a menu is shown over it to resume, open the settings or the rules, or go back to the main menu. leaving the table
+/// with a bet on it, from the menu or with Home, first asks for a confirmation saying what happens to the bet: a bet not
+/// dealt yet is handed back, a dealt hand is forfeited and recorded as lost.
+
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy::ui::FocusPolicy;
+use super::components::{Companion, DealerHand, LeaveChoice, LeaveDialogOverlay, Localized, PauseEntry, PauseOverlay, PlayerHands, Seat, SeatStatus};
+use super::constants::{AppState, GameRoundState, PauseState};
+use super::dealer_systems::settle_hand;
+use super::events::{HandSettled, RoundOutcome};
+use super::hand_history::{hand_history_path, HandHistory};
+use super::main_menu_systems::{MENU_BUTTON_COLOR, MENU_FOCUS_COLOR};
+use super::resources::{ActiveProfile, BalanceValue, BetValue, LeaveDialog, PauseFocus};
+use super::settings::Settings;
+use super::storage::DataDir;
+
+//color the table is dimmed with under the pause menu and the leave dialog
+static PAUSE_DIM_COLOR: Color = Color::srgba(0.0, 0.0, 0.0, 0.7);
+
+impl PauseEntry {
+    ///ALL lists the entries in the order they are shown
+    pub const ALL: [PauseEntry; 4] = [PauseEntry::Resume, PauseEntry::Settings, PauseEntry::Rules, PauseEntry::QuitToMenu];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            PauseEntry::Resume => "Resume",
+            PauseEntry::Settings => "Settings",
+            PauseEntry::Rules => "Rules",
+            PauseEntry::QuitToMenu => "Quit to Menu",
+        }
+    }
+}
+
+impl LeaveChoice {
+    pub fn label(&self) -> &'static str {
+        match self {
+            LeaveChoice::Stay => "Stay",
+            LeaveChoice::Leave => "Leave",
+        }
+    }
+}
+
+///leave_warning returns what the leave dialog says happens to the bet on the table: a bet not dealt yet is handed back, a
+/// dealt hand is forfeited and recorded as lost, and the balance Continue sits back down with
+pub fn leave_warning(bet: i32, balance: i32, round: GameRoundState) -> String {
+    let (bet_text, balance) = match round {
+        GameRoundState::Betting | GameRoundState::RoundStart => {
+            (format!("The ${bet} you have bet is not dealt yet, and is handed back if you leave now."), balance + bet)
+        }
+        _ => (format!("The round is not over: your ${bet} bet is forfeited, and the hand is recorded as lost."), balance),
+    };
+    if balance > 0 {
+        format!("{bet_text}\nContinue sits back down with ${balance}.")
+    } else {
+        format!("{bet_text}\nThere is no balance left to Continue with.")
+    }
+}
+
+///struct LeaveTable is the system parameter leaving the table goes through, it asks first when there is a bet on the table
+#[derive(SystemParam)]
+pub struct LeaveTable<'w, 's> {
+    settings: Res<'w, Settings>,
+    bet_value: ResMut<'w, BetValue>,
+    balance_value: ResMut<'w, BalanceValue>,
+    game_state: Res<'w, State<GameRoundState>>,
+    dialog: ResMut<'w, LeaveDialog>,
+    next_app_state: ResMut<'w, NextState<AppState>>,
+    next_pause_state: ResMut<'w, NextState<PauseState>>,
+    player_query: Query<'w, 's, (&'static Seat, &'static PlayerHands, &'static mut SeatStatus), Without<Companion>>,
+    dealer_query: Query<'w, 's, &'static DealerHand>,
+    hand_history: ResMut<'w, HandHistory>,
+    data_dir: Res<'w, DataDir>,
+    profile: Res<'w, ActiveProfile>,
+    hand_settled: EventWriter<'w, HandSettled>,
+}
+
+impl LeaveTable<'_, '_> {
+    ///request goes back to the start screen, or opens the leave dialog when there is a bet on the table and confirmation
+    /// prompts are on. staying goes back to `return_to`
+    pub fn request(&mut self, return_to: PauseState) {
+        if self.settings.confirmations && self.bet_value.value > 0 {
+            *self.dialog = LeaveDialog { focus: LeaveChoice::Stay, return_to };
+            self.next_pause_state.set(PauseState::ConfirmLeave);
+        } else {
+            self.leave();
+        }
+    }
+
+    ///leave goes back to the start screen. a bet not dealt yet is handed back, the hands of the player's that are dealt and
+    /// not settled yet are forfeited: each one is settled as lost, for the statistics and the hand history
+    pub fn leave(&mut self) {
+        match self.game_state.get() {
+            GameRoundState::RoundStart | GameRoundState::Betting => self.balance_value.value += self.bet_value.value,
+            GameRoundState::Settlement | GameRoundState::RoundEnd => {}
+            _ => self.forfeit_round(),
+        }
+        self.bet_value.value = 0;
+        self.next_pause_state.set(PauseState::Running);
+        self.next_app_state.set(AppState::Start);
+    }
+
+    //forfeit_round settles every dealt hand of the player's still in play as lost and writes the round to the hand history
+    fn forfeit_round(&mut self) {
+        let Ok(dealer_hand) = self.dealer_query.get_single() else {
+            return;
+        };
+        let mut seats: Vec<_> = self.player_query.iter_mut()
+            .filter(|(_, player_hands, seat_status)| seat_status.0.is_none() && player_hands.0.first().is_some_and(|hand| !hand.cards.is_empty()))
+            .collect();
+        seats.sort_by_key(|(seat, _, _)| **seat);
+        for (seat, player_hands, seat_status) in seats.iter_mut() {
+            let Some(player_hand) = player_hands.0.first() else {
+                continue;
+            };
+            let settled = settle_hand(seat, RoundOutcome::Loss, player_hand, dealer_hand, seat_status, None);
+            self.hand_history.record_settled(&settled);
+            self.hand_settled.send(settled);
+        }
+        self.hand_history.finish_round(self.balance_value.value, &hand_history_path(&self.data_dir, &self.profile.0));
+    }
+
+    ///stay closes the leave dialog
+    pub fn stay(&mut self) {
+        self.next_pause_state.set(self.dialog.return_to);
+    }
+
+    ///set_pause_state pauses, resumes or moves through the pause menu, for the systems that can also leave the table
+    pub fn set_pause_state(&mut self, state: PauseState) {
+        self.next_pause_state.set(state);
+    }
+}
+
+///pause_input pauses the table with Esc or the gamepad's start button, and moves through the pause menu like the main
+/// menu: the arrow keys, the d-pad or the mouse move the focus, and enter, space, the gamepad's south button or a click
+/// pick the entry. Esc (or start) again resumes
+pub fn pause_input(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    gamepads: Res<Gamepads>,
+    gamepad_buttons: Res<ButtonInput<GamepadButton>>,
+    pause_state: Res<State<PauseState>>,
+    mut focus: ResMut<PauseFocus>,
+    mut leave_table: LeaveTable,
+    entry_query: Query<(&Interaction, &PauseEntry), Changed<Interaction>>,
+) {
+    let gamepad_pressed = |button_type| gamepads.iter().any(|gamepad| gamepad_buttons.just_pressed(GamepadButton::new(gamepad, button_type)));
+    let toggle = keyboard_input.just_pressed(KeyCode::Escape) || gamepad_pressed(GamepadButtonType::Start);
+    match pause_state.get() {
+        PauseState::Running if toggle => {
+            debug!("Paused");
+            focus.0 = 0;
+            leave_table.set_pause_state(PauseState::Paused);
+            return;
+        }
+        PauseState::Paused => {}
+        _ => return,
+    }
+    if toggle {
+        leave_table.set_pause_state(PauseState::Running);
+        return;
+    }
+
+    let count = PauseEntry::ALL.len();
+    if keyboard_input.just_pressed(KeyCode::ArrowUp) || gamepad_pressed(GamepadButtonType::DPadUp) {
+        focus.0 = (focus.0 + count - 1) % count;
+    }
+    if keyboard_input.just_pressed(KeyCode::ArrowDown) || gamepad_pressed(GamepadButtonType::DPadDown) {
+        focus.0 = (focus.0 + 1) % count;
+    }
+    let mut picked = None;
+    if keyboard_input.any_just_pressed([KeyCode::Enter, KeyCode::NumpadEnter, KeyCode::Space]) || gamepad_pressed(GamepadButtonType::South) {
+        picked = Some(PauseEntry::ALL[focus.0]);
+    }
+    for (interaction, entry) in entry_query.iter() {
+        let index = PauseEntry::ALL.iter().position(|other| other == entry).unwrap_or_default();
+        match interaction {
+            Interaction::Hovered => focus.0 = index,
+            Interaction::Pressed => {
+                focus.0 = index;
+                picked = Some(*entry);
+            }
+            Interaction::None => {}
+        }
+    }
+
+    let Some(entry) = picked else {
+        return;
+    };
+    debug!("Pause menu: {}", entry.label());
+    match entry {
+        PauseEntry::Resume => leave_table.set_pause_state(PauseState::Running),
+        PauseEntry::Settings => leave_table.set_pause_state(PauseState::Settings),
+        PauseEntry::Rules => leave_table.set_pause_state(PauseState::Rules),
+        PauseEntry::QuitToMenu => leave_table.request(PauseState::Paused),
+    }
+}
+
+///leave_dialog_input moves between Stay and Leave with the arrow keys, the d-pad or the mouse, and picks one with enter,
+/// space, the gamepad's south button or a click. Esc (or the east button) stays
+pub fn leave_dialog_input(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    gamepads: Res<Gamepads>,
+    gamepad_buttons: Res<ButtonInput<GamepadButton>>,
+    mut leave_table: LeaveTable,
+    choice_query: Query<(&Interaction, &LeaveChoice), Changed<Interaction>>,
+) {
+    let gamepad_pressed = |button_type| gamepads.iter().any(|gamepad| gamepad_buttons.just_pressed(GamepadButton::new(gamepad, button_type)));
+    if keyboard_input.just_pressed(KeyCode::Escape) || gamepad_pressed(GamepadButtonType::East) {
+        leave_table.stay();
+        return;
+    }
+    if keyboard_input.any_just_pressed([KeyCode::ArrowLeft, KeyCode::ArrowRight]) || gamepad_pressed(GamepadButtonType::DPadLeft) || gamepad_pressed(GamepadButtonType::DPadRight) {
+        leave_table.dialog.focus = match leave_table.dialog.focus {
+            LeaveChoice::Stay => LeaveChoice::Leave,
+            LeaveChoice::Leave => LeaveChoice::Stay,
+        };
+    }
+    let mut picked = None;
+    if keyboard_input.any_just_pressed([KeyCode::Enter, KeyCode::NumpadEnter, KeyCode::Space]) || gamepad_pressed(GamepadButtonType::South) {
+        picked = Some(leave_table.dialog.focus);
+    }
+    for (interaction, choice) in choice_query.iter() {
+        match interaction {
+            Interaction::Hovered => leave_table.dialog.focus = *choice,
+            Interaction::Pressed => picked = Some(*choice),
+            Interaction::None => {}
+        }
+    }
+    match picked {
+        Some(LeaveChoice::Stay) => leave_table.stay(),
+        Some(LeaveChoice::Leave) => {
+            info!("Left the table with ${} bet", leave_table.bet_value.value);
+            leave_table.leave();
+        }
+        None => {}
+    }
+}
+
+//spawn_overlay spawns a root node dimming the whole table, above it and catching the clicks meant for it
+fn spawn_overlay(commands: &mut Commands, marker: impl Component, children: impl FnOnce(&mut ChildBuilder)) {
+    commands.spawn((
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(6.0),
+                ..default()
+            },
+            background_color: BackgroundColor(PAUSE_DIM_COLOR),
+            focus_policy: FocusPolicy::Block,
+            z_index: ZIndex::Global(5),
+            ..default()
+        },
+        marker,
+    ))
+    .with_children(children);
+}
+
+//spawn_button spawns a button of the pause menu or the leave dialog, labelled in the language of the settings
+fn spawn_button(parent: &mut ChildBuilder, assets: &AssetServer, settings: &Settings, label: &'static str, value: impl Component) {
+    parent.spawn((
+        ButtonBundle {
+            style: Style {
+                width: Val::Px(220.0),
+                height: Val::Px(30.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            background_color: BackgroundColor(MENU_BUTTON_COLOR),
+            ..default()
+        },
+        value,
+    ))
+    .with_children(|button| {
+        button.spawn((
+            TextBundle::from_section(settings.language.translate(label), TextStyle {
+                font: assets.load("fonts/FiraSans-SemiBold.ttf"),
+                font_size: 18.0,
+                color: Color::WHITE,
+            }),
+            Localized(label),
+        ));
+    });
+}
+
+///spawn_pause_menu spawns the pause menu over the table
+pub fn spawn_pause_menu(mut commands: Commands, assets: Res<AssetServer>, settings: Res<Settings>) {
+    spawn_overlay(&mut commands, PauseOverlay, |parent| {
+        parent.spawn((
+            TextBundle::from_section(settings.language.translate("Paused"), TextStyle {
+                font: assets.load("fonts/FiraSans-SemiBold.ttf"),
+                font_size: 36.0,
+                color: Color::WHITE,
+            }),
+            Localized("Paused"),
+        ));
+        for entry in PauseEntry::ALL {
+            spawn_button(parent, &assets, &settings, entry.label(), entry);
+        }
+    });
+}
+
+///spawn_leave_dialog spawns the dialog asking whether to leave the table, saying what happens to the bet on it
+pub fn spawn_leave_dialog(
+    mut commands: Commands,
+    assets: Res<AssetServer>,
+    settings: Res<Settings>,
+    bet_value: Res<BetValue>,
+    balance_value: Res<BalanceValue>,
+    game_state: Res<State<GameRoundState>>,
+) {
+    let text_style = |font_size| TextStyle {
+        font: assets.load("fonts/FiraSans-SemiBold.ttf"),
+        font_size,
+        color: Color::WHITE,
+    };
+    spawn_overlay(&mut commands, LeaveDialogOverlay, |parent| {
+        parent.spawn((TextBundle::from_section(settings.language.translate("Leave the table?"), text_style(30.0)), Localized("Leave the table?")));
+        parent.spawn(
+            TextBundle::from_section(leave_warning(bet_value.value, balance_value.value, *game_state.get()), text_style(18.0))
+                .with_text_justify(JustifyText::Center),
+        );
+        parent.spawn(NodeBundle {
+            style: Style { column_gap: Val::Px(10.0), margin: UiRect::top(Val::Px(10.0)), ..default() },
+            ..default()
+        })
+        .with_children(|buttons| {
+            for choice in [LeaveChoice::Stay, LeaveChoice::Leave] {
+                spawn_button(buttons, &assets, &settings, choice.label(), choice);
+            }
+        });
+    });
+}
+
+///highlight_pause_focus colors the focused entry of the pause menu and the focused button of the leave dialog
+pub fn highlight_pause_focus(
+    focus: Res<PauseFocus>,
+    dialog: Res<LeaveDialog>,
+    mut entry_query: Query<(&PauseEntry, &mut BackgroundColor), Without<LeaveChoice>>,
+    mut choice_query: Query<(&LeaveChoice, &mut BackgroundColor), Without<PauseEntry>>,
+    added_buttons: Query<(), Or<(Added<PauseEntry>, Added<LeaveChoice>)>>,
+) {
+    if !focus.is_changed() && !dialog.is_changed() && added_buttons.is_empty() {
+        return;
+    }
+    for (entry, mut background_color) in entry_query.iter_mut() {
+        background_color.0 = if *entry == PauseEntry::ALL[focus.0] { MENU_FOCUS_COLOR } else { MENU_BUTTON_COLOR };
+    }
+    for (choice, mut background_color) in choice_query.iter_mut() {
+        background_color.0 = if *choice == dialog.focus { MENU_FOCUS_COLOR } else { MENU_BUTTON_COLOR };
+    }
+}
+
+///despawn_pause_menu removes the pause menu when it is closed
+pub fn despawn_pause_menu(mut commands: Commands, query: Query<Entity, With<PauseOverlay>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+///despawn_leave_dialog removes the leave dialog when it is answered
+pub fn despawn_leave_dialog(mut commands: Commands, query: Query<Entity, With<LeaveDialogOverlay>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+///freeze_time stops the clock of the table once it is paused, its timers are not ticked until it is resumed
+pub fn freeze_time(mut time: ResMut<Time<Virtual>>) {
+    time.pause();
+}
+
+///unfreeze_time starts the clock of the table again once it is resumed
+pub fn unfreeze_time(mut time: ResMut<Time<Virtual>>) {
+    time.unpause();
+}
+
+///resume_on_leaving resumes the table when it is left some other way than the pause menu, so the next one starts running
+pub fn resume_on_leaving(pause_state: Res<State<PauseState>>, mut next_pause_state: ResMut<NextState<PauseState>>) {
+    if *pause_state.get() != PauseState::Running {
+        next_pause_state.set(PauseState::Running);
+    }
+}
@@ -1,15 +1,18 @@
 ///player_systems module holds and implements the logic and functionality for the player
 
 use bevy::prelude::*;
-use crate::game::components::{PlayerButtonValues, Card, PlayerBalance, PlayerHand, PlayerHands, PlayerName};
+use crate::game::components::{PlayerButtonValues, Card, Companion, PlayerBalance, PlayerHand, PlayerHands, PlayerName, Seat, SeatStatus};
 use crate::game::bundles::PlayerBundle;
-use crate::game::constants::{CARD_HORIZONTAL_SPACING, CARD_VERTICAL_SPACING, NO_CARD_VALUE, PLAYER_CARDS_INITIAL_HORIZONTAL_POSITION, PLAYER_CARDS_INITIAL_VERTICAL_POSITION};
-use crate::game::in_game_systems::{spawn_player_card};
 use super::components::{DealerHand, Deck, TextComponents};
 use super::constants::GameRoundState;
-use super::in_game_systems::{spawn_keep_playing_button, spawn_result_text};
-use super::resources::{BalanceValue, BetValue, ParentNode};
-use super::traits::{Dealable, Shufflable};
+use super::errors::GameError;
+use super::dealer_systems::{deal_from_shoe, settle_hand};
+use super::events::{ActionKind, ActionTaken, CardDealt, CardHolder, HandSettled, PlayerAction, RoundOutcome, ShoeShuffled};
+use super::hand_history::{HandAction, HandHistory};
+use super::resources::{ActiveSeat, BalanceValue, BetValue, LocalSpectator, TableConfig};
+use super::rules::Rules;
+use super::round_state::RoundTransition;
+use super::traits::{Dealable, Scorable, Shufflable};
 
 ///initial_shuffle ensures the deck is shuffled before dealing
 pub fn initial_shuffle(mut deck: ResMut<Deck>) {
@@ -25,208 +28,304 @@ pub fn spawn_player(mut commands: Commands, mut deck: ResMut<Deck>, balance: Res
         deck.shuffle(); 
     }
     let mut cards: Vec<Card> = vec![];
-    for i in 0..2{
+    for _ in 0..2{
         cards.push(deck.deal());
     }
     commands.spawn(PlayerBundle{
-        player_name: PlayerName(String::from("").into()),
+        player_name: PlayerName(String::from("")),
         player_balance: PlayerBalance(balance.value as f64),
         player_hands: PlayerHands(vec![PlayerHand{
             bet: 0,
             cards: cards, 
         }]),
+        seat: Seat(0),
+        seat_status: SeatStatus::default(),
     });
 }
 
-///hit_player_hand implements the logic for when the hit button is pressed by the player
-pub fn hit_player_hand( 
+///sync_table_seats spawns or despawns player entities whenever the number of seats at the table changes.
+/// bets on seats that are removed are handed back to the player.
+pub fn sync_table_seats(
     mut commands: Commands,
-    mut deck: ResMut<Deck>,
-    assets: Res<AssetServer>,
-    parent_node: Res<ParentNode>,
-    mut next_state: ResMut<NextState<GameRoundState>>,
-    mut player_query: Query<(&mut PlayerHands, &mut PlayerBalance)>,
-    mut hit_button_query: Query<(&Button, &mut Interaction, &PlayerButtonValues)>,
-    mut bet_amount: ResMut<BetValue>
-){    
-    for (_, mut interaction, value) in hit_button_query.iter_mut(){
-        match *interaction{
-            Interaction::Pressed => {
-                match *value{
-                    PlayerButtonValues::Hit => {    
-                        let (mut player_hands, _) = player_query.single_mut(); 
-                        let player_hand = &mut player_hands.0[0];
-                        let insert_index = player_hand.cards.len();
-                        let card_to_insert = deck.deal();
-                        player_hand.cards.push(card_to_insert.clone());
-                        let position = Vec2 {
-                            x: PLAYER_CARDS_INITIAL_HORIZONTAL_POSITION + (insert_index as f32)*CARD_HORIZONTAL_SPACING,
-                            y: PLAYER_CARDS_INITIAL_VERTICAL_POSITION  + (insert_index as f32)*CARD_VERTICAL_SPACING};
-                        
-                        // println!("Inserting card: {} of {} into player hand at index {}",   player_hand.cards[insert_index].face, player_hand.cards[insert_index].suite, insert_index);
-                        
-                        commands.entity(parent_node.0).with_children(|parent|{
-                            spawn_player_card(
-                                parent,
-                                &assets, 
-                                &card_to_insert, 
-                                insert_index, 
-                                position,
-                                true,
-                            );
-                        });
-                        let bust = determine_player_bust(player_hand);
-                        if bust{
-
-                            commands.entity(parent_node.0).with_children(|parent|{
-                                let result = format!("You Lose ${}! (Bust)", bet_amount.value);
-                                spawn_result_text(
-                                    parent,
-                                    &assets,
-                                    &result
-                                );
+    table: Res<TableConfig>,
+    mut balance_value: ResMut<BalanceValue>,
+    mut bet_value: ResMut<BetValue>,
+    mut active_seat: ResMut<ActiveSeat>,
+    player_query: Query<(Entity, &Seat, &PlayerHands)>,
+){
+    if !table.is_changed() {
+        return;
+    }
+    let mut occupied = vec![false; table.seats];
+    for (entity, seat, player_hands) in player_query.iter() {
+        if seat.0 < table.seats {
+            occupied[seat.0] = true;
+            continue;
+        }
+        let refund = player_hands.0.iter().map(|hand| hand.bet as i32).sum::<i32>();
+        balance_value.value += refund;
+        bet_value.value -= refund;
+        commands.entity(entity).despawn();
+    }
+    for (seat, _) in occupied.iter().enumerate().filter(|(_, occupied)| !**occupied) {
+        commands.spawn(PlayerBundle{
+            player_name: PlayerName(format!("Seat {}", seat + 1)),
+            player_balance: PlayerBalance(balance_value.value as f64),
+            player_hands: PlayerHands(vec![PlayerHand{
+                bet: 0,
+                cards: vec![],
+            }]),
+            seat: Seat(seat),
+            seat_status: SeatStatus::default(),
+        });
+    }
+    if active_seat.0 >= table.seats {
+        active_seat.0 = table.seats - 1;
+    }
+}
 
-                                spawn_keep_playing_button(parent, &assets);
-                            });
+///seats_in_play returns the seats that were dealt into the current round, in table order
+pub fn seats_in_play<'a>(player_hands: impl Iterator<Item = (&'a Seat, &'a PlayerHands)>) -> Vec<usize> {
+    let mut seats: Vec<usize> = player_hands
+        .filter(|(_, hands)| hands.0.first().is_some_and(|hand| !hand.cards.is_empty()))
+        .map(|(seat, _)| seat.0)
+        .collect();
+    seats.sort();
+    seats
+}
 
-                            bet_amount.value = 0;
-                            next_state.set(GameRoundState::RoundEnd);
-                            
-                        }
-                        *interaction = Interaction::None;
-                    }
-                    _ => {}
-                }
-            }
-            _ => {}
+///finish_seat_turn passes the turn to the next seat in play, or to the dealer once every seat has played
+pub fn finish_seat_turn(active_seat: &mut ActiveSeat, seats_in_play: &[usize], round_transition: &mut RoundTransition) {
+    match seats_in_play.iter().copied().find(|seat| *seat > active_seat.0) {
+        Some(seat) => active_seat.0 = seat,
+        None => {
+            round_transition.request(GameRoundState::DealerHand);
         }
     }
+}
+
+///deal_card_to_seat deals one card from the shoe face up onto a hand of a seat, sending CardDealt (and ShoeShuffled when the
+/// shoe is reshuffled to deal it)
+pub fn deal_card_to_seat(
+    deck: &mut Deck,
+    card_dealt: &mut EventWriter<CardDealt>,
+    shoe_shuffled: &mut EventWriter<ShoeShuffled>,
+    seat: Seat,
+    hand: usize,
+    player_hand: &mut PlayerHand,
+) -> Card {
+    let insert_index = player_hand.cards.len();
+    let card_to_insert = deal_from_shoe(deck, shoe_shuffled);
+    player_hand.cards.push(card_to_insert.clone());
+    card_dealt.send(CardDealt { holder: CardHolder::Seat(seat.0), hand, index: insert_index, card: card_to_insert.clone(), face_up: true, shuffle: Some(deck.shuffle_count) });
+    card_to_insert
+}
 
+///place_bet_on_first_player_seat puts the amount on the first seat of the player's, just as chips would, returning whether
+/// the player had a seat to bet on
+pub fn place_bet_on_first_player_seat(
+    player_query: &mut Query<(&Seat, &mut PlayerHands, Has<Companion>)>,
+    amount: i32,
+    balance_value: &mut BalanceValue,
+    bet_value: &mut BetValue,
+) -> Result<bool, GameError> {
+    let first_seat = player_query.iter_mut()
+        .filter(|(_, _, is_companion)| !is_companion)
+        .min_by_key(|(seat, _, _)| seat.0);
+    let Some((seat, mut player_hands, _)) = first_seat else {
+        return Ok(false);
+    };
+    player_hands.0.first_mut().ok_or(GameError::MissingHand(seat.0))?.bet += amount as u64;
+    balance_value.value -= amount;
+    bet_value.value += amount;
+    Ok(true)
 }
 
-///stand_player_hand is used to implement the logic for when the player presses the stand button.
-pub fn stand_player_hand(
-    mut next_state: ResMut<NextState<GameRoundState>>,
-    mut stand_button_query: Query<(&Button, &mut Interaction, &PlayerButtonValues)>,
+///player_action_buttons sends a PlayerAction when the hit, stand, double down, deal or keep playing button is clicked.
+/// a click is read once, when the button becomes pressed, so holding it down does not repeat the action
+pub fn player_action_buttons(
+    button_query: Query<(&Interaction, &PlayerButtonValues), Changed<Interaction>>,
+    mut player_actions: EventWriter<PlayerAction>,
 ){
+    for (interaction, value) in button_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        let kind = match value {
+            PlayerButtonValues::Hit => ActionKind::Hit,
+            PlayerButtonValues::Stand => ActionKind::Stand,
+            PlayerButtonValues::DoubleDown => ActionKind::Double,
+            PlayerButtonValues::Deal => ActionKind::Deal,
+            PlayerButtonValues::KeepPlaying => ActionKind::KeepPlaying,
+            _ => continue,
+        };
+        player_actions.send(PlayerAction::new(kind));
+    }
+}
 
-    for (_, mut interaction, value) in stand_button_query.iter_mut(){
-        match *interaction{
-            Interaction::Pressed => {
-                match *value{
-                    PlayerButtonValues::Stand => {    
-                        next_state.set(GameRoundState::DealerHand);
-                        *interaction = Interaction::None;
-                    }
-                    _ => {}
-                }
-            }
-            _ => {}
+///player_action_keys sends a PlayerAction for the action keys of the keyboard (H, S, D, X to split, U to surrender and
+/// I for insurance) and for the face buttons and triggers of any gamepad
+pub fn player_action_keys(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    gamepads: Res<Gamepads>,
+    gamepad_buttons: Res<ButtonInput<GamepadButton>>,
+    mut player_actions: EventWriter<PlayerAction>,
+){
+    let bindings = [
+        (KeyCode::KeyH, GamepadButtonType::South, ActionKind::Hit),
+        (KeyCode::KeyS, GamepadButtonType::East, ActionKind::Stand),
+        (KeyCode::KeyD, GamepadButtonType::West, ActionKind::Double),
+        (KeyCode::KeyX, GamepadButtonType::North, ActionKind::Split),
+        (KeyCode::KeyU, GamepadButtonType::LeftTrigger, ActionKind::Surrender),
+        (KeyCode::KeyI, GamepadButtonType::RightTrigger, ActionKind::Insurance),
+    ];
+    for (key, gamepad_button, kind) in bindings {
+        let gamepad_pressed = gamepads.iter().any(|gamepad| gamepad_buttons.just_pressed(GamepadButton::new(gamepad, gamepad_button)));
+        if keyboard_input.just_pressed(key) || gamepad_pressed {
+            player_actions.send(PlayerAction::new(kind));
         }
-    }    
+    }
 }
 
-///double_down_player_hand is responsible for implementing the logic associated with when the player presses double down.
-pub fn double_down_player_hand(
-    mut commands: Commands,
-    mut bet_value: ResMut<BetValue>,
-    mut balance_value: ResMut<BalanceValue>,
+///validate_action checks that an action can be played on the hand it names, returning the action to play or why it is turned down
+pub fn validate_action(action: &PlayerAction, player_hands: &PlayerHands, balance: i32, rules: &Rules) -> Result<HandAction, String> {
+    let Some(player_hand) = player_hands.0.get(action.hand) else {
+        return Err(format!("there is no hand {}", action.hand + 1));
+    };
+    if player_hand.cards.is_empty() || player_hand.is_bust() {
+        return Err(String::from("the hand is not being played"));
+    }
+    let Some(hand_action) = action.kind.hand_action() else {
+        return Err(String::from("it is not offered at this table"));
+    };
+    if hand_action == HandAction::DoubleDown {
+        if !rules.allow_double_down {
+            return Err(String::from("double down is not allowed at this table"));
+        }
+        if player_hand.cards.len() != 2 {
+            return Err(String::from("only the first two cards can be doubled"));
+        }
+        if balance < player_hand.bet as i32 {
+            return Err(String::from("insufficient balance to double down"));
+        }
+    }
+    Ok(hand_action)
+}
+
+///apply_player_actions plays the actions sent for the seat whose turn it is, wherever they come from. an action is turned
+/// down when no hand of the player's is being played, when a companion is playing its seat or when validate_action refuses it,
+/// ActionTaken is sent for the others. a hand that goes bust is settled right away, its bet is lost without waiting for the
+/// dealer. a turn given to a seat that is not at the table is a game error. deal and keep playing
+/// move the round on instead, deal only while betting with a bet on the table (or at a watched table) and keep playing once
+/// the round is over
+pub fn apply_player_actions(
     mut deck: ResMut<Deck>,
-    assets: Res<AssetServer>,
-    parent_node: Res<ParentNode>,
-    mut next_state: ResMut<NextState<GameRoundState>>,
+    rules: Res<Rules>,
+    spectator: Res<LocalSpectator>,
+    mut round_transition: RoundTransition,
+    mut active_seat: ResMut<ActiveSeat>,
+    mut balance_value: ResMut<BalanceValue>,
+    mut bet_value: ResMut<BetValue>,
     mut text_query: Query<(&TextComponents, &mut Text)>,
-    mut player_query: Query<(&mut PlayerHands, &mut PlayerBalance)>,
-    mut double_down_button_query: Query<(&Button, &mut Interaction, &PlayerButtonValues)>,
-){
-    for (_, mut interaction, value) in double_down_button_query.iter_mut(){
-        match *interaction{
-            Interaction::Pressed => {
-                match *value{
-                    PlayerButtonValues::DoubleDown => {    
-                        //Check player balance:
-                        let balance = balance_value.value;
-                        let bet = bet_value.value;
-                        if balance < bet {
-                            *interaction = Interaction::None;
-                            println!("Insufficient balance to double down");
-                            return;
-                        }
-                        else {
-                            let new_bet_text = (bet*2).to_string();
-                            let new_balance_text = (balance-bet).to_string();
-                            balance_value.value -= bet_value.value;
-                            bet_value.value = bet_value.value*2;
-                            for (text_component, mut text) in text_query.iter_mut() {
-                                if let TextComponents::Bet = text_component {
-                                    text.sections[0].value = new_bet_text.clone(); 
-                                }
-                    
-                                if let TextComponents::Balance = text_component {
-                                    text.sections[0].value = new_balance_text.clone();
-                                }
-                            }
-                    
-                        }
-                        //Player balance is valid
-                        let (mut player_hands, _) = player_query.single_mut(); 
-                        let player_hand = &mut player_hands.0[0];
-                        let insert_index = player_hand.cards.len();
-                        let card_to_insert = deck.deal();
-                        player_hand.cards.push(card_to_insert.clone());
-                        let position = Vec2 {
-                            x: PLAYER_CARDS_INITIAL_HORIZONTAL_POSITION + (insert_index as f32)*CARD_HORIZONTAL_SPACING,
-                            y: PLAYER_CARDS_INITIAL_VERTICAL_POSITION  + (insert_index as f32)*CARD_VERTICAL_SPACING};
-                        commands.entity(parent_node.0).with_children(|parent|{
-                            spawn_player_card(
-                                parent,
-                                &assets, 
-                                &card_to_insert, 
-                                insert_index, 
-                                position,
-                                true,
-                            );
-                        });
-                        
-                        let bust = determine_player_bust(player_hand);
-                        if bust{
-                            next_state.set(GameRoundState::RoundEnd);
-                        }
-                        else {
-                            next_state.set(GameRoundState::DealerHand);
-                        }
-                        *interaction = Interaction::None;
-                    }
-                    _ => {}
+    mut player_query: Query<(&Seat, &mut PlayerHands, &mut SeatStatus, Has<Companion>)>,
+    dealer_query: Query<&DealerHand>,
+    mut hand_history: ResMut<HandHistory>,
+    mut player_actions: EventReader<PlayerAction>,
+    mut card_dealt: EventWriter<CardDealt>,
+    mut shoe_shuffled: EventWriter<ShoeShuffled>,
+    mut action_taken: EventWriter<ActionTaken>,
+    mut hand_settled: EventWriter<HandSettled>,
+) -> Result<(), GameError> {
+    let seats = seats_in_play(player_query.iter().map(|(seat, player_hands, _, _)| (seat, player_hands)));
+    //once the last seat has played, the rest of the actions of the frame are turned down with the hand
+    let mut dealer_turn = round_transition.current() != GameRoundState::PlayerHand;
+
+    for action in player_actions.read() {
+        match action.kind {
+            ActionKind::Deal => {
+                //at least one seat needs a bet to be dealt in, a watched table only has companions betting
+                let can_deal = round_transition.current() == GameRoundState::Betting && (bet_value.value > 0 || spectator.watching);
+                if !can_deal || !round_transition.request(GameRoundState::Dealing) {
+                    info!("Deal turned down: there is no bet to deal");
+                }
+                continue;
+            }
+            ActionKind::KeepPlaying => {
+                if round_transition.current() != GameRoundState::RoundEnd || !round_transition.request(GameRoundState::Betting) {
+                    info!("Keep Playing turned down: the round is not over");
                 }
+                continue;
             }
             _ => {}
         }
-    }    
-    //check if player has enough balance to double down on this hand
-    //yes?
-        //update balance
-        //double bet for this hand
-        //Deal 1 more card to this hand
-        //end this hand (switch state?)
-    //no?
-        //notify player insufficient balance to double down
+        if dealer_turn {
+            info!("{} turned down: no hand is being played", action.kind.label());
+            continue;
+        }
+        let (seat, mut player_hands, mut seat_status, is_companion) = player_query.iter_mut()
+            .find(|(seat, _, _, _)| seat.0 == active_seat.0)
+            .ok_or(GameError::NoSeat(active_seat.0))?;
+        let checked = if is_companion {
+            Err(format!("a companion is playing seat {}", seat.0 + 1))
+        } else {
+            validate_action(action, &player_hands, balance_value.value, &rules)
+        };
+        let hand_action = match checked {
+            Ok(hand_action) => hand_action,
+            Err(reason) => {
+                info!("{} turned down: {reason}", action.kind.label());
+                continue;
+            }
+        };
+
+        let player_hand = &mut player_hands.0[action.hand];
+        let (turn_over, card) = match hand_action {
+            HandAction::Hit => {
+                let card = deal_card_to_seat(&mut deck, &mut card_dealt, &mut shoe_shuffled, *seat, action.hand, player_hand);
+                hand_history.record_action(seat.0, HandAction::Hit, Some(&card));
+                (determine_player_bust(player_hand), Some(card))
+            }
+            HandAction::Stand => {
+                hand_history.record_action(seat.0, HandAction::Stand, None);
+                (true, None)
+            }
+            HandAction::DoubleDown => {
+                let bet = player_hand.bet as i32;
+                balance_value.value -= bet;
+                bet_value.value += bet;
+                player_hand.bet *= 2;
+                for (text_component, mut text) in text_query.iter_mut() {
+                    match text_component {
+                        TextComponents::Bet => text.sections[0].value = bet_value.value.to_string(),
+                        TextComponents::Balance => text.sections[0].value = balance_value.value.to_string(),
+                        _ => {}
+                    }
+                }
+                //deal exactly one more card and end this seat's turn
+                let card = deal_card_to_seat(&mut deck, &mut card_dealt, &mut shoe_shuffled, *seat, action.hand, player_hand);
+                hand_history.record_action(seat.0, HandAction::DoubleDown, Some(&card));
+                (true, Some(card))
+            }
+        };
+        action_taken.send(ActionTaken { seat: seat.0, hand: action.hand, action: hand_action, card, companion: false });
+        if player_hand.is_bust() {
+            let dealer_hand = dealer_query.get_single().map_err(|_| GameError::NoDealer)?;
+            let settled = settle_hand(seat, RoundOutcome::Loss, player_hand, dealer_hand, &mut seat_status, None);
+            bet_value.value -= settled.wager;
+            hand_settled.send(settled);
+        }
+        if turn_over {
+            let seat = active_seat.0;
+            finish_seat_turn(&mut active_seat, &seats, &mut round_transition);
+            dealer_turn = active_seat.0 == seat;
+        }
+    }
+    Ok(())
 }
 
 ///determine_player_bust is used for implementing the logic when a player's hand is a bust.
 pub fn determine_player_bust(player_hand: &mut PlayerHand)-> bool{
-    let mut totals: (u8, u8) = (0,0);
-    for card in &player_hand.cards{
-        let (card_total1, card_total2) = card.value;
-        totals.0 += card_total1;
-        totals.1 += card_total2;
-    }
-    if totals.1 > 21 && totals.0 > 21 {
-        return true;
-    }
-    else{
-        return false;
-    }
+    player_hand.is_bust()
 }
 
 ///spawn_test_player is used to run a test player in the command line to monitor the values associated with a player
@@ -240,30 +339,35 @@ pub fn spawn_test_player(mut commands: Commands, mut deck: ResMut<Deck>){
     let card2 = deck.deal();
 
     commands.spawn(PlayerBundle{
-        player_name: PlayerName(String::from("test").into()),
+        player_name: PlayerName(String::from("test")),
         player_balance: PlayerBalance(100.),
         player_hands: PlayerHands(vec![PlayerHand{
             bet: 100,
             cards: vec![card1, card2], 
         }]),
+        seat: Seat(0),
+        seat_status: SeatStatus::default(),
     });
 }
 
 ///test_player_hand is used for testing and monitoring the values of a player's hand in the command line
 pub fn test_player_hand(mut query: Query<&mut PlayerHands>){
-    for player_hand in &mut query{
-        let bet = player_hand.0[0].bet;
-        let card1 = &player_hand.0[0].cards[0];
-        let card2 = &player_hand.0[0].cards[1];
-        println!("Bet of {} for cards: {} of {}, {} of {}", bet, card1.face, card1.suite, card2.face, card2.suite);
+    for player_hands in &mut query{
+        let Some(player_hand) = player_hands.0.first() else {
+            continue;
+        };
+        let bet = player_hand.bet;
+        let card1 = &player_hand.cards[0];
+        let card2 = &player_hand.cards[1];
+        debug!("Bet of {} for cards: {} of {}, {} of {}", bet, card1.face, card1.suite, card2.face, card2.suite);
     }
 }
 
 ///test_player_balance_change is used for testing and monitoring the player's balance when a change is supposed to occur.
 pub fn test_player_balance_change(mut query: Query<&mut PlayerBalance>){
     for mut balance in &mut query{
-        println!("Player has balance of {}", balance.0);
+        debug!("Player has balance of {}", balance.0);
         balance.0 += 1.;
-        println!("Player has updated balance of {}", balance.0);
+        debug!("Player has updated balance of {}", balance.0);
     }
 }
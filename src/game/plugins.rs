@@ -1,22 +1,579 @@
-/// plugins module used for initializing and implementing any plugins used for the game
+/// plugins module used for initializing and implementing any plugins used for the game. the table itself is split into
+/// BlackjackCorePlugin (states, rules, dealing), BlackjackUiPlugin (screens, buttons, cards), BlackjackOnlinePlugin and
+/// BlackjackAudioPlugin, with BlackjackConsolePlugin and BlackjackInspectorPlugin for developers, each set up with its own builder methods, so another Bevy app can drop a blackjack table into its world
 
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use bevy::app::ScheduleRunnerPlugin;
+use bevy::asset::io::{AssetReader, AssetReaderError, AssetSource, AssetSourceId, PathStream, Reader};
+use bevy::asset::AssetMetaCheck;
+use bevy::input::{InputPlugin, InputSystem};
 use bevy::prelude::*;
+use bevy::state::app::StatesPlugin;
+use bevy::window::WindowMode;
+use super::audio::{apply_music_volume, play_game_sounds, play_music, GameSound, GameSounds};
+use super::autoplay::{autoplay_controls, drive_autoplay, update_autoplay_text, Autoplay};
+use super::bot::BotPlayer;
+use super::bot_systems::{drive_bot_player, start_bot_player};
+use super::companions::{companion_selection_system, place_companion_bets, play_companion_turns};
+use super::components::Deck;
+use super::console::{console_input, run_console_commands, show_console, DevConsole};
+use super::constants::{AppState, DeckState, GameRoundState, PauseState, HEADLESS_FRAME_RATE};
+use super::dealer_systems::{deal_new_round, finish_dealing, play_dealer_hand, reveal_dealer_hand, settle_round, spawn_dealer};
+use super::errors::{handle_game_error, show_game_errors, GameErrors};
+use super::events::{ActionTaken, CardDealt, DealerRevealed, HandSettled, PlayerAction, RoundStarted, ShoeShuffled};
+use super::hand_history::{write_hand_history, HandHistory};
+use super::in_game_systems::{chip_button_click_system, despawn_cards_and_reset, draw_dealt_cards, hide_round_result, in_game_setup, load_last_session, player_button_system, show_dealt_table, reset_game, show_strategy_hint, respawn_seat_markers, save_last_session, seat_selection_system, show_round_result, show_script_errors, track_app_state, track_game_state, turn_hole_card, update_seat_markers};
+use super::inspector::{show_inspector, toggle_inspector, Inspector};
+use super::logging::{log_plugin, LogSettings};
+use super::main_menu_systems::{despawn_main_menu, highlight_menu_focus, main_menu_input, spawn_main_menu};
+use super::online::OnlineSession;
+use super::pause_menu_systems::{despawn_leave_dialog, despawn_pause_menu, freeze_time, highlight_pause_focus, leave_dialog_input, pause_input, resume_on_leaving, spawn_leave_dialog, spawn_pause_menu, unfreeze_time};
+use super::online_screen_systems::{despawn_online_screen, online_input, online_screen_setup, receive_server_messages, render_online_table, send_player_actions};
+use super::player_systems::{apply_player_actions, initial_shuffle, player_action_buttons, player_action_keys, spawn_player, sync_table_seats};
+use super::replay::ReplayViewer;
+use super::replay_screen_systems::{despawn_replay_screen, render_replay_step, replay_controls, replay_screen_setup};
+use super::resources::{ActiveProfile, ActiveSeat, BalanceValue, BetValue, CompanionTimer, LastSession, LeaveDialog, LocalSpectator, MenuFocus, ParentNode, PauseFocus, SettingsFocus, StartingBalance, StatsView, TableConfig};
+use super::rules::Rules;
+use super::rules_screen_systems::{despawn_rules_screen, rules_screen_input, rules_screen_setup};
+use super::round_state::{check_exit_contract, check_phase_contract, start_round};
+use super::scenario::{start_scenario, Scenario};
+use super::scripting::{load_scripts, CompanionScripts, ScriptsDir};
+use super::settings::{apply_animation_speed, apply_hints, apply_language, apply_table_look, apply_window_settings, save_settings, Settings};
+use super::settings_screen_systems::{despawn_settings_screen, settings_screen_input, settings_screen_setup, update_settings_screen};
+use super::setup::{ingame_screen_setup, reload_home_screen, spawn_camera, start_setup};
+use super::spectator::{drive_spectated_table, seat_spectator_companions, stop_watching};
+use super::start_game_systems::start_game;
+use super::statistics::{collect_round_statistics, load_statistics, Statistics};
+use super::storage::DataDir;
+use super::stats_screen_systems::{despawn_stats_screen, stats_screen_input, stats_screen_setup, update_stats_screen};
+use super::trainer::Trainer;
+use super::trainer_screen_systems::{despawn_trainer_screen, trainer_input, trainer_screen_setup, update_trainer_text};
+use super::{DeckSystemSet, PlayerGameplaySet, SetupGameSystemSet, StartGameSystemSet};
 
-///struct StartupPlugin sets up our window for the game to be held and displayed in
-pub struct StartupPlugin;
+///struct StartupPlugin sets up our window for the game to be held and displayed in, fullscreen or not, and the logs with
+/// the LogSettings inserted before it (the defaults otherwise). the window follows the Settings inserted before it (the
+/// saved ones otherwise), fullscreen when either asks for it, and is changed as they are
+#[derive(Default)]
+pub struct StartupPlugin {
+    pub fullscreen: bool,
+}
 
 impl Plugin for StartupPlugin{
     fn build(&self, app: &mut App) {
+        let log_settings = app.world().get_resource::<LogSettings>().cloned().unwrap_or_default();
+        let data_dir = app.world().get_resource::<DataDir>().cloned().unwrap_or_default();
+        let settings = app.world().get_resource::<Settings>().cloned().unwrap_or_else(|| Settings::load(&data_dir));
         app.add_plugins(DefaultPlugins.set(WindowPlugin{
             primary_window: Some(Window{
                 title: "Blackjack In Rust".to_string(),
-                resolution: (800.,500.).into(),
+                resolution: settings.window_size.into(),
                 resizable: false,
-                ..default() //sets all other params to defaults 
+                mode: if self.fullscreen || settings.fullscreen { WindowMode::BorderlessFullscreen } else { WindowMode::Windowed },
+                ..default() //sets all other params to defaults
             }),
             ..default()
-        }));
-        println!("build setup function ran!");
+        }).set(log_plugin(&log_settings)))
+            .add_systems(Update, apply_window_settings);
+        debug!("build setup function ran!");
+    }
+}
+
+///struct HeadlessPlugin is StartupPlugin without a window, for tests and for playing without a screen: the minimal plugins
+/// with input and states, running at HEADLESS_FRAME_RATE, and a stub asset server. the screens still ask it for their
+/// fonts and cards and are handed handles, but it reads no files and every load fails right away
+pub struct HeadlessPlugin;
+
+impl Plugin for HeadlessPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_asset_source(AssetSourceId::Default, AssetSource::build().with_reader(|| Box::new(StubAssetReader)));
+        app.add_plugins((
+            MinimalPlugins.set(ScheduleRunnerPlugin::run_loop(Duration::from_secs_f64(1. / HEADLESS_FRAME_RATE))),
+            InputPlugin,
+            StatesPlugin,
+            AssetPlugin { meta_check: AssetMetaCheck::Never, ..default() },
+        ))
+            .init_asset::<Image>()
+            .init_asset::<Font>()
+            .init_asset::<TextureAtlasLayout>();
+    }
+}
+
+//StubAssetReader is the asset source of HeadlessPlugin, it has no files in it
+struct StubAssetReader;
+
+impl AssetReader for StubAssetReader {
+    async fn read<'a>(&'a self, path: &'a Path) -> Result<Box<Reader<'a>>, AssetReaderError> {
+        Err(AssetReaderError::NotFound(path.to_path_buf()))
+    }
+
+    async fn read_meta<'a>(&'a self, path: &'a Path) -> Result<Box<Reader<'a>>, AssetReaderError> {
+        Err(AssetReaderError::NotFound(path.to_path_buf()))
+    }
+
+    async fn read_directory<'a>(&'a self, path: &'a Path) -> Result<Box<PathStream>, AssetReaderError> {
+        Err(AssetReaderError::NotFound(path.to_path_buf()))
+    }
+
+    async fn is_directory<'a>(&'a self, _path: &'a Path) -> Result<bool, AssetReaderError> {
+        Ok(false)
+    }
+}
+
+///struct BlackjackCorePlugin is the table itself: the states, the rules, the shoe, the seats, dealing and settling the
+/// rounds, the statistics and the hand history, and the players that are not the player (companions, bots, autoplay).
+/// it plays the rounds the buttons of BlackjackUiPlugin start, so a table played by hand needs both
+#[derive(Clone)]
+pub struct BlackjackCorePlugin {
+    initial_state: AppState,
+    rules: Rules,
+    seed: Option<u64>,
+    starting_balance: i32,
+    scenario: Option<Scenario>,
+    bot: Option<(String, Duration)>,
+    profile: ActiveProfile,
+    scripts_dir: Option<PathBuf>,
+}
+
+impl Default for BlackjackCorePlugin {
+    fn default() -> Self {
+        Self {
+            initial_state: AppState::Start,
+            rules: Rules::default(),
+            seed: None,
+            starting_balance: StartingBalance::default().0,
+            scenario: None,
+            bot: None,
+            profile: ActiveProfile::default(),
+            scripts_dir: None,
+        }
+    }
+}
+
+impl BlackjackCorePlugin {
+    ///with_initial_state sets the screen the game starts on, the start screen by default
+    pub fn with_initial_state(mut self, initial_state: AppState) -> Self {
+        self.initial_state = initial_state;
+        self
+    }
+
+    ///with_rules sets the rules of the table
+    pub fn with_rules(mut self, rules: Rules) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    ///with_seed shuffles the shoe with the seed, the same seed dealing the same cards
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    ///with_starting_balance sets the balance the player sits down with, and gets back on the start screen
+    pub fn with_starting_balance(mut self, balance: i32) -> Self {
+        self.starting_balance = balance;
+        self
+    }
+
+    ///with_scenario deals the scenario's rigged shoe instead of the shuffled one, each time the table is sat at
+    pub fn with_scenario(mut self, scenario: Scenario) -> Self {
+        self.scenario = Some(scenario);
+        self
+    }
+
+    ///with_bot has the bot started with `command` play the player's seats, `timeout` being how long it has to answer
+    pub fn with_bot(mut self, command: impl Into<String>, timeout: Duration) -> Self {
+        self.bot = Some((command.into(), timeout));
+        self
+    }
+
+    ///with_profile keeps the statistics and hand history under the named profile instead of the default one
+    pub fn with_profile(mut self, profile: impl Into<String>) -> Self {
+        self.profile = ActiveProfile(profile.into());
+        self
+    }
+
+    ///with_scripts_dir loads the companion and autoplay scripts from the directory instead of the data directory's scripts
+    pub fn with_scripts_dir(mut self, scripts_dir: impl Into<PathBuf>) -> Self {
+        self.scripts_dir = Some(scripts_dir.into());
+        self
+    }
+}
+
+impl Plugin for BlackjackCorePlugin {
+    fn build(&self, app: &mut App) {
+        let deck = self.seed.map_or_else(Deck::default, Deck::with_seed);
+        let bot_player = self.bot.as_ref()
+            .map_or_else(BotPlayer::default, |(command, timeout)| BotPlayer::new(command, *timeout));
+        if let Some(scenario) = &self.scenario {
+            info!("Playing the scenario {}", scenario.description);
+            app.insert_resource(scenario.clone());
+        }
+        let data_dir = app.world().get_resource::<DataDir>().cloned().unwrap_or_default();
+        if !app.world().contains_resource::<Settings>() {
+            app.insert_resource(Settings::load(&data_dir));
+        }
+        app.insert_resource(ScriptsDir(self.scripts_dir.clone().unwrap_or_else(|| data_dir.scripts_dir())));
+        app.insert_resource(data_dir);
+
+        app
+            // Set system execution order
+            .configure_sets(Startup, StartGameSystemSet.before(SetupGameSystemSet))
+            .configure_sets(Startup, SetupGameSystemSet.before(DeckSystemSet))
+            .configure_sets(Update, PlayerGameplaySet.run_if(in_state(AppState::InGame)).run_if(in_state(GameRoundState::PlayerHand)))
+
+            // Add startup systems
+            .add_systems(Startup, initial_shuffle)
+            .add_systems(Startup, spawn_player)
+            .add_systems(Startup, spawn_dealer)
+            .add_systems(Startup, load_statistics)
+            .add_systems(Startup, load_last_session)
+            .add_systems(Startup, load_scripts)
+            .add_systems(Startup, start_bot_player.pipe(handle_game_error))
+
+            // Add resources
+            .insert_resource(BetValue { value: 0 })
+            .insert_resource(BalanceValue { value: self.starting_balance })
+            .insert_resource(deck)
+            .insert_resource(ParentNode::default())
+            .insert_resource(self.profile.clone())
+            .insert_resource(Statistics::default())
+            .insert_resource(StatsView::default())
+            .insert_resource(self.rules.clone())
+            .insert_resource(HandHistory::default())
+            .insert_resource(ReplayViewer::default())
+            .insert_resource(TableConfig::default())
+            .insert_resource(ActiveSeat::default())
+            .insert_resource(CompanionTimer::default())
+            .insert_resource(LocalSpectator::default())
+            .insert_resource(bot_player)
+            .insert_resource(CompanionScripts::default())
+            .insert_resource(Autoplay::default())
+            .insert_resource(StartingBalance(self.starting_balance))
+            .insert_resource(GameErrors::default())
+            .insert_resource(LastSession::default())
+
+            // Add events, the ones the table sends as the round is played are listed in the events module
+            .add_event::<PlayerAction>()
+            .add_event::<RoundStarted>()
+            .add_event::<CardDealt>()
+            .add_event::<ActionTaken>()
+            .add_event::<DealerRevealed>()
+            .add_event::<HandSettled>()
+            .add_event::<ShoeShuffled>()
+
+            // Add table seat systems
+            .add_systems(Update, sync_table_seats.run_if(in_state(AppState::InGame)))
+
+            // Add player gameplay systems, the systems that can fail are piped into handle_game_error where they are added
+            .add_systems(Update, apply_player_actions.pipe(handle_game_error).run_if(in_state(AppState::InGame)))
+            .add_systems(Update, play_companion_turns.pipe(handle_game_error).in_set(PlayerGameplaySet).run_if(in_state(AppState::InGame)))
+
+            // Add statistics systems
+            .add_systems(Update, collect_round_statistics)
+
+            // Add hand history systems
+            .add_systems(Update, write_hand_history)
+
+            // Add settings systems, the settings are saved and the table paced as soon as they change
+            .add_systems(Update, (save_settings, apply_animation_speed))
+
+            // Add spectator systems, the deals are sent before the actions are applied
+            .add_systems(Update, (seat_spectator_companions, drive_spectated_table.before(apply_player_actions).run_if(in_state(PauseState::Running))).run_if(in_state(AppState::InGame)))
+            .add_systems(OnEnter(AppState::Start), stop_watching)
+
+            // Add bot systems, like the spectator the bot sends its actions before they are applied
+            .add_systems(Update, drive_bot_player.pipe(handle_game_error).before(apply_player_actions).run_if(in_state(AppState::InGame)).run_if(in_state(PauseState::Running)))
+
+            // Add autoplay systems, sending the actions before they are applied like the bot
+            .add_systems(Update, drive_autoplay.pipe(handle_game_error).before(apply_player_actions).run_if(in_state(AppState::InGame)).run_if(in_state(PauseState::Running)))
+
+            // Add scenario systems, the rigged shoe is set up again each time the table is sat at
+            .add_systems(OnEnter(AppState::InGame), start_scenario)
+
+            // Add round phase systems, the table is dealt (and set up again) on entering its phases
+            .add_systems(Update, start_round.run_if(in_state(AppState::InGame)).run_if(in_state(GameRoundState::RoundStart)))
+            .add_systems(Update, check_phase_contract.run_if(in_state(AppState::InGame)).run_if(state_changed::<GameRoundState>))
+
+            // Add dealer systems
+            .add_systems(OnEnter(GameRoundState::Dealing), (place_companion_bets.pipe(handle_game_error), deal_new_round.pipe(handle_game_error), finish_dealing).chain())
+            .add_systems(OnEnter(GameRoundState::DealerHand), play_dealer_hand.pipe(handle_game_error))
+            .add_systems(OnEnter(GameRoundState::Settlement), settle_round.pipe(handle_game_error))
+            .add_systems(OnExit(GameRoundState::PlayerHand), reveal_dealer_hand.pipe(handle_game_error))
+
+            // Track game states on entry
+            .add_systems(OnEnter(GameRoundState::Dealing), track_game_state)
+            .add_systems(OnEnter(GameRoundState::PlayerHand), track_game_state)
+            .add_systems(OnEnter(GameRoundState::DealerHand), track_game_state)
+            .add_systems(OnEnter(GameRoundState::Settlement), track_game_state)
+            .add_systems(OnEnter(GameRoundState::RoundEnd), track_game_state)
+            .add_systems(OnEnter(GameRoundState::RoundStart), track_game_state)
+            .add_systems(OnEnter(GameRoundState::Betting), track_game_state)
+
+            .add_systems(OnEnter(GameRoundState::Betting), despawn_cards_and_reset)
+
+            // Track app states on entry
+            .add_systems(OnEnter(AppState::Start), track_app_state)
+            .add_systems(OnEnter(AppState::InGame), track_app_state)
+            .add_systems(OnEnter(AppState::Stats), track_app_state)
+            .add_systems(OnEnter(AppState::Replay), track_app_state)
+            .add_systems(OnEnter(AppState::Online), track_app_state)
+            .add_systems(OnEnter(AppState::Practice), track_app_state)
+            .add_systems(OnEnter(AppState::Settings), track_app_state)
+            .add_systems(OnEnter(AppState::Rules), track_app_state)
+
+            // Add pause systems, the table's clock stands still while it is paused and it is running again once it is left
+            .add_systems(OnExit(PauseState::Running), freeze_time)
+            .add_systems(OnEnter(PauseState::Running), unfreeze_time)
+            .add_systems(OnExit(AppState::InGame), resume_on_leaving)
+
+            // the balance the table is left with is kept for Continue before the table is reset
+            .add_systems(OnTransition { exited: AppState::InGame, entered: AppState::Start }, save_last_session)
+            .add_systems(OnEnter(AppState::Start), reset_game)
+
+            // Set initial states
+            .insert_state(self.initial_state)
+            .insert_state(DeckState::NotShuffled)
+            .insert_state(PauseState::Running)
+            .insert_state(GameRoundState::RoundStart);
+
+        // Check what each phase leaves behind as it is left
+        for state in GameRoundState::ALL {
+            app.add_systems(OnExit(state), check_exit_contract.run_if(in_state(AppState::InGame)));
+        }
+    }
+}
+
+///struct BlackjackUiPlugin is what the player sees and presses: the start screen and its main menu, the statistics,
+/// replay, practice, rules and settings screens, the table's buttons, chips, seat markers and texts, and the keyboard and
+/// gamepad controls. the cards are drawn as they are dealt
+pub struct BlackjackUiPlugin {
+    camera: bool,
+}
+
+impl Default for BlackjackUiPlugin {
+    fn default() -> Self {
+        Self { camera: true }
     }
 }
 
+impl BlackjackUiPlugin {
+    ///with_camera sets whether the plugin spawns its own 2d camera, turned off when the app already has one
+    pub fn with_camera(mut self, camera: bool) -> Self {
+        self.camera = camera;
+        self
+    }
+}
+
+impl Plugin for BlackjackUiPlugin {
+    fn build(&self, app: &mut App) {
+        if self.camera {
+            app.add_systems(Startup, spawn_camera.before(start_setup));
+        }
+
+        app
+            // Add startup systems
+            .add_systems(Startup, start_setup.in_set(StartGameSystemSet))
+
+            // Add main menu systems
+            .init_resource::<MenuFocus>()
+            .add_systems(OnEnter(AppState::Start), spawn_main_menu)
+            .add_systems(Update, (main_menu_input, highlight_menu_focus).chain().run_if(in_state(AppState::Start)))
+            .add_systems(OnExit(AppState::Start), despawn_main_menu)
+
+            // Add in-game systems
+            .add_systems(Update, start_game.in_set(StartGameSystemSet).run_if(in_state(AppState::Start)))
+            .add_systems(Update, ingame_screen_setup.in_set(SetupGameSystemSet).run_if(in_state(AppState::InGame).and_then(run_once())))
+            .add_systems(Update, chip_button_click_system.pipe(handle_game_error).in_set(SetupGameSystemSet).run_if(in_state(AppState::InGame)).run_if(in_state(PauseState::Running)))
+            .add_systems(Update, player_button_system.in_set(SetupGameSystemSet).run_if(in_state(AppState::InGame)).run_if(in_state(PauseState::Running)))
+            .add_systems(OnEnter(GameRoundState::Betting), (hide_round_result, in_game_setup.after(despawn_cards_and_reset)))
+            .add_systems(OnEnter(GameRoundState::Dealing), show_dealt_table)
+
+            // Add table seat systems, the seats are picked before they are synced and their markers redrawn after
+            .add_systems(Update, (seat_selection_system, companion_selection_system.pipe(handle_game_error)).chain().before(sync_table_seats).run_if(in_state(AppState::InGame)).run_if(in_state(PauseState::Running)))
+            .add_systems(Update, (respawn_seat_markers, update_seat_markers.pipe(handle_game_error)).chain().after(sync_table_seats).run_if(in_state(AppState::InGame)))
+            .add_systems(Update, show_script_errors.run_if(in_state(AppState::InGame)))
+
+            // Add game error systems
+            .add_systems(Update, show_game_errors.run_if(in_state(AppState::InGame)))
+
+            // Add table event systems, the cards and results are drawn from the events the table sends
+            .add_systems(Update, (draw_dealt_cards, turn_hole_card, show_round_result).chain().after(apply_player_actions).after(play_companion_turns).run_if(in_state(AppState::InGame)))
+
+            // Add player gameplay systems
+            .add_systems(Update, (player_action_buttons, player_action_keys).before(apply_player_actions).run_if(in_state(AppState::InGame).or_else(in_state(AppState::Online))).run_if(in_state(PauseState::Running)))
+
+            // Add pause systems, the keys of the table are not read while it is paused
+            .init_resource::<PauseFocus>()
+            .init_resource::<LeaveDialog>()
+            .add_systems(Update, ((pause_input, leave_dialog_input.run_if(in_state(PauseState::ConfirmLeave))), highlight_pause_focus).chain().run_if(in_state(AppState::InGame)))
+            .add_systems(OnEnter(PauseState::Paused), spawn_pause_menu)
+            .add_systems(OnExit(PauseState::Paused), despawn_pause_menu)
+            .add_systems(OnEnter(PauseState::ConfirmLeave), spawn_leave_dialog)
+            .add_systems(OnExit(PauseState::ConfirmLeave), despawn_leave_dialog)
+            .add_systems(OnEnter(PauseState::Settings), settings_screen_setup)
+            .add_systems(OnExit(PauseState::Settings), despawn_settings_screen)
+            .add_systems(OnEnter(PauseState::Rules), rules_screen_setup)
+            .add_systems(OnExit(PauseState::Rules), despawn_rules_screen)
+
+            // Add statistics systems
+            .add_systems(OnEnter(AppState::Stats), stats_screen_setup)
+            .add_systems(Update, (stats_screen_input, update_stats_screen).run_if(in_state(AppState::Stats)))
+            .add_systems(OnExit(AppState::Stats), despawn_stats_screen)
+
+            // Add replay systems
+            .add_systems(OnEnter(AppState::Replay), replay_screen_setup)
+            .add_systems(Update, (replay_controls, render_replay_step).chain().run_if(in_state(AppState::Replay)))
+            .add_systems(OnExit(AppState::Replay), despawn_replay_screen)
+
+            // Add practice systems
+            .init_resource::<Trainer>()
+            .add_systems(OnEnter(AppState::Practice), trainer_screen_setup)
+            .add_systems(Update, (trainer_input, update_trainer_text).chain().run_if(in_state(AppState::Practice)))
+            .add_systems(OnExit(AppState::Practice), despawn_trainer_screen)
+
+            // Add rules and settings systems
+            .add_systems(OnEnter(AppState::Rules), rules_screen_setup)
+            .add_systems(Update, rules_screen_input.run_if(in_state(AppState::Rules).or_else(in_state(PauseState::Rules))))
+            .add_systems(OnExit(AppState::Rules), despawn_rules_screen)
+            .init_resource::<SettingsFocus>()
+            .add_systems(OnEnter(AppState::Settings), settings_screen_setup)
+            .add_systems(Update, (settings_screen_input, update_settings_screen).chain().run_if(in_state(AppState::Settings).or_else(in_state(PauseState::Settings))))
+            .add_systems(OnExit(AppState::Settings), despawn_settings_screen)
+
+            // Add the settings the screens follow, applied once what they change has been spawned
+            .add_systems(PostUpdate, (apply_language, apply_table_look, apply_hints))
+            .add_systems(Update, show_strategy_hint.after(apply_player_actions).after(play_companion_turns).run_if(in_state(AppState::InGame)))
+
+            // Add autoplay systems, the controls are read before autoplay plays and its text shows what it did
+            .add_systems(Update, (autoplay_controls.before(drive_autoplay).run_if(in_state(PauseState::Running)), update_autoplay_text.after(drive_autoplay)).run_if(in_state(AppState::InGame)))
+
+            // Clean-up on exit
+            .add_systems(OnExit(AppState::InGame), reload_home_screen)
+            .add_systems(OnTransition { exited: AppState::Start, entered: AppState::InGame }, ingame_screen_setup);
+    }
+}
+
+///struct BlackjackOnlinePlugin is the network table: the online screen and the connection to a table server, which is
+/// made when the online screen is entered (O or W on the start screen, or starting on it)
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BlackjackOnlinePlugin {
+    address: Option<String>,
+    name: Option<String>,
+    spectating: bool,
+}
+
+impl BlackjackOnlinePlugin {
+    ///connect sits at the table server at the address
+    pub fn connect(address: impl Into<String>) -> Self {
+        Self { address: Some(address.into()), ..default() }
+    }
+
+    ///spectate watches the table server at the address without a seat
+    pub fn spectate(address: impl Into<String>) -> Self {
+        Self { address: Some(address.into()), spectating: true, ..default() }
+    }
+
+    ///with_name sets the name to sit at the table with
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+}
+
+impl Plugin for BlackjackOnlinePlugin {
+    fn build(&self, app: &mut App) {
+        let mut session = OnlineSession::default();
+        if let Some(address) = &self.address {
+            session.address = address.clone();
+        }
+        if let Some(name) = &self.name {
+            session.name = name.clone();
+        }
+        session.spectating = self.spectating;
+
+        app
+            .insert_resource(session)
+            .add_systems(OnEnter(AppState::Online), online_screen_setup)
+            .add_systems(Update, (receive_server_messages, online_input, send_player_actions.after(player_action_keys), render_online_table).chain().run_if(in_state(AppState::Online)))
+            .add_systems(OnExit(AppState::Online), despawn_online_screen);
+    }
+}
+
+///struct BlackjackAudioPlugin plays a sound for what happens at the table, and music in the background. no sounds ship
+/// with the game, each is given with with_sound (and the music with with_music) as the path of an audio asset, and the
+/// plugin stays quiet in an app without Bevy's AudioPlugin. they are played at the volumes of the Settings
+#[derive(Clone)]
+pub struct BlackjackAudioPlugin {
+    volume: f32,
+    sounds: HashMap<GameSound, String>,
+    music: Option<String>,
+}
+
+impl Default for BlackjackAudioPlugin {
+    fn default() -> Self {
+        Self { volume: 1.0, sounds: HashMap::new(), music: None }
+    }
+}
+
+impl BlackjackAudioPlugin {
+    ///with_volume sets the volume of every sound, 1 being the volume they were recorded at
+    pub fn with_volume(mut self, volume: f32) -> Self {
+        self.volume = volume;
+        self
+    }
+
+    ///with_sound plays the audio asset at the path for the sound
+    pub fn with_sound(mut self, sound: GameSound, path: impl Into<String>) -> Self {
+        self.sounds.insert(sound, path.into());
+        self
+    }
+
+    ///with_music loops the audio asset at the path in the background
+    pub fn with_music(mut self, path: impl Into<String>) -> Self {
+        self.music = Some(path.into());
+        self
+    }
+}
+
+impl Plugin for BlackjackAudioPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<bevy::audio::AudioPlugin>() {
+            warn!("BlackjackAudioPlugin needs Bevy's AudioPlugin, the game is played without sounds");
+            return;
+        }
+        app
+            .insert_resource(GameSounds { volume: self.volume, sounds: self.sounds.clone(), music: self.music.clone() })
+            .add_systems(Startup, play_music)
+            .add_systems(Update, play_game_sounds.after(apply_player_actions).after(play_companion_turns))
+            .add_systems(Update, apply_music_volume);
+    }
+}
+
+///struct BlackjackConsolePlugin adds the developer console, opened with the backtick key, whose commands change the
+/// running table: the cards dealt, the balance, the phase of the round, the shoe and the rules
+#[derive(Clone, Default)]
+pub struct BlackjackConsolePlugin;
+
+impl Plugin for BlackjackConsolePlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<DevConsole>()
+            .add_systems(PreUpdate, console_input.after(InputSystem))
+            .add_systems(Update, (run_console_commands, show_console).chain().before(apply_player_actions));
+    }
+}
+
+///struct BlackjackInspectorPlugin adds the inspector, shown over the game with F3: the states, the deck (and, in a debug
+/// build, the cards it deals next), the cards on screen, the balance, the bet and the frame time
+#[derive(Clone, Default)]
+pub struct BlackjackInspectorPlugin;
+
+impl Plugin for BlackjackInspectorPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<Inspector>()
+            .add_systems(Last, (toggle_inspector, show_inspector).chain());
+    }
+}
@@ -0,0 +1,121 @@
+///protocol module defines the messages sent between the table server and its clients. every message is one line of json,
+/// and the first message of a connection is a Hello (to play) or a Spectate (to watch) carrying the protocol version
+/// the client speaks.
+
+use std::io::{self, BufRead, Write};
+use serde::{Deserialize, Serialize};
+use super::events::RoundOutcome;
+use super::hand_history::HandAction;
+
+///PROTOCOL_VERSION is bumped whenever a message changes in a way older clients or servers cannot read
+pub const PROTOCOL_VERSION: u32 = 2;
+
+///DEFAULT_SERVER_ADDRESS is where the server listens and the client connects when no address is given
+pub const DEFAULT_SERVER_ADDRESS: &str = "127.0.0.1:7878";
+
+///HIDDEN_CARD is sent in place of the dealer's hole card until it is revealed
+pub const HIDDEN_CARD: &str = "??";
+
+///enum ClientMessage lists the messages a client sends to the server
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientMessage {
+    ///first message of a connection, token being the one handed out by a previous Welcome to take the same seat back
+    Hello {
+        version: u32,
+        name: String,
+        token: Option<String>,
+    },
+    ///first message of a connection that only watches the table, without a seat or bankroll
+    Spectate { version: u32 },
+    Bet { amount: i32 },
+    Action { action: HandAction },
+    ///gives the seat up for good, a dropped connection keeps the seat for a reconnect
+    Leave,
+}
+
+///enum ServerMessage lists the messages the server sends to its clients
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerMessage {
+    ///answer to a Hello, token is what the client sends to reconnect to the seat
+    Welcome {
+        version: u32,
+        seat: usize,
+        token: String,
+        snapshot: TableSnapshot,
+    },
+    ///answer to a Spectate, with the full table so a spectator can join at any point of the shoe
+    Watching {
+        version: u32,
+        snapshot: TableSnapshot,
+    },
+    ///sent to every client whenever the table changes
+    Snapshot { snapshot: TableSnapshot },
+    ///sent to every client once the dealer has played and every seat is paid
+    Settle { results: Vec<SeatResult> },
+    Error { message: String },
+}
+
+///enum TablePhase is where the table is in its round
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TablePhase {
+    ///waiting for every connected seat to bet, the cards of the last round stay on the table until the next deal
+    Betting,
+    ///seats are playing their hands in table order
+    PlayerTurns,
+}
+
+///struct SeatSnapshot is the public state of one occupied seat, cards are written using Card::code
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SeatSnapshot {
+    pub seat: usize,
+    pub name: String,
+    pub connected: bool,
+    pub bankroll: i32,
+    pub bet: i32,
+    pub cards: Vec<String>,
+}
+
+///struct TableSnapshot is everything a client needs to draw the table
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TableSnapshot {
+    pub round: u64,
+    pub phase: TablePhase,
+    pub seats: usize,
+    pub players: Vec<SeatSnapshot>,
+    ///the hole card is sent as HIDDEN_CARD until the dealer plays
+    pub dealer_cards: Vec<String>,
+    pub active_seat: Option<usize>,
+}
+
+///struct SeatResult is how one seat did in a settled round
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SeatResult {
+    pub seat: usize,
+    pub outcome: RoundOutcome,
+    pub wager: i32,
+    ///amount handed back to the seat, the wager included
+    pub payout: i32,
+    pub bankroll: i32,
+}
+
+///write_message sends a message as a single line
+pub fn write_message<T: Serialize>(writer: &mut impl Write, message: &T) -> io::Result<()> {
+    let line = serde_json::to_string(message)
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+    writeln!(writer, "{line}")?;
+    writer.flush()
+}
+
+///read_message reads the next message, returning None once the other side has closed the connection
+pub fn read_message<T: for<'de> Deserialize<'de>>(reader: &mut impl BufRead) -> io::Result<Option<T>> {
+    let mut line = String::new();
+    if reader.read_line(&mut line)? == 0 {
+        return Ok(None);
+    }
+    serde_json::from_str(&line)
+        .map(Some)
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))
+}
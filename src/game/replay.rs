@@ -0,0 +1,167 @@
+///replay module turns a recorded hand history back into the sequence of table states it went through,
+/// so the replay screen can step through any past round.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+use bevy::prelude::*;
+use super::components::{Card, PlayerHand};
+use super::hand_history::{parse_hand_record, HandAction, HandRecord};
+use super::strategy::basic_strategy;
+use super::traits::Scorable;
+
+///struct ReplayStep is the state of the table at one point of a replayed round
+#[derive(Clone)]
+pub struct ReplayStep {
+    pub description: String,
+    ///the cards of every seat dealt in, along with the seat index
+    pub seat_cards: Vec<(usize, Vec<Card>)>,
+    ///the seat whose turn it is, if any
+    pub active_seat: Option<usize>,
+    pub dealer_cards: Vec<Card>,
+    pub dealer_hole_card_hidden: bool,
+    ///the action basic strategy recommends here, only set when the player has a decision to make
+    pub optimal_action: Option<HandAction>,
+    ///the action the player actually took from here
+    pub action_taken: Option<HandAction>,
+}
+
+///struct / resource ReplayViewer holds the loaded hand history and which round and step of it is being shown
+#[derive(Resource, Default)]
+pub struct ReplayViewer {
+    pub records: Vec<HandRecord>,
+    pub round: usize,
+    pub step: usize,
+    pub show_optimal_action: bool,
+}
+
+impl ReplayViewer {
+    ///steps returns the steps of the round currently selected
+    pub fn steps(&self) -> Vec<ReplayStep> {
+        self.records.get(self.round).map(replay_steps).unwrap_or_default()
+    }
+
+    ///select_round moves to another round, starting it from its first step
+    pub fn select_round(&mut self, round: usize) {
+        if round < self.records.len() {
+            self.round = round;
+            self.step = 0;
+        }
+    }
+}
+
+///load_hand_records reads every record from a hand history file, lines that cannot be read are skipped
+pub fn load_hand_records(path: &Path) -> io::Result<Vec<HandRecord>> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(error) => return Err(error),
+    };
+    let mut records = Vec::new();
+    for (line_number, line) in contents.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        match parse_hand_record(line) {
+            Ok(record) => records.push(record),
+            Err(error) => warn!("Skipping hand history line {}: {error}", line_number + 1),
+        }
+    }
+    Ok(records)
+}
+
+fn cards_from_codes(codes: &[String]) -> Vec<Card> {
+    codes.iter().filter_map(|code| Card::from_code(code)).collect()
+}
+
+///replay_steps rebuilds every state of a recorded round: the deal, each player action seat by seat, the dealer reveal,
+/// each dealer draw, and the result
+pub fn replay_steps(record: &HandRecord) -> Vec<ReplayStep> {
+    let mut seat_cards: Vec<(usize, Vec<Card>)> = record.seats.iter()
+        .map(|seat_record| (seat_record.seat, cards_from_codes(&seat_record.player_cards)))
+        .collect();
+    let mut dealer_cards = cards_from_codes(&record.dealer_cards);
+    let dealer_up_card = dealer_cards.get(1).map_or(0, |card| card.value.1);
+    let multiple_seats = record.seats.len() > 1;
+    let mut steps = Vec::new();
+
+    let total_bet: i32 = record.seats.iter().map(|seat_record| seat_record.bet).sum();
+    let mut description = format!("Round {} - bet ${}", record.round_id, total_bet);
+    for (i, seat_record) in record.seats.iter().enumerate() {
+        let seat_label = if multiple_seats { format!("Seat {}: ", seat_record.seat + 1) } else { String::new() };
+        for action_record in &seat_record.actions {
+            let player_hand = PlayerHand { cards: seat_cards[i].1.clone(), bet: seat_record.bet as u64 };
+            let can_double = record.rules.can_double(&player_hand);
+            steps.push(ReplayStep {
+                description,
+                seat_cards: seat_cards.clone(),
+                active_seat: Some(seat_record.seat),
+                dealer_cards: dealer_cards.clone(),
+                dealer_hole_card_hidden: true,
+                optimal_action: Some(basic_strategy(player_hand.best_total(), player_hand.is_soft(), dealer_up_card, can_double)),
+                action_taken: Some(action_record.action),
+            });
+            description = match action_record.card.as_deref().and_then(Card::from_code) {
+                Some(card) => {
+                    let code = card.code();
+                    seat_cards[i].1.push(card);
+                    let total = PlayerHand { cards: seat_cards[i].1.clone(), bet: 0 }.best_total();
+                    format!("{seat_label}{} {} ({})", action_record.action.label(), code, total)
+                }
+                None => format!("{seat_label}{}", action_record.action.label()),
+            };
+        }
+    }
+    steps.push(ReplayStep {
+        description,
+        seat_cards: seat_cards.clone(),
+        active_seat: None,
+        dealer_cards: dealer_cards.clone(),
+        dealer_hole_card_hidden: true,
+        optimal_action: None,
+        action_taken: None,
+    });
+
+    steps.push(ReplayStep {
+        description: String::from("Dealer reveals"),
+        seat_cards: seat_cards.clone(),
+        active_seat: None,
+        dealer_cards: dealer_cards.clone(),
+        dealer_hole_card_hidden: false,
+        optimal_action: None,
+        action_taken: None,
+    });
+    for code in &record.dealer_draws {
+        if let Some(card) = Card::from_code(code) {
+            dealer_cards.push(card);
+            steps.push(ReplayStep {
+                description: format!("Dealer draws {code}"),
+                seat_cards: seat_cards.clone(),
+                active_seat: None,
+                dealer_cards: dealer_cards.clone(),
+                dealer_hole_card_hidden: false,
+                optimal_action: None,
+                action_taken: None,
+            });
+        }
+    }
+
+    let mut results = Vec::new();
+    for seat_record in &record.seats {
+        let seat_label = if multiple_seats { format!("Seat {}: ", seat_record.seat + 1) } else { String::new() };
+        results.push(match seat_record.outcome {
+            Some(outcome) => format!("{seat_label}{outcome:?}: paid ${} on ${} wagered", seat_record.payout, seat_record.wager),
+            None => format!("{seat_label}not settled"),
+        });
+    }
+    steps.push(ReplayStep {
+        description: format!("{}, balance ${}", results.join("   "), record.balance),
+        seat_cards,
+        active_seat: None,
+        dealer_cards,
+        dealer_hole_card_hidden: false,
+        optimal_action: None,
+        action_taken: None,
+    });
+    steps
+}
@@ -0,0 +1,312 @@
+///replay screen systems module builds the hand replay screen, re-renders the selected step of a recorded round
+/// with the same card helpers used in game, and handles the replay controls
+
+use bevy::prelude::*;
+use super::components::{Seat, ReplayControl, ReplayScreen, ReplayTable, ReplayText, ReplayTimeline, ReplayTimelineStep};
+use super::constants::{AppState, CARD_HORIZONTAL_SPACING, CARD_VERTICAL_SPACING, DEALER_CARDS_INITIAL_HORIZONTAL_POSITION, DEALER_CARDS_INITIAL_VERTICAL_POSITION};
+use super::hand_history::hand_history_path;
+use super::in_game_systems::{seat_card_position, spawn_dealer_card, spawn_player_card};
+use super::replay::{load_hand_records, ReplayViewer};
+use super::resources::ActiveProfile;
+use super::storage::DataDir;
+
+static TIMELINE_WIDTH: f32 = 760.0;
+
+///replay_screen_setup loads the hand history of the active profile, selects the latest round and spawns the replay screen
+pub fn replay_screen_setup(
+    mut commands: Commands,
+    assets: Res<AssetServer>,
+    data_dir: Res<DataDir>,
+    profile: Res<ActiveProfile>,
+    mut viewer: ResMut<ReplayViewer>,
+) {
+    let records = match load_hand_records(&hand_history_path(&data_dir, &profile.0)) {
+        Ok(records) => records,
+        Err(error) => {
+            warn!("Could not load hand history for profile {}: {error}", profile.0);
+            Vec::new()
+        }
+    };
+    *viewer = ReplayViewer {
+        round: records.len().saturating_sub(1),
+        records,
+        ..default()
+    };
+
+    commands.spawn((
+        NodeBundle {
+            style: Style {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                ..default()
+            },
+            background_color: BackgroundColor(Color::srgb(0.05, 0.3, 0.15)),
+            ..default()
+        },
+        ReplayScreen,
+    ))
+    .with_children(|parent| {
+        parent.spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    ..default()
+                },
+                ..default()
+            },
+            ReplayTable,
+        ));
+
+        spawn_replay_text(parent, &assets, Vec2::new(10.0, 10.0), 20.0, ReplayText::Title);
+        spawn_replay_text(parent, &assets, Vec2::new(10.0, 290.0), 24.0, ReplayText::Description);
+        spawn_replay_text(parent, &assets, Vec2::new(10.0, 320.0), 20.0, ReplayText::OptimalAction);
+
+        let controls = [
+            (5.0, "<< Round", ReplayControl::PreviousRound),
+            (105.0, "< Step", ReplayControl::StepBack),
+            (205.0, "Step >", ReplayControl::StepForward),
+            (305.0, "Round >>", ReplayControl::NextRound),
+            (405.0, "Optimal", ReplayControl::ToggleOptimalAction),
+        ];
+        for (left, label, control) in controls {
+            spawn_replay_button(parent, &assets, Vec2::new(left, 360.0), label, control);
+        }
+
+        parent.spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    left: Val::Px(20.0),
+                    top: Val::Px(440.0),
+                    width: Val::Px(TIMELINE_WIDTH),
+                    height: Val::Px(30.0),
+                    flex_direction: FlexDirection::Row,
+                    ..default()
+                },
+                ..default()
+            },
+            ReplayTimeline,
+        ));
+    });
+}
+
+///spawn_replay_text is a helper function for spawning one of the text elements on the replay screen, the text itself is filled in by render_replay_step
+fn spawn_replay_text(
+    parent: &mut ChildBuilder,
+    assets: &Res<AssetServer>,
+    position: Vec2,
+    font_size: f32,
+    text_component: ReplayText,
+) {
+    parent.spawn(TextBundle {
+        text: Text::from_section(
+            "",
+            TextStyle {
+                font: assets.load("fonts/FiraSans-SemiBold.ttf"),
+                font_size,
+                color: Color::WHITE,
+            },
+        ),
+        style: Style {
+            position_type: PositionType::Absolute,
+            left: Val::Px(position.x),
+            top: Val::Px(position.y),
+            ..default()
+        },
+        ..default()
+    })
+    .insert(text_component);
+}
+
+///spawn_replay_button is a helper function for spawning the replay control buttons, styled like the in game buttons
+fn spawn_replay_button(
+    parent: &mut ChildBuilder,
+    assets: &Res<AssetServer>,
+    position: Vec2,
+    label: &str,
+    control: ReplayControl,
+) {
+    parent.spawn(ButtonBundle {
+        style: Style {
+            width: Val::Px(90.0),
+            height: Val::Px(50.0),
+            position_type: PositionType::Absolute,
+            left: Val::Px(position.x),
+            top: Val::Px(position.y),
+            border: UiRect::all(Val::Px(5.0)),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            margin: UiRect { left: Val::Px(10.0), bottom: Val::Px(10.0), ..default() },
+            ..default()
+        },
+        border_color: BorderColor(Color::BLACK),
+        background_color: BackgroundColor(Color::srgb(0.15, 0.15, 0.15)),
+        ..default()
+    })
+    .insert(control)
+    .with_children(|parent| {
+        parent.spawn(TextBundle::from_section(
+            label,
+            TextStyle {
+                font: assets.load("fonts/FiraSans-SemiBold.ttf"),
+                font_size: 18.0,
+                color: Color::srgb(0.9, 0.9, 0.9),
+            },
+        ));
+    });
+}
+
+///render_replay_step respawns the cards, texts and timeline of the replay screen whenever the selected round or step changes
+pub fn render_replay_step(
+    mut commands: Commands,
+    assets: Res<AssetServer>,
+    viewer: Res<ReplayViewer>,
+    table_query: Query<Entity, With<ReplayTable>>,
+    timeline_query: Query<Entity, With<ReplayTimeline>>,
+    mut text_query: Query<(&ReplayText, &mut Text)>,
+    added_screen: Query<(), Added<ReplayScreen>>,
+) {
+    if !viewer.is_changed() && added_screen.is_empty() {
+        return;
+    }
+    let (Ok(table), Ok(timeline)) = (table_query.get_single(), timeline_query.get_single()) else {
+        return;
+    };
+    commands.entity(table).despawn_descendants();
+    commands.entity(timeline).despawn_descendants();
+
+    let steps = viewer.steps();
+    let Some(step) = steps.get(viewer.step) else {
+        for (text_component, mut text) in text_query.iter_mut() {
+            text.sections[0].value = match text_component {
+                ReplayText::Title => String::from("No hands recorded yet - Esc to go back"),
+                _ => String::new(),
+            };
+        }
+        return;
+    };
+
+    let table_seats = viewer.records.get(viewer.round).map_or(1, |record| record.table_seats);
+    commands.entity(table).with_children(|parent| {
+        for (seat, cards) in step.seat_cards.iter() {
+            for (i, card) in cards.iter().enumerate() {
+                let position = seat_card_position(*seat, table_seats, i);
+                spawn_player_card(parent, &assets, card, Seat(*seat), i, position, true);
+            }
+        }
+        for (i, card) in step.dealer_cards.iter().enumerate() {
+            let position = Vec2 {
+                x: DEALER_CARDS_INITIAL_HORIZONTAL_POSITION + (i as f32)*CARD_HORIZONTAL_SPACING,
+                y: DEALER_CARDS_INITIAL_VERTICAL_POSITION + (i as f32)*CARD_VERTICAL_SPACING};
+            let face_up = i != 0 || !step.dealer_hole_card_hidden;
+            spawn_dealer_card(parent, &assets, card, i, position, face_up, true);
+        }
+    });
+
+    //one scrubber segment per step, the current step is highlighted
+    let segment_width = (TIMELINE_WIDTH / steps.len() as f32).min(60.0);
+    commands.entity(timeline).with_children(|parent| {
+        for i in 0..steps.len() {
+            let color = if i == viewer.step { Color::srgb(0.9, 0.75, 0.2) } else { Color::srgb(0.15, 0.15, 0.15) };
+            parent.spawn(ButtonBundle {
+                style: Style {
+                    width: Val::Px(segment_width - 4.0),
+                    height: Val::Px(20.0),
+                    margin: UiRect::right(Val::Px(4.0)),
+                    ..default()
+                },
+                background_color: BackgroundColor(color),
+                ..default()
+            })
+            .insert(ReplayTimelineStep(i));
+        }
+    });
+
+    for (text_component, mut text) in text_query.iter_mut() {
+        text.sections[0].value = match text_component {
+            ReplayText::Title => format!(
+                "Hand {} of {}   step {} of {}   (arrows: step/round, O: optimal, Esc: back)",
+                viewer.round + 1, viewer.records.len(), viewer.step + 1, steps.len()),
+            ReplayText::Description => match step.active_seat {
+                Some(seat) if table_seats > 1 => format!("Seat {} to act - {}", seat + 1, step.description),
+                _ => step.description.clone(),
+            },
+            ReplayText::OptimalAction => match (viewer.show_optimal_action, step.optimal_action, step.action_taken) {
+                (true, Some(optimal), Some(taken)) => {
+                    let verdict = if optimal == taken { "correct" } else { "mistake" };
+                    format!("Optimal: {}   played: {} ({verdict})", optimal.label(), taken.label())
+                }
+                _ => String::new(),
+            },
+        };
+    }
+}
+
+///replay_controls handles the replay buttons, the timeline scrubber and the keyboard shortcuts on the replay screen
+pub fn replay_controls(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut viewer: ResMut<ReplayViewer>,
+    mut control_query: Query<(&mut Interaction, &ReplayControl)>,
+    mut timeline_query: Query<(&mut Interaction, &ReplayTimelineStep), Without<ReplayControl>>,
+) {
+    let mut pressed_control = None;
+    for (mut interaction, control) in control_query.iter_mut() {
+        if *interaction == Interaction::Pressed {
+            pressed_control = Some(*control);
+            *interaction = Interaction::None;
+        }
+    }
+    for (mut interaction, timeline_step) in timeline_query.iter_mut() {
+        if *interaction == Interaction::Pressed {
+            viewer.step = timeline_step.0;
+            *interaction = Interaction::None;
+        }
+    }
+
+    if keyboard_input.just_pressed(KeyCode::ArrowLeft) {
+        pressed_control = Some(ReplayControl::StepBack);
+    }
+    if keyboard_input.just_pressed(KeyCode::ArrowRight) {
+        pressed_control = Some(ReplayControl::StepForward);
+    }
+    if keyboard_input.just_pressed(KeyCode::ArrowUp) {
+        pressed_control = Some(ReplayControl::PreviousRound);
+    }
+    if keyboard_input.just_pressed(KeyCode::ArrowDown) {
+        pressed_control = Some(ReplayControl::NextRound);
+    }
+    if keyboard_input.just_pressed(KeyCode::KeyO) {
+        pressed_control = Some(ReplayControl::ToggleOptimalAction);
+    }
+    if keyboard_input.just_pressed(KeyCode::Escape) || keyboard_input.just_pressed(KeyCode::Backspace) {
+        next_state.set(AppState::Start);
+    }
+
+    let Some(control) = pressed_control else {
+        return;
+    };
+    let last_step = viewer.steps().len().saturating_sub(1);
+    match control {
+        ReplayControl::PreviousRound => {
+            let round = viewer.round.saturating_sub(1);
+            viewer.select_round(round);
+        }
+        ReplayControl::NextRound => {
+            let round = viewer.round + 1;
+            viewer.select_round(round);
+        }
+        ReplayControl::StepBack => viewer.step = viewer.step.saturating_sub(1),
+        ReplayControl::StepForward => viewer.step = (viewer.step + 1).min(last_step),
+        ReplayControl::ToggleOptimalAction => viewer.show_optimal_action = !viewer.show_optimal_action,
+    }
+}
+
+///despawn_replay_screen removes the replay screen when leaving it
+pub fn despawn_replay_screen(mut commands: Commands, query: Query<Entity, With<ReplayScreen>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
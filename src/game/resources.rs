@@ -1,6 +1,9 @@
 ///resources module holds our resources that we access and update throughout ingame sessions
 
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use super::components::LeaveChoice;
+use super::constants::{PauseState, COMPANION_ACTION_DELAY, SPECTATOR_ROUND_DELAY};
 
 ///struct / resource BalanceValue is used for displaying and updating the player's balance throughout the lifetime of a match
 #[derive(Resource,Default)]
@@ -23,4 +26,101 @@ impl Default for ParentNode {
     fn default() -> Self {
         Self(Entity::PLACEHOLDER)
     }
-}
\ No newline at end of file
+}
+///struct / resource ActiveProfile holds the name of the profile whose saved data (statistics, etc) is currently in use
+#[derive(Resource, Clone)]
+pub struct ActiveProfile(pub String);
+
+impl Default for ActiveProfile {
+    fn default() -> Self {
+        Self(String::from("default"))
+    }
+}
+
+///enum / resource StatsView is used to switch the statistics screen between lifetime and current session counters
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq)]
+pub enum StatsView {
+    #[default]
+    Lifetime,
+    Session,
+}
+
+///struct / resource StartingBalance is the balance the player sits down with, and gets back when going home
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct StartingBalance(pub i32);
+
+impl Default for StartingBalance {
+    fn default() -> Self {
+        Self(1000)
+    }
+}
+
+///struct / resource LastSession holds the balance the player left the table with, Continue on the main menu sits back
+/// down with it. there is none before the table is first left, or when it was left broke
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LastSession {
+    pub balance: Option<i32>,
+}
+
+///struct / resource MenuFocus is the index of the main menu entry that has the focus, enter activates it
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq)]
+pub struct MenuFocus(pub usize);
+
+///struct / resource PauseFocus is the index of the pause menu entry that has the focus, enter activates it
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq)]
+pub struct PauseFocus(pub usize);
+
+///struct / resource LeaveDialog is the button of the leave dialog that has the focus, and where staying goes back to:
+/// the table when Home was pressed, the pause menu when Quit to Menu was picked
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq)]
+pub struct LeaveDialog {
+    pub focus: LeaveChoice,
+    pub return_to: PauseState,
+}
+
+///struct / resource SettingsFocus is the index of the settings screen row that has the focus, left and right change it
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq)]
+pub struct SettingsFocus(pub usize);
+
+///struct / resource TableConfig holds how many seats (1 to MAX_SEATS) are at the table
+#[derive(Resource, Debug, Clone)]
+pub struct TableConfig {
+    pub seats: usize,
+}
+
+impl Default for TableConfig {
+    fn default() -> Self {
+        Self { seats: 1 }
+    }
+}
+
+///struct / resource ActiveSeat is the seat chips are placed on while betting, and the seat whose turn it is while playing
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq)]
+pub struct ActiveSeat(pub usize);
+
+///struct / resource CompanionTimer paces the companions, one action each time it finishes
+#[derive(Resource)]
+pub struct CompanionTimer(pub Timer);
+
+impl Default for CompanionTimer {
+    fn default() -> Self {
+        Self(Timer::from_seconds(COMPANION_ACTION_DELAY, TimerMode::Once))
+    }
+}
+
+///struct / resource LocalSpectator is set while the player watches a table of companions instead of playing,
+/// the timer paces the deals so every round can be followed
+#[derive(Resource)]
+pub struct LocalSpectator {
+    pub watching: bool,
+    pub timer: Timer,
+}
+
+impl Default for LocalSpectator {
+    fn default() -> Self {
+        Self {
+            watching: false,
+            timer: Timer::from_seconds(SPECTATOR_ROUND_DELAY, TimerMode::Once),
+        }
+    }
+}
@@ -0,0 +1,225 @@
+///round_state module is the transition table of GameRoundState: which phases can follow each phase, what each phase
+/// can count on while it lasts and what it leaves behind when it ends. systems change phase through RoundTransition, which
+/// logs and turns down any transition that is not in the table.
+
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use super::components::{DealerHand, PlayerHands};
+use super::constants::GameRoundState;
+use super::resources::BetValue;
+use super::traits::Scorable;
+
+impl GameRoundState {
+    ///ALL lists every phase in the order a round goes through them
+    pub const ALL: [GameRoundState; 7] = [
+        GameRoundState::RoundStart,
+        GameRoundState::Betting,
+        GameRoundState::Dealing,
+        GameRoundState::PlayerHand,
+        GameRoundState::DealerHand,
+        GameRoundState::Settlement,
+        GameRoundState::RoundEnd,
+    ];
+
+    ///next_states returns the phases that can follow this one in a round. any phase can also go back to RoundStart,
+    /// which is how leaving the table resets the round
+    pub fn next_states(&self) -> &'static [GameRoundState] {
+        match self {
+            GameRoundState::RoundStart => &[GameRoundState::Betting],
+            GameRoundState::Betting => &[GameRoundState::Dealing],
+            //nothing is dealt when no seat has a bet
+            GameRoundState::Dealing => &[GameRoundState::PlayerHand, GameRoundState::Betting],
+            GameRoundState::PlayerHand => &[GameRoundState::DealerHand],
+            GameRoundState::DealerHand => &[GameRoundState::Settlement],
+            GameRoundState::Settlement => &[GameRoundState::RoundEnd],
+            GameRoundState::RoundEnd => &[GameRoundState::Betting],
+        }
+    }
+
+    ///can_transition_to returns whether the table has a transition from this phase to the next one
+    pub fn can_transition_to(&self, next: GameRoundState) -> bool {
+        next == GameRoundState::RoundStart || self.next_states().contains(&next)
+    }
+
+    ///label returns the name of the phase as it is logged
+    pub fn label(&self) -> &'static str {
+        match self {
+            GameRoundState::RoundStart => "Round Start",
+            GameRoundState::Betting => "Betting",
+            GameRoundState::Dealing => "Dealing",
+            GameRoundState::PlayerHand => "Player Hand",
+            GameRoundState::DealerHand => "Dealer Hand",
+            GameRoundState::Settlement => "Settlement",
+            GameRoundState::RoundEnd => "Round End",
+        }
+    }
+}
+
+///struct RoundTransition is the system parameter phase changes go through, it only sets the transitions of the table
+#[derive(SystemParam)]
+pub struct RoundTransition<'w> {
+    state: Res<'w, State<GameRoundState>>,
+    next_state: ResMut<'w, NextState<GameRoundState>>,
+}
+
+impl RoundTransition<'_> {
+    ///current returns the phase the round is in
+    pub fn current(&self) -> GameRoundState {
+        *self.state.get()
+    }
+
+    ///request sets the next phase if the table allows it from the current one, returning whether it was set. a reset to
+    /// RoundStart wins over any other phase requested in the same frame
+    pub fn request(&mut self, next: GameRoundState) -> bool {
+        let current = self.current();
+        if next != GameRoundState::RoundStart && matches!(*self.next_state, NextState::Pending(GameRoundState::RoundStart)) {
+            info!("Round transition turned down, the round is being reset: {} -> {}", current.label(), next.label());
+            return false;
+        }
+        if !current.can_transition_to(next) {
+            warn!("Illegal round transition turned down: {} -> {}", current.label(), next.label());
+            return false;
+        }
+        self.next_state.set(next);
+        true
+    }
+}
+
+///struct RoundSnapshot is the part of the table the phase contracts are checked against
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RoundSnapshot {
+    ///number of cards of each seat dealt into the round
+    pub seat_cards: Vec<usize>,
+    pub dealer_cards: usize,
+    ///total bet of the player's on the table
+    pub bet: i32,
+    ///number of seats dealt in whose hand is not bust
+    pub standing_seats: usize,
+    pub dealer_total: u8,
+}
+
+impl RoundSnapshot {
+    //dealt_in checks that at least one seat is dealt in, with two cards or more, along with the dealer's two cards
+    fn dealt_in(&self) -> Result<(), String> {
+        if self.seat_cards.is_empty() {
+            return Err(String::from("no seat is dealt in"));
+        }
+        if self.seat_cards.iter().any(|cards| *cards < 2) {
+            return Err(String::from("a seat has fewer than two cards"));
+        }
+        if self.dealer_cards != 2 {
+            return Err(format!("the dealer has {} cards instead of two", self.dealer_cards));
+        }
+        Ok(())
+    }
+}
+
+///phase_contract checks what a phase can count on while it lasts, returning what is wrong otherwise:
+/// - Betting starts from an empty table, no seat and not the dealer have cards
+/// - Dealing deals two cards to every seat with a bet and to the dealer, or nothing when no seat has a bet
+/// - PlayerHand has at least one seat dealt in, with two cards or more, and the dealer's two cards
+/// - DealerHand has the dealer's hand, the dealer draws on entry and the round is then settled
+/// - Settlement and RoundEnd have every bet paid out or lost, none is left on the table
+pub fn phase_contract(state: GameRoundState, snapshot: &RoundSnapshot) -> Result<(), String> {
+    match state {
+        GameRoundState::RoundStart => Ok(()),
+        GameRoundState::Betting if !snapshot.seat_cards.is_empty() || snapshot.dealer_cards > 0 => {
+            Err(String::from("cards of the last round are still on the table"))
+        }
+        GameRoundState::Betting => Ok(()),
+        GameRoundState::Dealing if snapshot.seat_cards.is_empty() && snapshot.dealer_cards == 0 => Ok(()),
+        GameRoundState::Dealing | GameRoundState::PlayerHand => snapshot.dealt_in(),
+        GameRoundState::DealerHand if snapshot.dealer_cards < 2 => Err(String::from("the dealer has no hand")),
+        GameRoundState::DealerHand => Ok(()),
+        GameRoundState::Settlement | GameRoundState::RoundEnd if snapshot.bet != 0 => {
+            Err(format!("{} is still bet on the table", snapshot.bet))
+        }
+        GameRoundState::Settlement | GameRoundState::RoundEnd => Ok(()),
+    }
+}
+
+///exit_contract checks what a phase leaves behind when it ends for the next one, returning what is wrong otherwise. a
+/// reset to RoundStart can happen at any point of the round and is not checked:
+/// - Betting ends before anything is dealt
+/// - Dealing ends with every seat with a bet and the dealer dealt in, or with nothing dealt when it goes back to Betting
+/// - PlayerHand ends with the hands dealt still on the table, the dealer's two cards untouched
+/// - DealerHand ends with the dealer done drawing: 17 or more, unless every seat went bust
+/// - Settlement and RoundEnd end with no bet left on the table
+pub fn exit_contract(state: GameRoundState, next: GameRoundState, snapshot: &RoundSnapshot) -> Result<(), String> {
+    if next == GameRoundState::RoundStart {
+        return Ok(());
+    }
+    match state {
+        GameRoundState::RoundStart => Ok(()),
+        GameRoundState::Betting if !snapshot.seat_cards.is_empty() || snapshot.dealer_cards > 0 => {
+            Err(String::from("cards were dealt before the bets were closed"))
+        }
+        GameRoundState::Betting => Ok(()),
+        GameRoundState::Dealing if next == GameRoundState::Betting => match snapshot.seat_cards.is_empty() && snapshot.dealer_cards == 0 {
+            true => Ok(()),
+            false => Err(String::from("cards were dealt to a round that went back to betting")),
+        },
+        GameRoundState::Dealing | GameRoundState::PlayerHand => snapshot.dealt_in(),
+        GameRoundState::DealerHand if snapshot.dealer_cards < 2 => Err(String::from("the dealer has no hand")),
+        GameRoundState::DealerHand if snapshot.standing_seats > 0 && snapshot.dealer_total < 17 => {
+            Err(format!("the dealer stopped drawing on {}", snapshot.dealer_total))
+        }
+        GameRoundState::DealerHand => Ok(()),
+        GameRoundState::Settlement | GameRoundState::RoundEnd if snapshot.bet != 0 => {
+            Err(format!("{} is still bet on the table", snapshot.bet))
+        }
+        GameRoundState::Settlement | GameRoundState::RoundEnd => Ok(()),
+    }
+}
+
+//round_snapshot takes the part of the table the contracts are checked against
+fn round_snapshot(bet_value: &BetValue, player_query: &Query<&PlayerHands>, dealer_query: &Query<&DealerHand>) -> RoundSnapshot {
+    let dealt_hands: Vec<_> = player_query.iter()
+        .filter_map(|player_hands| player_hands.0.first())
+        .filter(|player_hand| !player_hand.cards.is_empty())
+        .collect();
+    let dealer_hand = dealer_query.get_single().ok();
+    RoundSnapshot {
+        seat_cards: dealt_hands.iter().map(|player_hand| player_hand.cards.len()).collect(),
+        dealer_cards: dealer_hand.map_or(0, |dealer_hand| dealer_hand.cards.len()),
+        bet: bet_value.value,
+        standing_seats: dealt_hands.iter().filter(|player_hand| !player_hand.is_bust()).count(),
+        dealer_total: dealer_hand.map_or(0, |dealer_hand| dealer_hand.best_total()),
+    }
+}
+
+///check_phase_contract checks the contract of a phase on its first frame, once its entry systems have run, and logs
+/// what is wrong
+pub fn check_phase_contract(
+    game_state: Res<State<GameRoundState>>,
+    bet_value: Res<BetValue>,
+    player_query: Query<&PlayerHands>,
+    dealer_query: Query<&DealerHand>,
+) {
+    let snapshot = round_snapshot(&bet_value, &player_query, &dealer_query);
+    if let Err(reason) = phase_contract(*game_state.get(), &snapshot) {
+        error!("Round contract broken in {}: {reason}", game_state.get().label());
+    }
+}
+
+///check_exit_contract checks the exit contract of the phase being left, before the entry systems of the next phase run,
+/// and logs what is wrong
+pub fn check_exit_contract(
+    mut transitions: EventReader<StateTransitionEvent<GameRoundState>>,
+    bet_value: Res<BetValue>,
+    player_query: Query<&PlayerHands>,
+    dealer_query: Query<&DealerHand>,
+) {
+    let Some(StateTransitionEvent { exited: Some(exited), entered: Some(entered) }) = transitions.read().last().cloned() else {
+        return;
+    };
+    let snapshot = round_snapshot(&bet_value, &player_query, &dealer_query);
+    if let Err(reason) = exit_contract(exited, entered, &snapshot) {
+        error!("Round contract broken leaving {} for {}: {reason}", exited.label(), entered.label());
+    }
+}
+
+///start_round leaves RoundStart for Betting once the table is shown
+pub fn start_round(mut round_transition: RoundTransition) {
+    round_transition.request(GameRoundState::Betting);
+}
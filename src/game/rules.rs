@@ -0,0 +1,46 @@
+///rules module holds the rules a table can be played with, the presets that can be picked by name, and the checks the
+/// table makes against them
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use super::components::PlayerHand;
+
+///struct / resource Rules holds the table rules the game is played with, they are also written into every hand history record
+#[derive(Resource, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Rules {
+    ///whether the dealer draws on a soft 17 (true) or stands on every 17 (false)
+    pub dealer_hits_soft_17: bool,
+    pub allow_double_down: bool,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Self {
+            dealer_hits_soft_17: true,
+            allow_double_down: true,
+        }
+    }
+}
+
+impl Rules {
+    ///PRESETS are the names of the rules that can be picked without a rules file
+    pub const PRESETS: [&'static str; 3] = ["h17", "s17", "no-double"];
+
+    ///preset returns the rules of a preset: h17 (the default, the dealer hits soft 17), s17 (the dealer stands on every 17)
+    /// or no-double (h17 without double down)
+    pub fn preset(name: &str) -> Option<Rules> {
+        match name {
+            "h17" => Some(Rules::default()),
+            "s17" => Some(Rules { dealer_hits_soft_17: false, ..default() }),
+            "no-double" => Some(Rules { allow_double_down: false, ..default() }),
+            _ => None,
+        }
+    }
+
+    ///can_double returns true when the table allows double down and the hand still has only its first two cards, whether
+    /// the bet can be covered is left to the caller
+    pub fn can_double(&self, player_hand: &PlayerHand) -> bool {
+        self.allow_double_down && player_hand.cards.len() == 2
+    }
+}
@@ -0,0 +1,71 @@
+///rules screen systems module builds the Rules screen: how the game is played and the rules of the table it is played at
+
+use bevy::prelude::*;
+use super::components::RulesScreen;
+use super::constants::{AppState, PauseState};
+use super::rules::Rules;
+
+///rules_text lays out the rules of the game along with the table's own rules
+pub fn rules_text(rules: &Rules) -> String {
+    format!(
+        "Rules\n\n\
+        Get closer to 21 than the dealer without going over. Number cards count their value, faces count 10,\n\
+        and aces count 11 unless that busts the hand, then 1.\n\n\
+        Place a bet with the chips and deal. Hit to draw a card, stand to keep your hand{}.\n\
+        Over 21 is a bust and loses. Once you stand the dealer draws to 17 or more.\n\
+        A win pays even money, a push hands the bet back.\n\n\
+        This table: the dealer {} soft 17, double down is {}.\n\n\
+        Esc: back",
+        if rules.allow_double_down { ", or double down to double the bet and draw one last card" } else { "" },
+        if rules.dealer_hits_soft_17 { "hits" } else { "stands on" },
+        if rules.allow_double_down { "allowed" } else { "not allowed" },
+    )
+}
+
+///rules_screen_setup spawns the rules screen over the start screen, or over the table from the pause menu
+pub fn rules_screen_setup(mut commands: Commands, assets: Res<AssetServer>, rules: Res<Rules>) {
+    commands.spawn((
+        NodeBundle {
+            style: Style {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                padding: UiRect::all(Val::Px(15.0)),
+                ..default()
+            },
+            background_color: BackgroundColor(Color::srgb(0.05, 0.2, 0.1)),
+            z_index: ZIndex::Global(5),
+            ..default()
+        },
+        RulesScreen,
+    ))
+    .with_children(|parent| {
+        parent.spawn(TextBundle::from_section(rules_text(&rules), TextStyle {
+            font: assets.load("fonts/FiraSans-SemiBold.ttf"),
+            font_size: 18.0,
+            color: Color::WHITE,
+        }));
+    });
+}
+
+///rules_screen_input goes back with Esc, to the start screen or the pause menu it was opened from
+pub fn rules_screen_input(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    pause_state: Res<State<PauseState>>,
+    mut next_pause_state: ResMut<NextState<PauseState>>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    if keyboard_input.just_pressed(KeyCode::Escape) || keyboard_input.just_pressed(KeyCode::Backspace) {
+        if *pause_state.get() == PauseState::Rules {
+            next_pause_state.set(PauseState::Paused);
+        } else {
+            next_state.set(AppState::Start);
+        }
+    }
+}
+
+///despawn_rules_screen removes the rules screen when leaving it
+pub fn despawn_rules_screen(mut commands: Commands, query: Query<Entity, With<RulesScreen>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
@@ -0,0 +1,91 @@
+///scenario module loads scenario files, json files listing the exact order the cards are dealt in along with an optional
+/// starting balance and rules, so a hand can be played again exactly as it went. `--scenario <path>` plays one in place
+/// of the shuffled deck, and the scenarios directory holds the hands the integration tests play through.
+
+use std::fs;
+use std::path::Path;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use super::components::{Card, Deck};
+use super::resources::{BalanceValue, BetValue};
+use super::rules::Rules;
+
+///SCENARIOS_DIRECTORY is where the scenarios shipped with the game are kept
+pub const SCENARIOS_DIRECTORY: &str = "scenarios";
+
+//the rest of the shoe, once the scenario's cards are dealt, is shuffled with this seed so it is the same every time
+const SCENARIO_SEED: u64 = 0;
+
+///struct / resource Scenario is a rigged shoe: the cards are dealt in the order listed, from the first card of the first seat
+/// (for a single seat: the player's first card, the dealer's hole card, the player's second card, the dealer's up card, then
+/// every card drawn in turn)
+#[derive(Resource, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Scenario {
+    #[serde(default)]
+    pub description: String,
+    ///card codes separated by spaces, e.g. "AS KH 8C 8D"
+    pub cards: String,
+    ///balance the player sits down with, instead of the usual 1000
+    #[serde(default)]
+    pub balance: Option<i32>,
+    #[serde(default)]
+    pub rules: Option<Rules>,
+    ///what the player does, in order, when the scenario is played by the tests: "bet <amount>", "deal", "hit", "stand",
+    /// "double", "split" or "keep_playing"
+    #[serde(default)]
+    pub plays: Vec<String>,
+    ///balance once every play is made and the last round is settled
+    #[serde(default)]
+    pub expected_balance: Option<i32>,
+}
+
+impl Scenario {
+    ///parse reads a scenario from json, turning down any card code that is not a card
+    pub fn parse(source: &str) -> Result<Scenario, String> {
+        let scenario: Scenario = serde_json::from_str(source).map_err(|error| error.to_string())?;
+        if let Some(code) = scenario.cards.split_whitespace().find(|code| Card::from_code(code).is_none()) {
+            return Err(format!("{code} is not a card"));
+        }
+        if scenario.cards.split_whitespace().count() >= 52 {
+            return Err(String::from("a scenario lists at most 51 cards"));
+        }
+        Ok(scenario)
+    }
+
+    ///load reads a scenario file
+    pub fn load(path: &Path) -> Result<Scenario, String> {
+        let source = fs::read_to_string(path).map_err(|error| format!("{}: {error}", path.display()))?;
+        Scenario::parse(&source).map_err(|error| format!("{}: {error}", path.display()))
+    }
+
+    ///shoe returns the cards of the scenario in the order they are dealt
+    pub fn shoe(&self) -> Vec<Card> {
+        self.cards.split_whitespace().filter_map(Card::from_code).collect()
+    }
+
+    ///deck returns the rigged deck dealing the scenario's cards first
+    pub fn deck(&self) -> Deck {
+        Deck::rigged(&self.shoe(), SCENARIO_SEED)
+    }
+}
+
+///start_scenario sets the table up for the scenario each time it is sat at: the rigged deck, the rules and the balance
+pub fn start_scenario(
+    scenario: Option<Res<Scenario>>,
+    mut deck: ResMut<Deck>,
+    mut rules: ResMut<Rules>,
+    mut balance_value: ResMut<BalanceValue>,
+    mut bet_value: ResMut<BetValue>,
+) {
+    let Some(scenario) = scenario else {
+        return;
+    };
+    *deck = scenario.deck();
+    if let Some(scenario_rules) = &scenario.rules {
+        *rules = scenario_rules.clone();
+    }
+    if let Some(balance) = scenario.balance {
+        balance_value.value = balance;
+        bet_value.value = 0;
+    }
+}
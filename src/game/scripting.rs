@@ -0,0 +1,246 @@
+///scripting module runs strategies and betting systems written as rhai scripts, loaded from the scripts directory when the game starts
+/// (the data directory's, or the one given with `--scripts`).
+/// a script defines `fn action(hand)` returning "hit", "stand" or "double", and/or `fn bet(table)` returning the amount to bet.
+/// a script that fails is reported and the companion playing it falls back on its personality.
+
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use bevy::prelude::*;
+use rhai::{Dynamic, Engine, Map, Scope, AST};
+use super::components::PlayerHand;
+use super::events::RoundOutcome;
+use super::hand_history::HandAction;
+use super::traits::Scorable;
+
+///struct / resource ScriptsDir is the directory the scripts are loaded from, the scripts directory of the data directory
+/// unless BlackjackCorePlugin is given another one
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct ScriptsDir(pub PathBuf);
+
+//a script is stopped after this many operations, so an endless loop cannot hang the game
+const MAX_SCRIPT_OPERATIONS: u64 = 100_000;
+
+///struct ScriptError is a script that could not be loaded or failed while running
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScriptError {
+    pub script: String,
+    pub message: String,
+}
+
+impl fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.script, self.message)
+    }
+}
+
+///struct HandInputs is what a strategy script is given to decide on an action, as the fields of its `hand` argument
+#[derive(Debug, Clone, PartialEq)]
+pub struct HandInputs {
+    pub total: u8,
+    pub soft: bool,
+    ///whether the hand is two cards of the same value
+    pub pair: bool,
+    ///value of the dealer's face up card, aces count as 11
+    pub up_card: u8,
+    pub running_count: i32,
+    pub bankroll: i32,
+    pub can_double: bool,
+}
+
+impl HandInputs {
+    ///from_hand returns the inputs of a hand being played against the dealer's up card
+    pub fn from_hand(player_hand: &PlayerHand, up_card: u8, running_count: i32, bankroll: i32, can_double: bool) -> HandInputs {
+        HandInputs {
+            total: player_hand.best_total(),
+            soft: player_hand.is_soft(),
+            pair: player_hand.cards.len() == 2 && player_hand.cards[0].value == player_hand.cards[1].value,
+            up_card,
+            running_count,
+            bankroll,
+            can_double,
+        }
+    }
+}
+
+///struct BetInputs is what a betting system script is given to size its next bet, as the fields of its `table` argument
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BetInputs {
+    pub bankroll: i32,
+    pub running_count: i32,
+    ///the bet the system starts from and goes back to
+    pub base_bet: i32,
+    pub progress: BetProgress,
+}
+
+///struct BetProgress is how the last rounds went, which is what betting progressions are built on
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BetProgress {
+    pub last_bet: i32,
+    pub last_outcome: Option<RoundOutcome>,
+    pub wins_in_a_row: i32,
+    pub losses_in_a_row: i32,
+}
+
+impl BetProgress {
+    ///record adds a settled round, a push leaving both streaks as they were
+    pub fn record(&mut self, outcome: RoundOutcome, wager: i32) {
+        self.last_bet = wager;
+        self.last_outcome = Some(outcome);
+        match outcome {
+            RoundOutcome::Blackjack | RoundOutcome::Win => {
+                self.wins_in_a_row += 1;
+                self.losses_in_a_row = 0;
+            }
+            RoundOutcome::Loss => {
+                self.losses_in_a_row += 1;
+                self.wins_in_a_row = 0;
+            }
+            RoundOutcome::Push => {}
+        }
+    }
+}
+
+///struct Script is a compiled script along with the name it is shown with (its file name)
+pub struct Script {
+    pub name: String,
+    engine: Engine,
+    ast: AST,
+}
+
+impl Script {
+    ///compile compiles the source of a script, it has to define an action or a bet function to be of any use
+    pub fn compile(name: &str, source: &str) -> Result<Script, ScriptError> {
+        let mut engine = Engine::new();
+        engine.set_max_operations(MAX_SCRIPT_OPERATIONS);
+        let ast = engine.compile(source).map_err(|error| ScriptError { script: name.to_string(), message: error.to_string() })?;
+        let script = Script { name: name.to_string(), engine, ast };
+        if !script.plays() && !script.bets() {
+            return Err(script.error("defines neither fn action(hand) nor fn bet(table)"));
+        }
+        Ok(script)
+    }
+
+    ///load reads and compiles a script file, named after the file
+    pub fn load(path: &Path) -> Result<Script, ScriptError> {
+        let name = path.file_stem().map_or_else(|| path.display().to_string(), |stem| stem.to_string_lossy().into_owned());
+        let source = fs::read_to_string(path).map_err(|error| ScriptError { script: name.clone(), message: error.to_string() })?;
+        Script::compile(&name, &source)
+    }
+
+    ///plays returns whether the script defines a strategy
+    pub fn plays(&self) -> bool {
+        self.defines("action")
+    }
+
+    ///bets returns whether the script defines a betting system
+    pub fn bets(&self) -> bool {
+        self.defines("bet")
+    }
+
+    ///choose_action runs the script's action function, which returns "hit", "stand" or "double"
+    pub fn choose_action(&self, inputs: &HandInputs) -> Result<HandAction, ScriptError> {
+        let mut hand = Map::new();
+        hand.insert("total".into(), Dynamic::from(inputs.total as i64));
+        hand.insert("soft".into(), Dynamic::from(inputs.soft));
+        hand.insert("pair".into(), Dynamic::from(inputs.pair));
+        hand.insert("up_card".into(), Dynamic::from(inputs.up_card as i64));
+        hand.insert("running_count".into(), Dynamic::from(inputs.running_count as i64));
+        hand.insert("bankroll".into(), Dynamic::from(inputs.bankroll as i64));
+        hand.insert("can_double".into(), Dynamic::from(inputs.can_double));
+
+        let result = self.call("action", hand)?;
+        let action = result.into_string().map_err(|type_name| self.error(&format!("action returned a {type_name} instead of a string")))?;
+        match action.to_lowercase().as_str() {
+            "hit" => Ok(HandAction::Hit),
+            "stand" => Ok(HandAction::Stand),
+            "double" | "double_down" if inputs.can_double => Ok(HandAction::DoubleDown),
+            "double" | "double_down" => Err(self.error("action returned double on a hand that cannot double")),
+            _ => Err(self.error(&format!("action returned \"{action}\", expected hit, stand or double"))),
+        }
+    }
+
+    ///bet runs the script's bet function, the amount it returns has to be between 1 and the bankroll
+    pub fn bet(&self, inputs: &BetInputs) -> Result<i32, ScriptError> {
+        let last_outcome = match inputs.progress.last_outcome {
+            Some(RoundOutcome::Blackjack | RoundOutcome::Win) => "win",
+            Some(RoundOutcome::Loss) => "loss",
+            Some(RoundOutcome::Push) => "push",
+            None => "",
+        };
+        let mut table = Map::new();
+        table.insert("bankroll".into(), Dynamic::from(inputs.bankroll as i64));
+        table.insert("running_count".into(), Dynamic::from(inputs.running_count as i64));
+        table.insert("base_bet".into(), Dynamic::from(inputs.base_bet as i64));
+        table.insert("last_bet".into(), Dynamic::from(inputs.progress.last_bet as i64));
+        table.insert("last_outcome".into(), Dynamic::from(last_outcome.to_string()));
+        table.insert("wins_in_a_row".into(), Dynamic::from(inputs.progress.wins_in_a_row as i64));
+        table.insert("losses_in_a_row".into(), Dynamic::from(inputs.progress.losses_in_a_row as i64));
+
+        let result = self.call("bet", table)?;
+        let amount = result.as_int().map_err(|type_name| self.error(&format!("bet returned a {type_name} instead of a number")))?;
+        if amount < 1 || amount > inputs.bankroll as i64 {
+            return Err(self.error(&format!("bet returned {amount}, which is not between 1 and the bankroll of {}", inputs.bankroll)));
+        }
+        Ok(amount as i32)
+    }
+
+    fn defines(&self, function: &str) -> bool {
+        self.ast.iter_functions().any(|metadata| metadata.name == function && metadata.params.len() == 1)
+    }
+
+    fn call(&self, function: &str, argument: Map) -> Result<Dynamic, ScriptError> {
+        self.engine.call_fn::<Dynamic>(&mut Scope::new(), &self.ast, function, (argument,))
+            .map_err(|error| self.error(&error.to_string()))
+    }
+
+    fn error(&self, message: &str) -> ScriptError {
+        ScriptError { script: self.name.clone(), message: message.to_string() }
+    }
+}
+
+///struct / resource CompanionScripts holds the scripts companions can play with, and the last error a script ran into
+#[derive(Resource, Default)]
+pub struct CompanionScripts {
+    pub scripts: Vec<Script>,
+    pub last_error: Option<ScriptError>,
+}
+
+impl CompanionScripts {
+    ///report keeps the error to be shown on the table
+    pub fn report(&mut self, error: ScriptError) {
+        warn!("Script error in {error}");
+        self.last_error = Some(error);
+    }
+
+    ///choose_action returns the action the script at the index plays with the hand, None when there is no such script
+    /// playing hands or it failed (the failure being reported)
+    pub fn choose_action(&mut self, index: usize, inputs: &HandInputs) -> Option<HandAction> {
+        let script = self.scripts.get(index).filter(|script| script.plays())?;
+        match script.choose_action(inputs) {
+            Ok(action) => Some(action),
+            Err(error) => {
+                self.report(error);
+                None
+            }
+        }
+    }
+}
+
+///load_scripts loads every .rhai file of the scripts directory in name order, scripts that do not compile are reported
+pub fn load_scripts(scripts_dir: Res<ScriptsDir>, mut companion_scripts: ResMut<CompanionScripts>) {
+    let Ok(entries) = fs::read_dir(&scripts_dir.0) else {
+        return;
+    };
+    let mut paths: Vec<_> = entries.flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|extension| extension == "rhai"))
+        .collect();
+    paths.sort();
+    for path in paths {
+        match Script::load(&path) {
+            Ok(script) => companion_scripts.scripts.push(script),
+            Err(error) => companion_scripts.report(error),
+        }
+    }
+}
@@ -0,0 +1,209 @@
+///server module hosts a Table over tcp. every connection gets its own thread, the table itself sits behind a mutex so
+/// each message is applied in full before the next one, and every change is broadcast to all connected clients.
+
+use std::io::{self, BufReader};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+use std::time::Duration;
+use bevy::log::{error, warn};
+use super::protocol::{read_message, write_message, ClientMessage, SeatResult, ServerMessage, PROTOCOL_VERSION};
+use super::table::Table;
+
+//how long a write to one client may block, broadcasts are written while the table is locked
+static WRITE_TIMEOUT: Duration = Duration::from_secs(2);
+
+///struct ConnectedClient is one open connection, seated or spectating
+struct ConnectedClient {
+    id: u64,
+    writer: TcpStream,
+}
+
+///struct ServerState is what every connection thread shares
+struct ServerState {
+    table: Table,
+    clients: Vec<ConnectedClient>,
+    next_client_id: u64,
+}
+
+impl ServerState {
+    ///broadcast sends a message to every connected client, clients that cannot be written to are dropped by their own thread
+    fn broadcast(&mut self, message: &ServerMessage) {
+        for client in self.clients.iter_mut() {
+            let _ = write_message(&mut client.writer, message);
+        }
+    }
+
+    ///broadcast_changes sends the results of a settled round, if any, followed by a snapshot of the table
+    fn broadcast_changes(&mut self, results: Option<Vec<SeatResult>>) {
+        //spectators get the same messages as the players, the dealer's hole card is hidden in the snapshot itself
+        if let Some(results) = results {
+            self.broadcast(&ServerMessage::Settle { results });
+        }
+        let snapshot = self.table.snapshot();
+        self.broadcast(&ServerMessage::Snapshot { snapshot });
+    }
+}
+
+///struct ServerHandle is returned by start_server, address being where the server ended up listening
+pub struct ServerHandle {
+    pub address: SocketAddr,
+}
+
+///start_server binds the address (port 0 picks a free port) and serves the table on a background thread
+pub fn start_server(address: &str, table: Table) -> io::Result<ServerHandle> {
+    let listener = TcpListener::bind(address)?;
+    let address = listener.local_addr()?;
+    thread::spawn(move || serve(listener, table));
+    Ok(ServerHandle { address })
+}
+
+///serve accepts connections until the listener fails, handing each one to its own thread
+pub fn serve(listener: TcpListener, table: Table) {
+    let state = Arc::new(Mutex::new(ServerState { table, clients: Vec::new(), next_client_id: 0 }));
+    for stream in listener.incoming() {
+        match stream {
+            Ok(stream) => {
+                //a client that stops reading must not hold every other connection up
+                if let Err(error) = stream.set_write_timeout(Some(WRITE_TIMEOUT)) {
+                    warn!("Could not set a write timeout: {error}");
+                    continue;
+                }
+                let state = Arc::clone(&state);
+                thread::spawn(move || {
+                    if let Err(error) = handle_connection(stream, &state) {
+                        warn!("Connection closed: {error}");
+                    }
+                });
+            }
+            Err(error) => error!("Could not accept connection: {error}"),
+        }
+    }
+}
+
+fn lock(state: &Mutex<ServerState>) -> MutexGuard<'_, ServerState> {
+    //a panicking connection thread must not take the whole table down with it
+    state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+///handle_connection seats the client from its Hello, then applies its messages to the table until it leaves or drops.
+/// a client that opens with Spectate only gets the table sent to it
+fn handle_connection(stream: TcpStream, state: &Mutex<ServerState>) -> io::Result<()> {
+    let mut writer = stream.try_clone()?;
+    let mut reader = BufReader::new(stream);
+
+    let (version, name, token) = match read_message(&mut reader)? {
+        Some(ClientMessage::Hello { version, name, token }) => (version, Some(name), token),
+        Some(ClientMessage::Spectate { version }) => (version, None, None),
+        _ => {
+            write_message(&mut writer, &ServerMessage::Error { message: String::from("the first message must be a hello or a spectate") })?;
+            return Ok(());
+        }
+    };
+    if version != PROTOCOL_VERSION {
+        let message = format!("protocol version {version} is not supported, the server speaks version {PROTOCOL_VERSION}");
+        write_message(&mut writer, &ServerMessage::Error { message })?;
+        return Ok(());
+    }
+    let Some(name) = name else {
+        return spectate(&mut reader, writer, state);
+    };
+
+    let (client_id, seat) = {
+        let mut state = lock(state);
+        let joined = match &token {
+            Some(token) => state.table.rejoin(token).map(|seat| (seat, token.clone())),
+            None => state.table.join(&name),
+        };
+        let (seat, token) = match joined {
+            Ok(joined) => joined,
+            Err(error) => {
+                write_message(&mut writer, &ServerMessage::Error { message: error.to_string() })?;
+                return Ok(());
+            }
+        };
+        let snapshot = state.table.snapshot();
+        write_message(&mut writer, &ServerMessage::Welcome { version: PROTOCOL_VERSION, seat, token, snapshot })?;
+
+        let client_id = state.next_client_id;
+        state.next_client_id += 1;
+        state.clients.push(ConnectedClient { id: client_id, writer: writer.try_clone()? });
+        state.broadcast_changes(None);
+        (client_id, seat)
+    };
+
+    let result = client_loop(&mut reader, &mut writer, state, seat);
+
+    let mut state = lock(state);
+    state.clients.retain(|client| client.id != client_id);
+    //a seat given up with Leave is no longer connected
+    let mut results = None;
+    if state.table.seat(seat).is_some_and(|table_seat| table_seat.connected) {
+        results = state.table.disconnect(seat);
+    }
+    state.broadcast_changes(results);
+    result
+}
+
+///client_loop handles the messages of a seated client, answering anything the table turns down with an Error
+fn client_loop(
+    reader: &mut BufReader<TcpStream>,
+    writer: &mut TcpStream,
+    state: &Mutex<ServerState>,
+    seat: usize,
+) -> io::Result<()> {
+    while let Some(message) = read_message::<ClientMessage>(reader)? {
+        let mut state = lock(state);
+        let applied = match message {
+            ClientMessage::Hello { .. } | ClientMessage::Spectate { .. } => {
+                write_message(writer, &ServerMessage::Error { message: String::from("already seated") })?;
+                continue;
+            }
+            ClientMessage::Bet { amount } => state.table.bet(seat, amount).map(|_| None),
+            ClientMessage::Action { action } => state.table.action(seat, action),
+            ClientMessage::Leave => {
+                let results = state.table.leave(seat);
+                state.broadcast_changes(results);
+                return Ok(());
+            }
+        };
+        match applied {
+            Ok(results) => state.broadcast_changes(results),
+            Err(error) => write_message(writer, &ServerMessage::Error { message: error.to_string() })?,
+        }
+    }
+    Ok(())
+}
+
+///spectate sends the whole table to a spectator and keeps it up to date, anything the spectator sends is turned down
+fn spectate(reader: &mut BufReader<TcpStream>, mut writer: TcpStream, state: &Mutex<ServerState>) -> io::Result<()> {
+    let client_id = {
+        let mut state = lock(state);
+        let snapshot = state.table.snapshot();
+        write_message(&mut writer, &ServerMessage::Watching { version: PROTOCOL_VERSION, snapshot })?;
+        let client_id = state.next_client_id;
+        state.next_client_id += 1;
+        state.clients.push(ConnectedClient { id: client_id, writer: writer.try_clone()? });
+        client_id
+    };
+
+    let mut result = Ok(());
+    loop {
+        match read_message::<ClientMessage>(reader) {
+            Ok(Some(ClientMessage::Leave)) | Ok(None) => break,
+            Ok(Some(_)) => {
+                let message = String::from("spectators cannot play, join the table to play");
+                if let Err(error) = write_message(&mut writer, &ServerMessage::Error { message }) {
+                    result = Err(error);
+                    break;
+                }
+            }
+            Err(error) => {
+                result = Err(error);
+                break;
+            }
+        }
+    }
+    lock(state).clients.retain(|client| client.id != client_id);
+    result
+}
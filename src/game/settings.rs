@@ -0,0 +1,402 @@
+///settings module holds the player's preferences: volumes, animation speed, the look of the table, the window, the language,
+/// hints and confirmations. they are saved to settings.json in the data directory whenever they change, and the systems
+/// below apply them live to whatever is on screen, so nothing waits for a restart.
+
+use std::path::PathBuf;
+use std::time::Duration;
+use bevy::prelude::*;
+use bevy::window::{PrimaryWindow, WindowMode, WindowResized};
+use serde::{Deserialize, Serialize};
+use super::components::{Background, InGameCardAccess, Localized, StartScreenHint};
+use super::constants::{ANIMATION_SPEEDS, BASE_WINDOW_SIZE, COMPANION_ACTION_DELAY, SPECTATOR_ROUND_DELAY, VOLUME_STEP, WINDOW_SIZES};
+use super::resources::{CompanionTimer, LocalSpectator};
+use super::storage::{load_json, save_json, DataDir};
+
+///enum CardBack is how the back of the cards is tinted
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum CardBack {
+    #[default]
+    Classic,
+    Blue,
+    Green,
+}
+
+impl CardBack {
+    pub const ALL: [CardBack; 3] = [CardBack::Classic, CardBack::Blue, CardBack::Green];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            CardBack::Classic => "Classic",
+            CardBack::Blue => "Blue",
+            CardBack::Green => "Green",
+        }
+    }
+
+    pub fn tint(&self) -> Color {
+        match self {
+            CardBack::Classic => Color::WHITE,
+            CardBack::Blue => Color::srgb(0.55, 0.65, 1.0),
+            CardBack::Green => Color::srgb(0.55, 1.0, 0.6),
+        }
+    }
+}
+
+///enum TableFelt is how the table's felt is tinted
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum TableFelt {
+    #[default]
+    Green,
+    Blue,
+    Red,
+}
+
+impl TableFelt {
+    pub const ALL: [TableFelt; 3] = [TableFelt::Green, TableFelt::Blue, TableFelt::Red];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            TableFelt::Green => "Green",
+            TableFelt::Blue => "Blue",
+            TableFelt::Red => "Red",
+        }
+    }
+
+    pub fn tint(&self) -> Color {
+        match self {
+            TableFelt::Green => Color::WHITE,
+            TableFelt::Blue => Color::srgb(0.5, 0.75, 1.0),
+            TableFelt::Red => Color::srgb(1.0, 0.55, 0.5),
+        }
+    }
+}
+
+///enum Language is the language of the menus and the table's buttons
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum Language {
+    #[default]
+    English,
+    Spanish,
+}
+
+impl Language {
+    pub const ALL: [Language; 2] = [Language::English, Language::Spanish];
+
+    ///label returns the name of the language in that language
+    pub fn label(&self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::Spanish => "Español",
+        }
+    }
+
+    ///translate returns the text in the language, the english text being the key. a text without a translation is
+    /// returned as it is
+    pub fn translate(&self, text: &'static str) -> &'static str {
+        if *self == Language::English {
+            return text;
+        }
+        match text {
+            "New Game" => "Nueva partida",
+            "Continue" => "Continuar",
+            "Practice" => "Práctica",
+            "Statistics" => "Estadísticas",
+            "Settings" => "Ajustes",
+            "Rules" => "Reglas",
+            "Quit" => "Salir",
+            "Stand" => "Plantarse",
+            "Hit" => "Pedir",
+            "Double Down" => "Doblar",
+            "Deal" => "Repartir",
+            "Home" => "Inicio",
+            "Autoplay" => "Auto",
+            "Keep Playing" => "Seguir",
+            "Master volume" => "Volumen general",
+            "Music volume" => "Volumen de la música",
+            "Sound effects volume" => "Volumen de los efectos",
+            "Animation speed" => "Velocidad de animación",
+            "Card back" => "Reverso de las cartas",
+            "Table felt" => "Tapete",
+            "Fullscreen" => "Pantalla completa",
+            "Window size" => "Tamaño de la ventana",
+            "Language" => "Idioma",
+            "Hints" => "Consejos",
+            "Confirmation prompts" => "Confirmaciones",
+            "On" => "Sí",
+            "Off" => "No",
+            "Classic" => "Clásico",
+            "Blue" => "Azul",
+            "Green" => "Verde",
+            "Red" => "Rojo",
+            "Basic strategy" => "Estrategia básica",
+            "Paused" => "Pausa",
+            "Resume" => "Reanudar",
+            "Quit to Menu" => "Salir al menú",
+            "Leave the table?" => "¿Dejar la mesa?",
+            "Stay" => "Quedarse",
+            "Leave" => "Salir",
+            "Up/Down: pick   Left/Right: change   Esc: back" => "Arriba/Abajo: elegir   Izquierda/Derecha: cambiar   Esc: volver",
+            _ => text,
+        }
+    }
+}
+
+///struct / resource Settings is the player's preferences, shared by every profile
+#[derive(Resource, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    ///volumes from 0 to 1, the music and the sound effects are both played at the master volume times their own
+    pub master_volume: f32,
+    pub music_volume: f32,
+    pub sfx_volume: f32,
+    ///how fast the companions and a watched table play, 1 being the usual pace
+    pub animation_speed: f32,
+    pub card_back: CardBack,
+    pub table_felt: TableFelt,
+    pub fullscreen: bool,
+    ///size of the window when it is not fullscreen
+    pub window_size: (f32, f32),
+    pub language: Language,
+    ///whether the start screen lists its keys and the table shows the basic strategy play
+    pub hints: bool,
+    ///whether what cannot be undone asks to be confirmed first
+    pub confirmations: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            master_volume: 1.0,
+            music_volume: 0.7,
+            sfx_volume: 1.0,
+            animation_speed: 1.0,
+            card_back: CardBack::default(),
+            table_felt: TableFelt::default(),
+            fullscreen: false,
+            window_size: BASE_WINDOW_SIZE,
+            language: Language::default(),
+            hints: true,
+            confirmations: true,
+        }
+    }
+}
+
+impl Settings {
+    ///load reads the settings saved in the data directory, the defaults are used when there are none or they cannot be read
+    pub fn load(data_dir: &DataDir) -> Self {
+        match load_json::<Settings>(&settings_path(data_dir)) {
+            Ok(settings) => settings.unwrap_or_default(),
+            Err(error) => {
+                warn!("Could not load the settings: {error}");
+                Settings::default()
+            }
+        }
+    }
+
+    ///on_off returns "On" or "Off" in the language of the settings
+    pub fn on_off(&self, value: bool) -> &'static str {
+        self.language.translate(if value { "On" } else { "Off" })
+    }
+}
+
+///settings_path returns the file the settings are saved to
+pub fn settings_path(data_dir: &DataDir) -> PathBuf {
+    data_dir.0.join("settings.json")
+}
+
+///enum SettingsEntry is a row of the settings screen, from top to bottom
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub enum SettingsEntry {
+    MasterVolume,
+    MusicVolume,
+    SfxVolume,
+    AnimationSpeed,
+    CardBack,
+    TableFelt,
+    Fullscreen,
+    WindowSize,
+    Language,
+    Hints,
+    Confirmations,
+}
+
+impl SettingsEntry {
+    pub const ALL: [SettingsEntry; 11] = [
+        SettingsEntry::MasterVolume,
+        SettingsEntry::MusicVolume,
+        SettingsEntry::SfxVolume,
+        SettingsEntry::AnimationSpeed,
+        SettingsEntry::CardBack,
+        SettingsEntry::TableFelt,
+        SettingsEntry::Fullscreen,
+        SettingsEntry::WindowSize,
+        SettingsEntry::Language,
+        SettingsEntry::Hints,
+        SettingsEntry::Confirmations,
+    ];
+
+    ///label returns the name of the row in english, see Language::translate
+    pub fn label(&self) -> &'static str {
+        match self {
+            SettingsEntry::MasterVolume => "Master volume",
+            SettingsEntry::MusicVolume => "Music volume",
+            SettingsEntry::SfxVolume => "Sound effects volume",
+            SettingsEntry::AnimationSpeed => "Animation speed",
+            SettingsEntry::CardBack => "Card back",
+            SettingsEntry::TableFelt => "Table felt",
+            SettingsEntry::Fullscreen => "Fullscreen",
+            SettingsEntry::WindowSize => "Window size",
+            SettingsEntry::Language => "Language",
+            SettingsEntry::Hints => "Hints",
+            SettingsEntry::Confirmations => "Confirmation prompts",
+        }
+    }
+
+    ///text returns the row as it is shown, its name and its value in the language of the settings
+    pub fn text(&self, settings: &Settings) -> String {
+        let language = settings.language;
+        let percent = |volume: f32| format!("{:.0}%", volume * 100.0);
+        let value = match self {
+            SettingsEntry::MasterVolume => percent(settings.master_volume),
+            SettingsEntry::MusicVolume => percent(settings.music_volume),
+            SettingsEntry::SfxVolume => percent(settings.sfx_volume),
+            SettingsEntry::AnimationSpeed => format!("{}x", settings.animation_speed),
+            SettingsEntry::CardBack => language.translate(settings.card_back.label()).to_string(),
+            SettingsEntry::TableFelt => language.translate(settings.table_felt.label()).to_string(),
+            SettingsEntry::Fullscreen => settings.on_off(settings.fullscreen).to_string(),
+            SettingsEntry::WindowSize => format!("{} x {}", settings.window_size.0, settings.window_size.1),
+            SettingsEntry::Language => language.label().to_string(),
+            SettingsEntry::Hints => settings.on_off(settings.hints).to_string(),
+            SettingsEntry::Confirmations => settings.on_off(settings.confirmations).to_string(),
+        };
+        format!("{}: {value}", language.translate(self.label()))
+    }
+
+    ///change moves the setting of the row one step up (1) or down (-1): volumes stop at 0% and 100%, the choices go
+    /// around and the switches are turned over
+    pub fn change(&self, settings: &mut Settings, step: i32) {
+        let volume = |volume: f32| ((volume + step as f32 * VOLUME_STEP).clamp(0.0, 1.0) * 10.0).round() / 10.0;
+        match self {
+            SettingsEntry::MasterVolume => settings.master_volume = volume(settings.master_volume),
+            SettingsEntry::MusicVolume => settings.music_volume = volume(settings.music_volume),
+            SettingsEntry::SfxVolume => settings.sfx_volume = volume(settings.sfx_volume),
+            SettingsEntry::AnimationSpeed => settings.animation_speed = cycle(&ANIMATION_SPEEDS, &settings.animation_speed, step),
+            SettingsEntry::CardBack => settings.card_back = cycle(&CardBack::ALL, &settings.card_back, step),
+            SettingsEntry::TableFelt => settings.table_felt = cycle(&TableFelt::ALL, &settings.table_felt, step),
+            SettingsEntry::Fullscreen => settings.fullscreen = !settings.fullscreen,
+            SettingsEntry::WindowSize => settings.window_size = cycle(&WINDOW_SIZES, &settings.window_size, step),
+            SettingsEntry::Language => settings.language = cycle(&Language::ALL, &settings.language, step),
+            SettingsEntry::Hints => settings.hints = !settings.hints,
+            SettingsEntry::Confirmations => settings.confirmations = !settings.confirmations,
+        }
+    }
+}
+
+//cycle returns the choice step places away from the current one, going around the ends. a current value that is not one
+// of the choices (edited by hand in the file) starts over from the first
+fn cycle<T: Copy + PartialEq>(choices: &[T], current: &T, step: i32) -> T {
+    let Some(index) = choices.iter().position(|choice| choice == current) else {
+        return choices[0];
+    };
+    choices[(index as i32 + step).rem_euclid(choices.len() as i32) as usize]
+}
+
+///save_settings saves the settings each time they are changed
+pub fn save_settings(data_dir: Res<DataDir>, settings: Res<Settings>) {
+    if !settings.is_changed() || settings.is_added() {
+        return;
+    }
+    if let Err(error) = save_json(&settings_path(&data_dir), &*settings) {
+        error!("Could not save the settings: {error}");
+    }
+}
+
+///apply_animation_speed paces the companions and a watched table at the animation speed
+pub fn apply_animation_speed(settings: Res<Settings>, mut companion_timer: ResMut<CompanionTimer>, mut spectator: ResMut<LocalSpectator>) {
+    if !settings.is_changed() {
+        return;
+    }
+    let speed = settings.animation_speed.max(0.1);
+    companion_timer.0.set_duration(Duration::from_secs_f32(COMPANION_ACTION_DELAY / speed));
+    spectator.timer.set_duration(Duration::from_secs_f32(SPECTATOR_ROUND_DELAY / speed));
+}
+
+///apply_language writes every text that has a translation in the language of the settings
+pub fn apply_language(
+    settings: Res<Settings>,
+    mut text_query: Query<(&Localized, &mut Text)>,
+    added_text: Query<(), Added<Localized>>,
+) {
+    if !settings.is_changed() && added_text.is_empty() {
+        return;
+    }
+    for (localized, mut text) in text_query.iter_mut() {
+        text.sections[0].value = settings.language.translate(localized.0).to_string();
+    }
+}
+
+///apply_table_look tints the felt and the backs of the cards on screen, a card turned over shows its front untinted
+pub fn apply_table_look(
+    settings: Res<Settings>,
+    assets: Res<AssetServer>,
+    mut background_query: Query<Mut<Sprite>, With<Background>>,
+    mut card_query: Query<Mut<UiImage>, With<InGameCardAccess>>,
+) {
+    for mut sprite in background_query.iter_mut() {
+        if settings.is_changed() || sprite.is_added() {
+            sprite.color = settings.table_felt.tint();
+        }
+    }
+    let card_back: Handle<Image> = assets.load("deck/card_back.png");
+    for mut image in card_query.iter_mut() {
+        if !settings.is_changed() && !image.is_changed() {
+            continue;
+        }
+        let color = if image.texture == card_back { settings.card_back.tint() } else { Color::WHITE };
+        //only written when it differs, so the image is not seen as changed again
+        if image.color != color {
+            image.color = color;
+        }
+    }
+}
+
+///apply_hints shows the keys of the start screen while hints are on
+pub fn apply_hints(
+    settings: Res<Settings>,
+    mut hint_query: Query<&mut Visibility, With<StartScreenHint>>,
+    added_hints: Query<(), Added<StartScreenHint>>,
+) {
+    if !settings.is_changed() && added_hints.is_empty() {
+        return;
+    }
+    for mut visibility in hint_query.iter_mut() {
+        *visibility = if settings.hints { Visibility::Inherited } else { Visibility::Hidden };
+    }
+}
+
+///apply_window_settings sets the window fullscreen or to the window size of the settings, and scales the table to the
+/// window each time its size changes
+pub fn apply_window_settings(
+    settings: Res<Settings>,
+    mut resized: EventReader<WindowResized>,
+    mut window_query: Query<&mut Window, With<PrimaryWindow>>,
+    mut projection_query: Query<&mut OrthographicProjection, With<Camera2d>>,
+    mut ui_scale: ResMut<UiScale>,
+) {
+    let Ok(mut window) = window_query.get_single_mut() else {
+        return;
+    };
+    if settings.is_changed() && !settings.is_added() {
+        window.mode = if settings.fullscreen { WindowMode::BorderlessFullscreen } else { WindowMode::Windowed };
+        if !settings.fullscreen {
+            window.resolution.set(settings.window_size.0, settings.window_size.1);
+        }
+    }
+    if resized.read().last().is_none() && !settings.is_changed() {
+        return;
+    }
+    let scale = (window.width() / BASE_WINDOW_SIZE.0).min(window.height() / BASE_WINDOW_SIZE.1).max(0.1);
+    ui_scale.0 = scale;
+    for mut projection in projection_query.iter_mut() {
+        projection.scale = 1.0 / scale;
+    }
+}
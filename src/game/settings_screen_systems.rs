@@ -0,0 +1,146 @@
+///settings screen systems module builds the Settings screen, a row for each setting, moved through like the main menu
+/// (arrows, d-pad or mouse) and changed with left and right (or enter and a click, which move a setting forward)
+
+use bevy::prelude::*;
+use super::components::{Localized, SettingsScreen};
+use super::constants::{AppState, PauseState};
+use super::main_menu_systems::{MENU_BUTTON_COLOR, MENU_FOCUS_COLOR};
+use super::resources::SettingsFocus;
+use super::settings::{Settings, SettingsEntry};
+
+//help line at the bottom of the settings screen
+static SETTINGS_HELP: &str = "Up/Down: pick   Left/Right: change   Esc: back";
+
+///settings_screen_setup spawns the settings screen over the start screen, or over the table from the pause menu
+pub fn settings_screen_setup(mut commands: Commands, assets: Res<AssetServer>, settings: Res<Settings>) {
+    let text_style = |font_size| TextStyle {
+        font: assets.load("fonts/FiraSans-SemiBold.ttf"),
+        font_size,
+        color: Color::WHITE,
+    };
+    commands.spawn((
+        NodeBundle {
+            style: Style {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                padding: UiRect::all(Val::Px(15.0)),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(4.0),
+                ..default()
+            },
+            background_color: BackgroundColor(Color::srgb(0.05, 0.2, 0.1)),
+            z_index: ZIndex::Global(5),
+            ..default()
+        },
+        SettingsScreen,
+    ))
+    .with_children(|parent| {
+        parent.spawn((TextBundle::from_section(settings.language.translate("Settings"), text_style(24.0)), Localized("Settings")));
+        for entry in SettingsEntry::ALL {
+            parent.spawn((
+                ButtonBundle {
+                    style: Style {
+                        width: Val::Px(380.0),
+                        height: Val::Px(26.0),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    background_color: BackgroundColor(MENU_BUTTON_COLOR),
+                    ..default()
+                },
+                entry,
+            ))
+            .with_children(|button| {
+                button.spawn(TextBundle::from_section(entry.text(&settings), text_style(16.0)));
+            });
+        }
+        parent.spawn((TextBundle::from_section(settings.language.translate(SETTINGS_HELP), text_style(14.0)), Localized(SETTINGS_HELP)));
+    });
+}
+
+///settings_screen_input moves the focus with the arrow keys, the d-pad or the mouse, changes the focused setting with left
+/// and right, enter, space, the gamepad's south button or a click, and goes back with Esc, to the start screen or the
+/// pause menu it was opened from
+pub fn settings_screen_input(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    pause_state: Res<State<PauseState>>,
+    mut next_pause_state: ResMut<NextState<PauseState>>,
+    gamepads: Res<Gamepads>,
+    gamepad_buttons: Res<ButtonInput<GamepadButton>>,
+    mut focus: ResMut<SettingsFocus>,
+    mut settings: ResMut<Settings>,
+    mut next_state: ResMut<NextState<AppState>>,
+    entry_query: Query<(&Interaction, &SettingsEntry), Changed<Interaction>>,
+) {
+    if keyboard_input.just_pressed(KeyCode::Escape) || keyboard_input.just_pressed(KeyCode::Backspace) {
+        if *pause_state.get() == PauseState::Settings {
+            next_pause_state.set(PauseState::Paused);
+        } else {
+            next_state.set(AppState::Start);
+        }
+        return;
+    }
+    let gamepad_pressed = |button_type| gamepads.iter().any(|gamepad| gamepad_buttons.just_pressed(GamepadButton::new(gamepad, button_type)));
+    let count = SettingsEntry::ALL.len();
+    if keyboard_input.just_pressed(KeyCode::ArrowUp) || gamepad_pressed(GamepadButtonType::DPadUp) {
+        focus.0 = (focus.0 + count - 1) % count;
+    }
+    if keyboard_input.just_pressed(KeyCode::ArrowDown) || gamepad_pressed(GamepadButtonType::DPadDown) {
+        focus.0 = (focus.0 + 1) % count;
+    }
+
+    let mut step = 0;
+    if keyboard_input.just_pressed(KeyCode::ArrowLeft) || gamepad_pressed(GamepadButtonType::DPadLeft) {
+        step = -1;
+    }
+    if keyboard_input.any_just_pressed([KeyCode::ArrowRight, KeyCode::Enter, KeyCode::NumpadEnter, KeyCode::Space])
+        || gamepad_pressed(GamepadButtonType::DPadRight) || gamepad_pressed(GamepadButtonType::South) {
+        step = 1;
+    }
+    for (interaction, entry) in entry_query.iter() {
+        let index = SettingsEntry::ALL.iter().position(|other| other == entry).unwrap_or_default();
+        match interaction {
+            Interaction::Hovered => focus.0 = index,
+            Interaction::Pressed => {
+                focus.0 = index;
+                step = 1;
+            }
+            Interaction::None => {}
+        }
+    }
+    if step != 0 {
+        let entry = SettingsEntry::ALL[focus.0];
+        entry.change(&mut settings, step);
+        debug!("Settings: {}", entry.text(&settings));
+    }
+}
+
+///update_settings_screen colors the focused row and writes the rows again when the settings change
+pub fn update_settings_screen(
+    focus: Res<SettingsFocus>,
+    settings: Res<Settings>,
+    mut entry_query: Query<(&SettingsEntry, &mut BackgroundColor, &Children)>,
+    mut text_query: Query<&mut Text>,
+    added_entries: Query<(), Added<SettingsEntry>>,
+) {
+    if !focus.is_changed() && !settings.is_changed() && added_entries.is_empty() {
+        return;
+    }
+    for (entry, mut background_color, children) in entry_query.iter_mut() {
+        background_color.0 = if *entry == SettingsEntry::ALL[focus.0] { MENU_FOCUS_COLOR } else { MENU_BUTTON_COLOR };
+        for child in children.iter() {
+            if let Ok(mut text) = text_query.get_mut(*child) {
+                text.sections[0].value = entry.text(&settings);
+            }
+        }
+    }
+}
+
+///despawn_settings_screen removes the settings screen when leaving it
+pub fn despawn_settings_screen(mut commands: Commands, query: Query<Entity, With<SettingsScreen>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
@@ -0,0 +1,34 @@
+///settlement module holds the rules of the table that decide a hand: when the dealer draws and who wins a hand that
+/// neither side busted. the systems that deal and settle rounds, and the online table, both play by them
+
+use super::components::{DealerHand, PlayerHand};
+use super::events::RoundOutcome;
+use super::traits::Scorable;
+
+///dealer_should_hit returns true while the dealer's hand is under 17, or is a soft 17 when the dealer hits soft 17
+pub fn dealer_should_hit(dealer_hand: &DealerHand, hits_soft_17: bool) -> bool {
+    let total = dealer_hand.best_total();
+    total < 17 || (hits_soft_17 && total == 17 && dealer_hand.is_soft())
+}
+
+///determine_win is used to determine who wins when neither the player nor the dealer busts.
+/// it returns the outcome for the player. a blackjack beats any other hand, 21 included, and two blackjacks push
+pub fn determine_win(player_hand: &PlayerHand, dealer_hand: &DealerHand) -> RoundOutcome {
+    match (player_hand.is_blackjack(), dealer_hand.is_blackjack()) {
+        (true, true) => return RoundOutcome::Push,
+        (true, false) => return RoundOutcome::Blackjack,
+        (false, true) => return RoundOutcome::Loss,
+        (false, false) => {}
+    }
+
+    let player_total = player_hand.best_total();
+    let dealer_total = dealer_hand.best_total();
+
+    if player_total > dealer_total {
+        RoundOutcome::Win
+    } else if dealer_total > player_total {
+        RoundOutcome::Loss
+    } else {
+        RoundOutcome::Push
+    }
+}
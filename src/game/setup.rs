@@ -1,16 +1,20 @@
 ///setup module used for building the start screen UI
 
 use bevy::prelude::*;
-use crate::game::components::{Background, Logo, PressEnterToPlay};
+use crate::game::components::{Background, Logo, StartScreenHint};
 use super::components::{ChipButtonValue, InGameCardAccess, PlayerButtonValues, TextComponents};
 
-/// start_setup spawns the camera for our 2d game as well as the home screen UI components via helper function spawn_home_assets
+/// spawn_camera spawns the camera for our 2d game, unless the app the game is added to has its own
+pub fn spawn_camera(mut commands: Commands) {
+    commands.spawn(Camera2dBundle::default());
+}
+
+/// start_setup spawns the home screen UI components via helper function spawn_home_assets
 pub fn start_setup(
-    mut commands: Commands, 
+    commands: Commands, 
     asset_server: Res<AssetServer>, 
     )
     {
-        commands.spawn(Camera2dBundle::default());
         spawn_home_assets(commands, asset_server);
 }
 
@@ -19,7 +23,7 @@ pub fn ingame_screen_setup(mut commands: Commands,
     asset_server: Res<AssetServer>, 
     mut texture_atlas: ResMut<Assets<TextureAtlasLayout>>,
     query: Query<Entity, With<Logo>>, 
-    query2: Query<Entity, With<PressEnterToPlay>>) {
+    query2: Query<Entity, With<StartScreenHint>>) {
 
         for entity in query.iter() {
             commands.entity(entity).despawn();
@@ -73,7 +77,8 @@ pub fn ingame_screen_setup(mut commands: Commands,
     }
 
     ///spawn_home_assets is a helper function used in the initial home setup and the reload home function.
-    /// this function spawns the appropriate sprite bundles for the home screen UI
+    /// this function spawns the appropriate sprite bundles for the home screen UI, the main menu is spawned under the logo
+    /// by the main menu systems
     pub fn spawn_home_assets( mut commands: Commands, 
         asset_server: Res<AssetServer>,
 
@@ -94,24 +99,28 @@ pub fn ingame_screen_setup(mut commands: Commands,
             SpriteBundle{
                 texture: asset_server.load("game_title.png"), 
                 sprite: Sprite {
+                    custom_size: Some(Vec2::new(311., 150.)),
                     ..default()
                 },
-                transform: Transform::from_xyz(0.0, 0.0, 1.0), 
+                transform: Transform::from_xyz(0.0, 150.0, 1.0), 
                 ..default()
             },
             Logo,
         ));
         commands.spawn((
-            SpriteBundle{
-                texture: asset_server.load("press_enter_to_play.png"), 
-                sprite: Sprite {
-                    custom_size: Some(Vec2::new(400., 50.)),
-                    ..default()
-                },
-                transform: Transform::from_xyz(0.0, -150.0, 2.0), 
+            Text2dBundle{
+                text: Text::from_section(
+                    "H: hand replays   O: play online   W: watch online   V: watch a table of companions",
+                    TextStyle {
+                        font: asset_server.load("fonts/FiraSans-SemiBold.ttf"),
+                        font_size: 16.0,
+                        color: Color::WHITE,
+                    },
+                ),
+                transform: Transform::from_xyz(0.0, -225.0, 2.0),
                 ..default()
             },
-            PressEnterToPlay,
+            StartScreenHint,
         ));
 
     }
\ No newline at end of file
@@ -0,0 +1,140 @@
+///simulator module plays rounds without a window: a bot plays a single seat of a Table, as fast as it answers.
+/// it is what the blackjack-sim binary runs to measure a bot over many rounds.
+
+use std::fmt;
+use super::bot::{action_request, checked_action, checked_bet, default_bet, legal_actions, BotError, BotProcess, BotRequest};
+use super::components::Deck;
+use super::events::RoundOutcome;
+use super::hand_history::HandAction;
+use super::rules::Rules;
+use super::table::Table;
+
+///struct SimulationReport sums up a simulation
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SimulationReport {
+    pub rounds: u64,
+    pub wins: u64,
+    pub losses: u64,
+    pub pushes: u64,
+    ///answers that were turned down, late answers included
+    pub illegal_moves: u64,
+    pub timeouts: u64,
+    pub starting_bankroll: i32,
+    pub bankroll: i32,
+    ///why the simulation ended before every round was played
+    pub stopped: Option<String>,
+}
+
+impl fmt::Display for SimulationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Rounds played: {}", self.rounds)?;
+        writeln!(f, "Wins / losses / pushes: {} / {} / {}", self.wins, self.losses, self.pushes)?;
+        writeln!(f, "Illegal moves: {} (timeouts: {})", self.illegal_moves, self.timeouts)?;
+        write!(f, "Bankroll: {} -> {} ({:+})", self.starting_bankroll, self.bankroll, self.bankroll - self.starting_bankroll)?;
+        if let Some(reason) = &self.stopped {
+            write!(f, "\nStopped early: {reason}")?;
+        }
+        Ok(())
+    }
+}
+
+impl SimulationReport {
+    ///turned_down counts an answer that was turned down and tells the bot, returning false once the bot is gone
+    fn turned_down(&mut self, bot: &mut BotProcess, reason: String, timed_out: bool) -> bool {
+        self.illegal_moves += 1;
+        if timed_out {
+            self.timeouts += 1;
+        }
+        let id = bot.last_request_id();
+        bot.notify(&BotRequest::Error { message: reason }, Some(id)).is_ok()
+    }
+}
+
+///simulate has the bot play up to `rounds` rounds at a one seat table, stopping early if the bot runs out of money or exits.
+/// a late, unreadable or illegal answer is counted and replaced by the default bet or by standing
+pub fn simulate(bot: &mut BotProcess, deck: Deck, rules: Rules, starting_bankroll: i32, rounds: u64) -> SimulationReport {
+    let mut table = Table::new(deck, rules.clone(), 1, starting_bankroll);
+    let mut report = SimulationReport { starting_bankroll, bankroll: starting_bankroll, ..Default::default() };
+    if let Err(error) = table.join("bot") {
+        report.stopped = Some(error.to_string());
+        return report;
+    }
+
+    for round in 1..=rounds {
+        let bankroll = table.seat(0).map_or(0, |seat| seat.bankroll);
+        if bankroll <= 0 {
+            report.stopped = Some(String::from("the bot ran out of money"));
+            break;
+        }
+
+        let reply = bot.ask(&BotRequest::Bet { round, bankroll, min_bet: 1, max_bet: bankroll });
+        if reply == Err(BotError::Exited) {
+            report.stopped = Some(BotError::Exited.to_string());
+            break;
+        }
+        let timed_out = reply == Err(BotError::Timeout);
+        let amount = match checked_bet(reply, bankroll) {
+            Ok(amount) => amount,
+            Err(reason) => {
+                if !report.turned_down(bot, reason, timed_out) {
+                    report.stopped = Some(BotError::Exited.to_string());
+                    break;
+                }
+                default_bet(bankroll)
+            }
+        };
+        //a single seat is dealt as soon as it has bet
+        if let Err(error) = table.bet(0, amount) {
+            report.stopped = Some(error.to_string());
+            break;
+        }
+
+        let results = loop {
+            let (Some(seat), Some(dealer_up_card)) = (table.seat(0), table.dealer_up_card()) else {
+                break Vec::new();
+            };
+            let legal = legal_actions(&seat.hand, seat.bankroll, &rules);
+            let reply = bot.ask(&action_request(round, &seat.hand, dealer_up_card, seat.bankroll, &rules));
+            let exited = reply == Err(BotError::Exited);
+            let timed_out = reply == Err(BotError::Timeout);
+            let action = match checked_action(reply, &legal) {
+                Ok(action) => action,
+                Err(reason) => {
+                    if exited || !report.turned_down(bot, reason, timed_out) {
+                        report.stopped = Some(BotError::Exited.to_string());
+                    }
+                    HandAction::Stand
+                }
+            };
+            match table.action(0, action) {
+                Ok(Some(results)) => break results,
+                Ok(None) => continue,
+                Err(error) => {
+                    report.stopped = Some(error.to_string());
+                    break Vec::new();
+                }
+            }
+        };
+
+        for result in results {
+            report.rounds += 1;
+            match result.outcome {
+                RoundOutcome::Blackjack | RoundOutcome::Win => report.wins += 1,
+                RoundOutcome::Loss => report.losses += 1,
+                RoundOutcome::Push => report.pushes += 1,
+            }
+            report.bankroll = result.bankroll;
+            let _ = bot.notify(&BotRequest::Result {
+                round,
+                outcome: result.outcome,
+                wager: result.wager,
+                payout: result.payout,
+                bankroll: result.bankroll,
+            }, None);
+        }
+        if report.stopped.is_some() {
+            break;
+        }
+    }
+    report
+}
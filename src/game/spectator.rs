@@ -0,0 +1,65 @@
+///spectator module lets the player watch a local table without a seat: every seat is played by a companion, and the
+/// rounds are driven by sending the same deal and keep playing actions the player's buttons send.
+
+use bevy::prelude::*;
+use super::companions::Personality;
+use super::components::{Companion, Seat};
+use super::constants::GameRoundState;
+use super::events::{ActionKind, PlayerAction};
+use super::resources::{LocalSpectator, TableConfig};
+
+///seat_spectator_companions sits a companion at every seat of a watched table, each seat getting the next personality
+pub fn seat_spectator_companions(
+    mut commands: Commands,
+    spectator: Res<LocalSpectator>,
+    seat_query: Query<(Entity, &Seat), Without<Companion>>,
+) {
+    if !spectator.watching {
+        return;
+    }
+    for (entity, seat) in seat_query.iter() {
+        commands.entity(entity).insert(Companion::new(Personality::ALL[seat.0 % Personality::ALL.len()]));
+    }
+}
+
+///drive_spectated_table deals a new round, and clears it once it is settled, each time the spectator timer finishes
+pub fn drive_spectated_table(
+    time: Res<Time>,
+    mut spectator: ResMut<LocalSpectator>,
+    game_state: Res<State<GameRoundState>>,
+    mut player_actions: EventWriter<PlayerAction>,
+) {
+    if !spectator.watching {
+        return;
+    }
+    let action = match game_state.get() {
+        GameRoundState::Betting => ActionKind::Deal,
+        GameRoundState::RoundEnd => ActionKind::KeepPlaying,
+        _ => {
+            spectator.timer.reset();
+            return;
+        }
+    };
+    if !spectator.timer.tick(time.delta()).finished() {
+        return;
+    }
+    spectator.timer.reset();
+    player_actions.send(PlayerAction::new(action));
+}
+
+///stop_watching hands the table back to the player when going back to the start screen
+pub fn stop_watching(
+    mut commands: Commands,
+    mut spectator: ResMut<LocalSpectator>,
+    mut table: ResMut<TableConfig>,
+    companion_query: Query<Entity, With<Companion>>,
+) {
+    if !spectator.watching {
+        return;
+    }
+    spectator.watching = false;
+    table.seats = 1;
+    for entity in companion_query.iter() {
+        commands.entity(entity).remove::<Companion>();
+    }
+}
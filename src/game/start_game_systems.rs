@@ -1,16 +1,42 @@
-///start game systems is used for the logic that brings you from the start screen to the in game screen
+///start game systems is used for the shortcuts of the start screen, next to its main menu
 
 use bevy::prelude::*;
-use super::constants::AppState;
+use super::constants::{AppState, SPECTATOR_SEATS};
+use super::online::OnlineSession;
+use super::resources::{LocalSpectator, TableConfig};
 
-///start_game is used to bring you from the start screen to the in_game screen by having the enter button hit
+///start_game is used to bring you from the start screen to the statistics, hand replay and online screens by having the
+/// S, H and O keys hit, the main menu handles the rest.
+/// W watches the online table instead of playing at it, and V watches a local table of companions
 pub fn start_game(
     keyboard_input: Res<ButtonInput<KeyCode>>,
     mut next_state: ResMut<NextState<AppState>>,
+    online_session: Option<ResMut<OnlineSession>>,
+    mut spectator: ResMut<LocalSpectator>,
+    mut table: ResMut<TableConfig>,
 
 ){
-    if keyboard_input.just_pressed(KeyCode::Enter) {
+    if keyboard_input.just_pressed(KeyCode::KeyS) {
 
+        next_state.set(AppState::Stats);
+
+    }
+    if keyboard_input.just_pressed(KeyCode::KeyH) {
+
+        next_state.set(AppState::Replay);
+
+    }
+    //the online table is only there when the app has BlackjackOnlinePlugin
+    if let Some(mut online_session) = online_session.filter(|_| keyboard_input.any_just_pressed([KeyCode::KeyO, KeyCode::KeyW])) {
+
+        online_session.spectating = keyboard_input.just_pressed(KeyCode::KeyW);
+        next_state.set(AppState::Online);
+
+    }
+    if keyboard_input.just_pressed(KeyCode::KeyV) {
+
+        spectator.watching = true;
+        table.seats = SPECTATOR_SEATS;
         next_state.set(AppState::InGame);
 
     }
@@ -0,0 +1,151 @@
+///statistics module holds the lifetime and session counters that are collected from settled rounds,
+/// as well as saving and loading the lifetime counters for the active profile.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use super::events::{HandSettled, RoundOutcome};
+use super::resources::ActiveProfile;
+use super::storage::{load_json, save_json, DataDir};
+
+///struct OutcomeTally counts the wins, losses and pushes for a single situation
+#[derive(Serialize, Deserialize, Default, Clone, Copy, Debug, PartialEq)]
+pub struct OutcomeTally {
+    pub wins: u32,
+    pub losses: u32,
+    pub pushes: u32,
+}
+
+impl OutcomeTally {
+    pub fn total(&self) -> u32 {
+        self.wins + self.losses + self.pushes
+    }
+}
+
+///struct StatsCounters holds every counter shown on the statistics screen
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct StatsCounters {
+    pub hands_played: u32,
+    pub wins: u32,
+    pub losses: u32,
+    pub pushes: u32,
+    pub blackjacks: u32,
+    pub net_result: i64,
+    pub biggest_win: i64,
+    pub biggest_loss: i64,
+    ///positive while on a winning streak, negative while on a losing streak, pushes do not break a streak
+    pub current_streak: i32,
+    pub longest_win_streak: u32,
+    pub longest_loss_streak: u32,
+    ///results keyed by "<starting total>v<dealer up card>", for example "16v10"
+    pub by_starting_total: BTreeMap<String, OutcomeTally>,
+}
+
+impl StatsCounters {
+    ///record adds a single settled round to the counters
+    pub fn record(&mut self, round: &HandSettled) {
+        self.hands_played += 1;
+        self.net_result += round.net as i64;
+        if round.player_blackjack {
+            self.blackjacks += 1;
+        }
+
+        let tally = self.by_starting_total
+            .entry(breakdown_key(round.starting_total, round.dealer_up_card))
+            .or_default();
+        match round.outcome {
+            RoundOutcome::Blackjack | RoundOutcome::Win => {
+                self.wins += 1;
+                tally.wins += 1;
+                self.biggest_win = self.biggest_win.max(round.net as i64);
+                self.current_streak = self.current_streak.max(0) + 1;
+                self.longest_win_streak = self.longest_win_streak.max(self.current_streak as u32);
+            }
+            RoundOutcome::Loss => {
+                self.losses += 1;
+                tally.losses += 1;
+                self.biggest_loss = self.biggest_loss.max(-round.net as i64);
+                self.current_streak = self.current_streak.min(0) - 1;
+                self.longest_loss_streak = self.longest_loss_streak.max(self.current_streak.unsigned_abs());
+            }
+            RoundOutcome::Push => {
+                self.pushes += 1;
+                tally.pushes += 1;
+            }
+        }
+    }
+
+    ///rate returns a count as a percentage of the hands played
+    pub fn rate(&self, count: u32) -> f64 {
+        if self.hands_played == 0 {
+            return 0.;
+        }
+        count as f64 * 100. / self.hands_played as f64
+    }
+
+    ///tally returns the results for a starting total against a dealer up card
+    pub fn tally(&self, starting_total: u8, dealer_up_card: u8) -> OutcomeTally {
+        self.by_starting_total
+            .get(&breakdown_key(starting_total, dealer_up_card))
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+fn breakdown_key(starting_total: u8, dealer_up_card: u8) -> String {
+    format!("{starting_total}v{dealer_up_card}")
+}
+
+///struct / resource Statistics holds the lifetime counters of the active profile and the counters for this session only
+#[derive(Resource, Default)]
+pub struct Statistics {
+    pub lifetime: StatsCounters,
+    pub session: StatsCounters,
+}
+
+///statistics_path returns the file the lifetime counters of a profile are saved to
+pub fn statistics_path(data_dir: &DataDir, profile: &str) -> PathBuf {
+    data_dir.profile_dir(profile).join("statistics.json")
+}
+
+///load_statistics reads the lifetime counters of the active profile from disk when the game starts
+pub fn load_statistics(data_dir: Res<DataDir>, profile: Res<ActiveProfile>, mut statistics: ResMut<Statistics>) {
+    match load_json::<StatsCounters>(&statistics_path(&data_dir, &profile.0)) {
+        Ok(Some(lifetime)) => statistics.lifetime = lifetime,
+        Ok(None) => {}
+        Err(error) => warn!("Could not load statistics for profile {}: {error}", profile.0),
+    }
+}
+
+///collect_round_statistics listens for the settled hands of the player's seats, records them and saves the lifetime counters
+pub fn collect_round_statistics(
+    mut settled_rounds: EventReader<HandSettled>,
+    mut statistics: ResMut<Statistics>,
+    data_dir: Res<DataDir>,
+    profile: Res<ActiveProfile>,
+) {
+    let mut recorded = false;
+    for round in settled_rounds.read().filter(|round| !round.companion) {
+        statistics.lifetime.record(round);
+        statistics.session.record(round);
+        recorded = true;
+    }
+    if !recorded {
+        return;
+    }
+    save_statistics(&data_dir, &profile.0, &statistics.lifetime);
+}
+
+///reset_statistics clears the lifetime counters of the given profile, the counters of this session are kept
+pub fn reset_statistics(data_dir: &DataDir, profile: &str, statistics: &mut Statistics) {
+    statistics.lifetime = StatsCounters::default();
+    save_statistics(data_dir, profile, &statistics.lifetime);
+}
+
+fn save_statistics(data_dir: &DataDir, profile: &str, lifetime: &StatsCounters) {
+    if let Err(error) = save_json(&statistics_path(data_dir, profile), lifetime) {
+        error!("Could not save statistics for profile {profile}: {error}");
+    }
+}
@@ -0,0 +1,189 @@
+///stats screen systems module builds the statistics screen and handles the keys used on it
+
+use bevy::prelude::*;
+use super::components::{StatsScreen, StatsScreenText};
+use super::constants::AppState;
+use super::resources::{ActiveProfile, StatsView};
+use super::settings::Settings;
+use super::statistics::{reset_statistics, Statistics, StatsCounters};
+use super::storage::DataDir;
+
+///dealer up cards in the order they are shown as columns in the breakdown table (11 is an ace)
+static DEALER_UP_CARDS: [u8; 10] = [2, 3, 4, 5, 6, 7, 8, 9, 10, 11];
+///lowest and highest starting totals shown as rows in the breakdown table
+static STARTING_TOTALS: std::ops::RangeInclusive<u8> = 4..=21;
+
+///stats_screen_setup spawns the statistics screen over the start screen
+pub fn stats_screen_setup(mut commands: Commands, assets: Res<AssetServer>) {
+    commands.spawn((
+        NodeBundle {
+            style: Style {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                ..default()
+            },
+            background_color: BackgroundColor(Color::srgb(0.05, 0.2, 0.1)),
+            ..default()
+        },
+        StatsScreen,
+    ))
+    .with_children(|parent| {
+        spawn_stats_text(parent, &assets, Vec2::new(15.0, 10.0), 30.0, StatsScreenText::Title);
+        spawn_stats_text(parent, &assets, Vec2::new(15.0, 60.0), 18.0, StatsScreenText::Summary);
+        spawn_stats_text(parent, &assets, Vec2::new(340.0, 60.0), 13.0, StatsScreenText::Breakdown);
+    });
+}
+
+///spawn_stats_text is a helper function for spawning one of the text elements on the statistics screen, the text itself is filled in by update_stats_screen
+fn spawn_stats_text(
+    parent: &mut ChildBuilder,
+    assets: &Res<AssetServer>,
+    position: Vec2,
+    font_size: f32,
+    text_component: StatsScreenText,
+) {
+    parent.spawn(TextBundle {
+        text: Text::from_section(
+            "",
+            TextStyle {
+                font: assets.load("fonts/FiraSans-SemiBold.ttf"),
+                font_size,
+                color: Color::WHITE,
+            },
+        ),
+        style: Style {
+            position_type: PositionType::Absolute,
+            left: Val::Px(position.x),
+            top: Val::Px(position.y),
+            ..default()
+        },
+        ..default()
+    })
+    .insert(text_component);
+}
+
+///update_stats_screen refreshes the statistics screen text whenever the counters or the selected view change
+pub fn update_stats_screen(
+    statistics: Res<Statistics>,
+    view: Res<StatsView>,
+    profile: Res<ActiveProfile>,
+    mut text_query: Query<(&StatsScreenText, &mut Text)>,
+    added_text: Query<(), Added<StatsScreenText>>,
+) {
+    if !statistics.is_changed() && !view.is_changed() && added_text.is_empty() {
+        return;
+    }
+    let (counters, view_name) = match *view {
+        StatsView::Lifetime => (&statistics.lifetime, "Lifetime"),
+        StatsView::Session => (&statistics.session, "This Session"),
+    };
+    for (text_component, mut text) in text_query.iter_mut() {
+        text.sections[0].value = match text_component {
+            StatsScreenText::Title => format!("Statistics - {view_name} ({})", profile.0),
+            StatsScreenText::Summary => summary_text(counters),
+            StatsScreenText::Breakdown => breakdown_text(counters),
+        };
+    }
+}
+
+///summary_text lays out the overall counters shown on the left side of the statistics screen
+fn summary_text(counters: &StatsCounters) -> String {
+    format!(
+        "Hands played: {}\n\
+        Wins: {} ({:.1}%)\n\
+        Losses: {} ({:.1}%)\n\
+        Pushes: {} ({:.1}%)\n\
+        Blackjacks: {} ({:.1}%)\n\
+        \n\
+        Net result: ${}\n\
+        Biggest win: ${}\n\
+        Biggest loss: ${}\n\
+        \n\
+        Longest win streak: {}\n\
+        Longest losing streak: {}\n\
+        \n\
+        Tab: lifetime / session\n\
+        R: reset the lifetime statistics of this profile\n\
+        Esc: back",
+        counters.hands_played,
+        counters.wins, counters.rate(counters.wins),
+        counters.losses, counters.rate(counters.losses),
+        counters.pushes, counters.rate(counters.pushes),
+        counters.blackjacks, counters.rate(counters.blackjacks),
+        counters.net_result,
+        counters.biggest_win,
+        counters.biggest_loss,
+        counters.longest_win_streak,
+        counters.longest_loss_streak,
+    )
+}
+
+///breakdown_text lays out the wins minus losses for each starting total (rows) against each dealer up card (columns)
+fn breakdown_text(counters: &StatsCounters) -> String {
+    let mut text = String::from("Start  ");
+    for up_card in DEALER_UP_CARDS {
+        let label = if up_card == 11 { String::from("A") } else { up_card.to_string() };
+        text.push_str(&format!("{label:>4}"));
+    }
+    for starting_total in STARTING_TOTALS.clone() {
+        text.push_str(&format!("\n{starting_total:>5}  "));
+        for up_card in DEALER_UP_CARDS {
+            let tally = counters.tally(starting_total, up_card);
+            let cell = if tally.total() == 0 {
+                String::from(".")
+            } else {
+                format!("{:+}", tally.wins as i64 - tally.losses as i64)
+            };
+            text.push_str(&format!("{cell:>4}"));
+        }
+    }
+    text
+}
+
+///stats_screen_input handles the keys on the statistics screen: switching views, resetting the profile, and going back.
+/// with confirmation prompts on, R has to be pressed a second time (and nothing else in between) to reset
+pub fn stats_screen_input(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut view: ResMut<StatsView>,
+    mut statistics: ResMut<Statistics>,
+    data_dir: Res<DataDir>,
+    profile: Res<ActiveProfile>,
+    settings: Res<Settings>,
+    mut reset_asked: Local<bool>,
+    mut text_query: Query<(&StatsScreenText, &mut Text)>,
+) {
+    if keyboard_input.just_pressed(KeyCode::Tab) {
+        *view = match *view {
+            StatsView::Lifetime => StatsView::Session,
+            StatsView::Session => StatsView::Lifetime,
+        };
+    }
+    if keyboard_input.just_pressed(KeyCode::KeyR) {
+        if *reset_asked || !settings.confirmations {
+            reset_statistics(&data_dir, &profile.0, &mut statistics);
+            *reset_asked = false;
+        } else {
+            *reset_asked = true;
+            for (text_component, mut text) in text_query.iter_mut() {
+                if let StatsScreenText::Title = text_component {
+                    text.sections[0].value = format!("Reset the lifetime statistics of {}? Press R again to reset, any other key to keep them", profile.0);
+                }
+            }
+        }
+    } else if *reset_asked && keyboard_input.get_just_pressed().next().is_some() {
+        *reset_asked = false;
+        //the title is written again by update_stats_screen
+        view.set_changed();
+    }
+    if keyboard_input.just_pressed(KeyCode::Escape) || keyboard_input.just_pressed(KeyCode::Backspace) {
+        next_state.set(AppState::Start);
+    }
+}
+
+///despawn_stats_screen removes the statistics screen when leaving it
+pub fn despawn_stats_screen(mut commands: Commands, query: Query<Entity, With<StatsScreen>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
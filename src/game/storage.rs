@@ -0,0 +1,64 @@
+///storage module is responsible for where the game keeps files on disk between sessions (statistics, profiles, etc)
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use bevy::prelude::Resource;
+use serde::{de::DeserializeOwned, Serialize};
+
+///environment variable that can be set to move the data directory somewhere else
+pub static DATA_DIR_ENV_VAR: &str = "BLACKJACK_DATA_DIR";
+
+///data_dir returns the directory used for everything the game saves by default, creating nothing on its own
+pub fn data_dir() -> PathBuf {
+    if let Some(dir) = std::env::var_os(DATA_DIR_ENV_VAR) {
+        return PathBuf::from(dir);
+    }
+    dirs::data_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("blackjack-in-rust")
+}
+
+///struct / resource DataDir is the directory the game saves to. BlackjackCorePlugin uses the one inserted before it, so
+/// an app (or a test) can keep its files apart, and data_dir otherwise
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct DataDir(pub PathBuf);
+
+impl Default for DataDir {
+    fn default() -> Self {
+        Self(data_dir())
+    }
+}
+
+impl DataDir {
+    ///profile_dir returns the directory holding the saved data of a single profile
+    pub fn profile_dir(&self, profile: &str) -> PathBuf {
+        self.0.join("profiles").join(profile)
+    }
+
+    ///scripts_dir returns the directory the companion and autoplay scripts are loaded from by default
+    pub fn scripts_dir(&self) -> PathBuf {
+        self.0.join("scripts")
+    }
+}
+
+///load_json reads a json file, returning None when it does not exist yet
+pub fn load_json<T: DeserializeOwned>(path: &Path) -> io::Result<Option<T>> {
+    match fs::read_to_string(path) {
+        Ok(contents) => serde_json::from_str(&contents)
+            .map(Some)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error)),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(error) => Err(error),
+    }
+}
+
+///save_json writes a value to a json file, creating any missing parent directories
+pub fn save_json<T: Serialize>(path: &Path, value: &T) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let contents = serde_json::to_string_pretty(value)
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+    fs::write(path, contents)
+}
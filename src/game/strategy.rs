@@ -0,0 +1,65 @@
+///strategy module holds basic strategy, the mathematically optimal play for every player total against every dealer up card.
+/// it is used to show the optimal action when replaying hands.
+
+use super::hand_history::HandAction;
+
+///enum Advice is a single entry of the basic strategy chart
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Advice {
+    Hit,
+    Stand,
+    ///double down if allowed, otherwise hit
+    DoubleOrHit,
+    ///double down if allowed, otherwise stand
+    DoubleOrStand,
+}
+
+///basic_strategy returns the optimal action for a hand, dealer_up_card being the value of the dealer's face up card (aces are 11).
+/// can_double should only be true on the first two cards of a hand when the rules allow doubling down.
+/// the chart is the one for a dealer that hits soft 17.
+pub fn basic_strategy(total: u8, soft: bool, dealer_up_card: u8, can_double: bool) -> HandAction {
+    let advice = if soft {
+        soft_total_advice(total, dealer_up_card)
+    } else {
+        hard_total_advice(total, dealer_up_card)
+    };
+    match advice {
+        Advice::Hit => HandAction::Hit,
+        Advice::Stand => HandAction::Stand,
+        Advice::DoubleOrHit if can_double => HandAction::DoubleDown,
+        Advice::DoubleOrHit => HandAction::Hit,
+        Advice::DoubleOrStand if can_double => HandAction::DoubleDown,
+        Advice::DoubleOrStand => HandAction::Stand,
+    }
+}
+
+fn hard_total_advice(total: u8, dealer_up_card: u8) -> Advice {
+    match (total, dealer_up_card) {
+        (0..=8, _) => Advice::Hit,
+        (9, 3..=6) => Advice::DoubleOrHit,
+        (9, _) => Advice::Hit,
+        (10, 2..=9) => Advice::DoubleOrHit,
+        (10, _) => Advice::Hit,
+        (11, _) => Advice::DoubleOrHit,
+        (12, 4..=6) => Advice::Stand,
+        (12, _) => Advice::Hit,
+        (13..=16, 2..=6) => Advice::Stand,
+        (13..=16, _) => Advice::Hit,
+        _ => Advice::Stand,
+    }
+}
+
+fn soft_total_advice(total: u8, dealer_up_card: u8) -> Advice {
+    match (total, dealer_up_card) {
+        (0..=12, _) => Advice::Hit,
+        (13..=14, 5..=6) => Advice::DoubleOrHit,
+        (15..=16, 4..=6) => Advice::DoubleOrHit,
+        (17, 3..=6) => Advice::DoubleOrHit,
+        (13..=17, _) => Advice::Hit,
+        (18, 2..=6) => Advice::DoubleOrStand,
+        (18, 7..=8) => Advice::Stand,
+        (18, _) => Advice::Hit,
+        (19, 6) => Advice::DoubleOrStand,
+        _ => Advice::Stand,
+    }
+}
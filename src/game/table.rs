@@ -0,0 +1,323 @@
+///table module is the authoritative table the server runs: it owns the shoe and every bankroll, checks each bet and action
+/// it is sent, and deals, plays the dealer and settles with the same rules as the local game.
+
+use std::fmt;
+use super::components::{Card, DealerHand, Deck, PlayerHand};
+use super::settlement::{dealer_should_hit, determine_win};
+use super::events::RoundOutcome;
+use super::hand_history::HandAction;
+use super::protocol::{SeatResult, SeatSnapshot, TablePhase, TableSnapshot, HIDDEN_CARD};
+use super::rules::Rules;
+use super::traits::{Dealable, Scorable};
+
+///enum TableError lists why the table turned down a join, bet or action
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TableError {
+    TableFull,
+    UnknownToken,
+    SeatInUse,
+    NotSeated,
+    NotBetting,
+    AlreadyBet,
+    InvalidBet,
+    NotYourTurn,
+    IllegalAction,
+}
+
+impl fmt::Display for TableError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = match self {
+            TableError::TableFull => "every seat is taken",
+            TableError::UnknownToken => "no seat is held for this token",
+            TableError::SeatInUse => "this seat is still connected, it can be taken back once that connection drops",
+            TableError::NotSeated => "you are not seated at this table",
+            TableError::NotBetting => "bets can only be placed between rounds",
+            TableError::AlreadyBet => "you already placed a bet this round",
+            TableError::InvalidBet => "the bet must be more than 0 and no more than your bankroll",
+            TableError::NotYourTurn => "it is not your turn",
+            TableError::IllegalAction => "that action is not allowed on this hand",
+        };
+        write!(f, "{message}")
+    }
+}
+
+///struct TableSeat is a player sitting at the table
+#[derive(Clone)]
+pub struct TableSeat {
+    pub name: String,
+    ///handed to the player when they sit down, and used to take the seat back after a dropped connection
+    pub token: String,
+    pub bankroll: i32,
+    pub hand: PlayerHand,
+    pub connected: bool,
+    ///set when the player leaves in the middle of a round, the seat is freed once the round is settled
+    pub leaving: bool,
+}
+
+///struct Table is the state of a whole table: its seats, the dealer's hand and the shoe
+pub struct Table {
+    deck: Deck,
+    rules: Rules,
+    starting_bankroll: i32,
+    seats: Vec<Option<TableSeat>>,
+    dealer_hand: DealerHand,
+    phase: TablePhase,
+    active_seat: Option<usize>,
+    round: u64,
+}
+
+impl Table {
+    ///new sets up an empty table with the given number of seats, every player starting with starting_bankroll
+    pub fn new(deck: Deck, rules: Rules, seats: usize, starting_bankroll: i32) -> Self {
+        Self {
+            deck,
+            rules,
+            starting_bankroll,
+            seats: vec![None; seats],
+            dealer_hand: DealerHand { cards: Vec::new() },
+            phase: TablePhase::Betting,
+            active_seat: None,
+            round: 0,
+        }
+    }
+
+    pub fn phase(&self) -> TablePhase {
+        self.phase
+    }
+
+    pub fn seat(&self, seat: usize) -> Option<&TableSeat> {
+        self.seats.get(seat)?.as_ref()
+    }
+
+    ///dealer_up_card returns the dealer's face up card, once a round has been dealt
+    pub fn dealer_up_card(&self) -> Option<&Card> {
+        self.dealer_hand.cards.get(1)
+    }
+
+    ///join sits a new player at the first free seat, returning the seat and its reconnect token
+    pub fn join(&mut self, name: &str) -> Result<(usize, String), TableError> {
+        let seat = self.seats.iter().position(Option::is_none).ok_or(TableError::TableFull)?;
+        let token = format!("{:016x}", rand::random::<u64>());
+        self.seats[seat] = Some(TableSeat {
+            name: name.to_string(),
+            token: token.clone(),
+            bankroll: self.starting_bankroll,
+            hand: PlayerHand { cards: Vec::new(), bet: 0 },
+            connected: true,
+            leaving: false,
+        });
+        Ok((seat, token))
+    }
+
+    ///rejoin gives a player their seat back, cards and bet included, from the token they were handed when joining.
+    /// the seat is only handed over once its last connection has been disconnected
+    pub fn rejoin(&mut self, token: &str) -> Result<usize, TableError> {
+        let seat = self.seats.iter()
+            .position(|seat| seat.as_ref().is_some_and(|seat| seat.token == token && !seat.leaving))
+            .ok_or(TableError::UnknownToken)?;
+        if let Some(table_seat) = &mut self.seats[seat] {
+            if table_seat.connected {
+                return Err(TableError::SeatInUse);
+            }
+            table_seat.connected = true;
+        }
+        Ok(seat)
+    }
+
+    ///disconnect keeps the seat for a reconnect. the table stops waiting on its bet, and a hand whose turn it is stands,
+    /// returning the results when that ended the round
+    pub fn disconnect(&mut self, seat: usize) -> Option<Vec<SeatResult>> {
+        if let Some(Some(table_seat)) = self.seats.get_mut(seat) {
+            table_seat.connected = false;
+        }
+        if self.phase == TablePhase::PlayerTurns && self.active_seat == Some(seat) {
+            return self.finish_turn();
+        }
+        self.deal_if_ready()
+    }
+
+    ///leave gives the seat up. between rounds the seat is freed right away, in the middle of a round the hand stands
+    /// and the seat is freed once it is settled
+    pub fn leave(&mut self, seat: usize) -> Option<Vec<SeatResult>> {
+        let in_play = self.phase == TablePhase::PlayerTurns
+            && self.seat(seat).is_some_and(|table_seat| !table_seat.hand.cards.is_empty());
+        if !in_play {
+            self.seats[seat] = None;
+            return self.deal_if_ready();
+        }
+        if let Some(table_seat) = &mut self.seats[seat] {
+            table_seat.connected = false;
+            table_seat.leaving = true;
+        }
+        if self.active_seat == Some(seat) {
+            return self.finish_turn();
+        }
+        None
+    }
+
+    ///bet places a seat's bet for the next round, the cards are dealt once every connected seat has bet
+    pub fn bet(&mut self, seat: usize, amount: i32) -> Result<(), TableError> {
+        if self.phase != TablePhase::Betting {
+            return Err(TableError::NotBetting);
+        }
+        let table_seat = self.seats.get_mut(seat).and_then(Option::as_mut).ok_or(TableError::NotSeated)?;
+        if table_seat.hand.bet > 0 {
+            return Err(TableError::AlreadyBet);
+        }
+        if amount <= 0 || amount > table_seat.bankroll {
+            return Err(TableError::InvalidBet);
+        }
+        table_seat.bankroll -= amount;
+        table_seat.hand.bet = amount as u64;
+        //the seat betting is connected and takes a turn, so dealing cannot settle the round right away
+        self.deal_if_ready();
+        Ok(())
+    }
+
+    ///action plays the hand of the seat whose turn it is, returning the results when it ended the round
+    pub fn action(&mut self, seat: usize, action: HandAction) -> Result<Option<Vec<SeatResult>>, TableError> {
+        if self.phase != TablePhase::PlayerTurns || self.active_seat != Some(seat) {
+            return Err(TableError::NotYourTurn);
+        }
+        let rules = self.rules.clone();
+        let table_seat = self.seats[seat].as_mut().ok_or(TableError::NotSeated)?;
+        match action {
+            HandAction::Hit => {
+                table_seat.hand.cards.push(self.deck.deal());
+                if table_seat.hand.is_bust() {
+                    return Ok(self.finish_turn());
+                }
+                Ok(None)
+            }
+            HandAction::Stand => Ok(self.finish_turn()),
+            HandAction::DoubleDown => {
+                let bet = table_seat.hand.bet as i32;
+                if !rules.can_double(&table_seat.hand) || table_seat.bankroll < bet {
+                    return Err(TableError::IllegalAction);
+                }
+                table_seat.bankroll -= bet;
+                table_seat.hand.bet *= 2;
+                table_seat.hand.cards.push(self.deck.deal());
+                Ok(self.finish_turn())
+            }
+        }
+    }
+
+    ///snapshot is the table as every client is shown it, with the dealer's hole card hidden while seats are playing
+    pub fn snapshot(&self) -> TableSnapshot {
+        let players = self.seats.iter().enumerate()
+            .filter_map(|(seat, table_seat)| table_seat.as_ref().map(|table_seat| SeatSnapshot {
+                seat,
+                name: table_seat.name.clone(),
+                connected: table_seat.connected,
+                bankroll: table_seat.bankroll,
+                bet: table_seat.hand.bet as i32,
+                cards: table_seat.hand.cards.iter().map(Card::code).collect(),
+            }))
+            .collect();
+        let dealer_cards = self.dealer_hand.cards.iter().enumerate()
+            .map(|(i, card)| if i == 0 && self.phase == TablePhase::PlayerTurns { HIDDEN_CARD.to_string() } else { card.code() })
+            .collect();
+        TableSnapshot {
+            round: self.round,
+            phase: self.phase,
+            seats: self.seats.len(),
+            players,
+            dealer_cards,
+            active_seat: self.active_seat,
+        }
+    }
+
+    ///seats_in_play returns the seats dealt into the current round, in table order
+    fn seats_in_play(&self) -> Vec<usize> {
+        self.seats.iter().enumerate()
+            .filter(|(_, table_seat)| table_seat.as_ref().is_some_and(|table_seat| !table_seat.hand.cards.is_empty()))
+            .map(|(seat, _)| seat)
+            .collect()
+    }
+
+    ///deal_if_ready deals a new round once at least one seat has bet and no connected seat is still to bet, returning the
+    /// results when no seat dealt in is connected to play
+    fn deal_if_ready(&mut self) -> Option<Vec<SeatResult>> {
+        if self.phase != TablePhase::Betting {
+            return None;
+        }
+        let seated = self.seats.iter().flatten();
+        let any_bet = seated.clone().any(|table_seat| table_seat.hand.bet > 0);
+        let waiting = seated.clone().any(|table_seat| table_seat.connected && table_seat.hand.bet == 0);
+        if !any_bet || waiting {
+            return None;
+        }
+
+        self.round += 1;
+        self.deck.start_round();
+        self.dealer_hand.cards.clear();
+        for table_seat in self.seats.iter_mut().flatten() {
+            table_seat.hand.cards.clear();
+        }
+        //two cards around the table in seat order, the dealer's first card being the hole card
+        for _ in 0..2 {
+            for table_seat in self.seats.iter_mut().flatten().filter(|table_seat| table_seat.hand.bet > 0) {
+                table_seat.hand.cards.push(self.deck.deal());
+            }
+            self.dealer_hand.cards.push(self.deck.deal());
+        }
+        self.phase = TablePhase::PlayerTurns;
+        self.active_seat = None;
+        self.finish_turn()
+    }
+
+    ///finish_turn passes the turn to the next seat in play, or plays the dealer and settles once every seat has played.
+    /// seats that are not connected stand on the cards they have
+    fn finish_turn(&mut self) -> Option<Vec<SeatResult>> {
+        let current = self.active_seat;
+        let next_seat = self.seats_in_play().into_iter()
+            .filter(|seat| current.is_none_or(|current| *seat > current))
+            .find(|seat| self.seats[*seat].as_ref().is_some_and(|table_seat| table_seat.connected));
+        match next_seat {
+            Some(seat) => {
+                self.active_seat = Some(seat);
+                None
+            }
+            None => Some(self.settle()),
+        }
+    }
+
+    ///settle plays the dealer's hand and pays every seat in play
+    fn settle(&mut self) -> Vec<SeatResult> {
+        let in_play = self.seats_in_play();
+        let any_seat_standing = in_play.iter()
+            .filter_map(|seat| self.seats[*seat].as_ref())
+            .any(|table_seat| !table_seat.hand.is_bust());
+        while any_seat_standing && dealer_should_hit(&self.dealer_hand, self.rules.dealer_hits_soft_17) {
+            self.dealer_hand.cards.push(self.deck.deal());
+        }
+        let dealer_bust = self.dealer_hand.is_bust();
+
+        let mut results = Vec::new();
+        for seat in in_play {
+            let Some(table_seat) = &mut self.seats[seat] else {
+                continue;
+            };
+            let wager = table_seat.hand.bet as i32;
+            let outcome = if table_seat.hand.is_bust() {
+                RoundOutcome::Loss
+            } else if dealer_bust && !table_seat.hand.is_blackjack() {
+                RoundOutcome::Win
+            } else {
+                determine_win(&table_seat.hand, &self.dealer_hand)
+            };
+            let payout = wager + outcome.net(wager);
+            table_seat.bankroll += payout;
+            table_seat.hand.bet = 0;
+            results.push(SeatResult { seat, outcome, wager, payout, bankroll: table_seat.bankroll });
+            if table_seat.leaving {
+                self.seats[seat] = None;
+            }
+        }
+
+        self.phase = TablePhase::Betting;
+        self.active_seat = None;
+        results
+    }
+}
@@ -0,0 +1,93 @@
+///trainer module is the basic strategy trainer of the Practice screen: a hand is dealt (two cards and the dealer's up
+/// card), the player picks an action and is told whether basic strategy agrees, one hand after another.
+
+use bevy::prelude::*;
+use super::components::{Card, Deck, PlayerHand};
+use super::hand_history::HandAction;
+use super::rules::Rules;
+use super::strategy::basic_strategy;
+use super::traits::{Dealable, Scorable};
+
+///struct TrainerHand is a hand to play in the trainer
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrainerHand {
+    pub cards: Vec<Card>,
+    pub up_card: Card,
+}
+
+impl TrainerHand {
+    ///deal deals a hand from the deck, a blackjack has nothing to decide and is dealt again
+    pub fn deal(deck: &mut Deck) -> Self {
+        loop {
+            let hand = Self { cards: vec![deck.deal(), deck.deal()], up_card: deck.deal() };
+            if !hand.player_hand().is_blackjack() {
+                return hand;
+            }
+        }
+    }
+
+    fn player_hand(&self) -> PlayerHand {
+        PlayerHand { cards: self.cards.clone(), bet: 0 }
+    }
+
+    ///description returns the hand as it is shown, e.g. "soft 17"
+    pub fn description(&self) -> String {
+        let player_hand = self.player_hand();
+        format!("{} {}", if player_hand.is_soft() { "soft" } else { "hard" }, player_hand.best_total())
+    }
+
+    ///best_action returns what basic strategy plays with the hand
+    pub fn best_action(&self, rules: &Rules) -> HandAction {
+        let player_hand = self.player_hand();
+        basic_strategy(player_hand.best_total(), player_hand.is_soft(), self.up_card.value.1, rules.allow_double_down)
+    }
+}
+
+///struct / resource Trainer is the trainer's own deck, the hand being played, the score and what was said about the last
+/// answer
+#[derive(Resource)]
+pub struct Trainer {
+    pub deck: Deck,
+    pub hand: TrainerHand,
+    pub answered: u32,
+    pub correct: u32,
+    pub feedback: String,
+}
+
+impl Trainer {
+    ///new starts the trainer with a deck shuffled with the seed
+    pub fn new(seed: u64) -> Self {
+        let mut deck = Deck::with_seed(seed);
+        let hand = TrainerHand::deal(&mut deck);
+        Self { deck, hand, answered: 0, correct: 0, feedback: String::new() }
+    }
+
+    ///answer scores the action picked for the hand against basic strategy and deals the next hand, returning whether it
+    /// was right
+    pub fn answer(&mut self, action: HandAction, rules: &Rules) -> bool {
+        let best_action = self.hand.best_action(rules);
+        let right = action == best_action;
+        self.answered += 1;
+        if right {
+            self.correct += 1;
+            self.feedback = format!("Correct: {} on {} against a {}", best_action.label(), self.hand.description(), self.up_card_label());
+        } else {
+            self.feedback = format!("Basic strategy plays {} on {} against a {}, not {}", best_action.label(), self.hand.description(), self.up_card_label(), action.label());
+        }
+        self.hand = TrainerHand::deal(&mut self.deck);
+        right
+    }
+
+    fn up_card_label(&self) -> String {
+        match self.hand.up_card.value.1 {
+            11 => String::from("A"),
+            value => value.to_string(),
+        }
+    }
+}
+
+impl Default for Trainer {
+    fn default() -> Self {
+        Self::new(rand::random())
+    }
+}
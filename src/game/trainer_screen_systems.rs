@@ -0,0 +1,95 @@
+///trainer screen systems module builds the Practice screen, where hands are played against the basic strategy chart,
+/// and handles its keys: the same keys and gamepad buttons as at the table
+
+use bevy::prelude::*;
+use super::components::{Card, TrainerScreen, TrainerText};
+use super::constants::AppState;
+use super::hand_history::HandAction;
+use super::rules::Rules;
+use super::trainer::Trainer;
+
+///trainer_screen_setup spawns the practice screen over the start screen
+pub fn trainer_screen_setup(mut commands: Commands, assets: Res<AssetServer>) {
+    commands.spawn((
+        NodeBundle {
+            style: Style {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                padding: UiRect::all(Val::Px(15.0)),
+                ..default()
+            },
+            background_color: BackgroundColor(Color::srgb(0.05, 0.2, 0.1)),
+            ..default()
+        },
+        TrainerScreen,
+    ))
+    .with_children(|parent| {
+        parent.spawn((
+            TextBundle::from_section("", TextStyle {
+                font: assets.load("fonts/FiraSans-SemiBold.ttf"),
+                font_size: 22.0,
+                color: Color::WHITE,
+            }),
+            TrainerText,
+        ));
+    });
+}
+
+///trainer_input plays the hand with H, S and D (or the gamepad's south, east and west buttons), Esc goes back
+pub fn trainer_input(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    gamepads: Res<Gamepads>,
+    gamepad_buttons: Res<ButtonInput<GamepadButton>>,
+    mut trainer: ResMut<Trainer>,
+    rules: Res<Rules>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    let actions = [
+        (KeyCode::KeyH, GamepadButtonType::South, HandAction::Hit),
+        (KeyCode::KeyS, GamepadButtonType::East, HandAction::Stand),
+        (KeyCode::KeyD, GamepadButtonType::West, HandAction::DoubleDown),
+    ];
+    for (key_code, gamepad_button, action) in actions {
+        let gamepad_pressed = gamepads.iter().any(|gamepad| gamepad_buttons.just_pressed(GamepadButton::new(gamepad, gamepad_button)));
+        if keyboard_input.just_pressed(key_code) || gamepad_pressed {
+            if action == HandAction::DoubleDown && !rules.allow_double_down {
+                continue;
+            }
+            trainer.answer(action, &rules);
+        }
+    }
+    if keyboard_input.just_pressed(KeyCode::Escape) || keyboard_input.just_pressed(KeyCode::Backspace) {
+        next_state.set(AppState::Start);
+    }
+}
+
+///update_trainer_text shows the hand to play, the answer to the last one and the score
+pub fn update_trainer_text(
+    trainer: Res<Trainer>,
+    rules: Res<Rules>,
+    mut text_query: Query<&mut Text, With<TrainerText>>,
+    added_text: Query<(), Added<TrainerText>>,
+) {
+    if !trainer.is_changed() && added_text.is_empty() {
+        return;
+    }
+    let cards: Vec<String> = trainer.hand.cards.iter().map(Card::code).collect();
+    let score = match trainer.answered {
+        0 => String::new(),
+        answered => format!("Score: {} / {answered} ({:.0}%)", trainer.correct, trainer.correct as f32 * 100.0 / answered as f32),
+    };
+    let keys = if rules.allow_double_down { "H: hit   S: stand   D: double down   Esc: back" } else { "H: hit   S: stand   Esc: back" };
+    for mut text in text_query.iter_mut() {
+        text.sections[0].value = format!(
+            "Practice - basic strategy\n\nYour hand: {} ({})\nDealer shows: {}\n\n{keys}\n\n{}\n{score}",
+            cards.join(" "), trainer.hand.description(), trainer.hand.up_card.code(), trainer.feedback,
+        );
+    }
+}
+
+///despawn_trainer_screen removes the practice screen when leaving it
+pub fn despawn_trainer_screen(mut commands: Commands, query: Query<Entity, With<TrainerScreen>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
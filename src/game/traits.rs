@@ -8,4 +8,48 @@ pub trait Shufflable{
 
 pub trait Dealable{
     fn deal(&mut self) -> Card;
-}
\ No newline at end of file
+}
+
+///Scorable is implemented by anything holding a set of cards that needs a blackjack total (player and dealer hands).
+/// aces count as 11 whenever that does not bust the hand, otherwise they count as 1.
+pub trait Scorable{
+    fn hand_cards(&self) -> &[Card];
+
+    ///best_total returns the highest total of the hand that is 21 or under, or the lowest total if every total busts
+    fn best_total(&self) -> u8 {
+        let (hard_total, has_ace) = hard_total_and_ace(self.hand_cards());
+        if has_ace && hard_total + 10 <= 21 {
+            hard_total + 10
+        } else {
+            hard_total
+        }
+    }
+
+    ///is_soft returns true when an ace is currently being counted as 11
+    fn is_soft(&self) -> bool {
+        let (hard_total, has_ace) = hard_total_and_ace(self.hand_cards());
+        has_ace && hard_total + 10 <= 21
+    }
+
+    fn is_bust(&self) -> bool {
+        self.best_total() > 21
+    }
+
+    ///is_blackjack returns true for a two card 21
+    fn is_blackjack(&self) -> bool {
+        self.hand_cards().len() == 2 && self.best_total() == 21
+    }
+}
+
+///hard_total_and_ace sums a set of cards counting every ace as 1, and reports whether there was an ace in the set
+fn hard_total_and_ace(cards: &[Card]) -> (u8, bool) {
+    let mut hard_total = 0;
+    let mut has_ace = false;
+    for card in cards {
+        hard_total += card.value.0;
+        if card.value.0 != card.value.1 {
+            has_ace = true;
+        }
+    }
+    (hard_total, has_ace)
+}
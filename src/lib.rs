@@ -0,0 +1,9 @@
+//! blackjack is a blackjack table for Bevy. the game binary is built from these plugins, and another app can add them to
+//! its own world: BlackjackCorePlugin plays the rounds, BlackjackUiPlugin draws the table and its buttons,
+//! BlackjackOnlinePlugin sits at a network table and BlackjackAudioPlugin plays sounds, while
+//! BlackjackConsolePlugin and BlackjackInspectorPlugin add a developer console and an F3 inspector.
+
+pub mod game;
+mod test;
+
+pub use game::plugins::{BlackjackAudioPlugin, BlackjackConsolePlugin, BlackjackCorePlugin, BlackjackInspectorPlugin, BlackjackOnlinePlugin, BlackjackUiPlugin, HeadlessPlugin, StartupPlugin};
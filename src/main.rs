@@ -1,6 +1,63 @@
-mod game;
-mod test;
-use game::run;
+use std::process::exit;
+use bevy::prelude::*;
+use blackjack::game::cli::{GameArgs, WindowMode, USAGE};
+use blackjack::game::logging::log_plugin;
+use blackjack::game::settings::Settings;
+use blackjack::game::storage::DataDir;
+use blackjack::{BlackjackAudioPlugin, BlackjackConsolePlugin, BlackjackCorePlugin, BlackjackInspectorPlugin, BlackjackUiPlugin, HeadlessPlugin, StartupPlugin};
+
+//main reads the command line (see cli::USAGE) and runs the game as it asked
 fn main() {
-    run();
-}
\ No newline at end of file
+    let args = GameArgs::parse(std::env::args().skip(1)).unwrap_or_else(|error| {
+        eprintln!("{error}\n\n{USAGE}");
+        exit(2);
+    });
+    if args.help {
+        println!("{USAGE}");
+        return;
+    }
+
+    let dev_tools = args.dev_tools();
+
+    //the logs are set up with the plugins, first, so everything after them can be logged
+    let mut app = App::new();
+    app.insert_resource(args.log.clone());
+    let data_dir = DataDir::default();
+    app.insert_resource(Settings::load(&data_dir));
+    app.insert_resource(data_dir);
+    match args.window_mode {
+        WindowMode::Headless => {
+            app.add_plugins((HeadlessPlugin, log_plugin(&args.log)));
+        }
+        WindowMode::Windowed | WindowMode::Fullscreen => {
+            app.add_plugins((StartupPlugin { fullscreen: args.window_mode == WindowMode::Fullscreen }, BlackjackAudioPlugin::default()));
+        }
+    }
+
+    let mut core = BlackjackCorePlugin::default()
+        .with_initial_state(args.initial_app_state())
+        .with_rules(args.rules.clone().unwrap_or_default());
+    if let Some(seed) = args.seed {
+        core = core.with_seed(seed);
+    }
+    if let Some(balance) = args.balance {
+        core = core.with_starting_balance(balance);
+    }
+    if let Some(scenario) = args.scenario {
+        core = core.with_scenario(scenario);
+    }
+    if let Some(profile) = args.profile {
+        core = core.with_profile(profile);
+    }
+    if let Some(scripts) = args.scripts {
+        core = core.with_scripts_dir(scripts);
+    }
+    if let Some((command, timeout)) = args.bot {
+        core = core.with_bot(command, timeout);
+    }
+    app.add_plugins((core, BlackjackUiPlugin::default(), args.online.unwrap_or_default()));
+    if dev_tools {
+        app.add_plugins((BlackjackConsolePlugin, BlackjackInspectorPlugin));
+    }
+    app.run();
+}
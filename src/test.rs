@@ -1,6 +1,22 @@
 use crate::game::{bundles::PlayerBundle, components::{PlayerBalance, PlayerHand, PlayerHands, PlayerName}, resources::BalanceValue, traits::Dealable};
 use crate::game::player_systems::spawn_player;
 
+///hand_of returns a hand of the cards with the given codes and a bet of 10
+#[cfg(test)]
+fn hand_of(codes: &[&str]) -> PlayerHand {
+    use crate::game::components::Card;
+
+    PlayerHand{ cards: codes.iter().map(|code| Card::from_code(code).unwrap()).collect(), bet: 10 }
+}
+
+///dealer_of returns a dealer's hand of the cards with the given codes
+#[cfg(test)]
+fn dealer_of(codes: &[&str]) -> crate::game::components::DealerHand {
+    use crate::game::components::{Card, DealerHand};
+
+    DealerHand{ cards: codes.iter().map(|code| Card::from_code(code).unwrap()).collect() }
+}
+
 #[test]
 fn test_spawn_player(){
     use bevy::prelude::*;
@@ -25,4 +41,829 @@ fn test_spawn_player(){
     assert!(player_found);
 
 
-}
\ No newline at end of file
+}
+#[test]
+fn test_hand_totals_and_statistics(){
+    use crate::game::components::{Card, DealerHand};
+    use crate::game::events::{RoundOutcome, HandSettled};
+    use crate::game::statistics::StatsCounters;
+    use crate::game::traits::Scorable;
+
+    let card = |face: &str, value: (u8, u8)| Card{
+        suite: String::from("spades"),
+        face: face.to_string(),
+        value,
+        front_asset_path: String::new(),
+        back_asset_path: String::new(),
+    };
+    let ace = card("ace", (1, 11));
+    let six = card("6", (6, 6));
+    let king = card("king", (10, 10));
+
+    let player_hand = PlayerHand{ cards: vec![ace.clone(), six.clone()], bet: 10 };
+    assert_eq!(player_hand.best_total(), 17);
+    assert!(player_hand.is_soft());
+
+    let dealer_hand = DealerHand{ cards: vec![ace.clone(), ace.clone(), king.clone()] };
+    assert_eq!(dealer_hand.best_total(), 12);
+    assert!(!dealer_hand.is_soft());
+
+    let mut counters = StatsCounters::default();
+    counters.record(&HandSettled::new(0, RoundOutcome::Win, 10, &player_hand, &dealer_hand));
+    counters.record(&HandSettled::new(0, RoundOutcome::Win, 20, &player_hand, &dealer_hand));
+    counters.record(&HandSettled::new(0, RoundOutcome::Push, 5, &player_hand, &dealer_hand));
+    counters.record(&HandSettled::new(0, RoundOutcome::Loss, 50, &player_hand, &dealer_hand));
+    assert_eq!(counters.hands_played, 4);
+    assert_eq!(counters.net_result, -20);
+    assert_eq!(counters.biggest_win, 20);
+    assert_eq!(counters.biggest_loss, 50);
+    assert_eq!(counters.longest_win_streak, 2);
+    assert_eq!(counters.longest_loss_streak, 1);
+    //player started on soft 17 against an ace up card (dealer card 1)
+    let tally = counters.tally(17, 11);
+    assert_eq!((tally.wins, tally.losses, tally.pushes), (2, 1, 1));
+}
+
+#[test]
+fn test_hand_history_appends_json_lines(){
+    use crate::game::components::Deck;
+    use crate::game::hand_history::{append_hand_record, HandAction, HandHistory, HandRecord};
+    use crate::game::rules::Rules;
+
+    let mut deck = Deck::with_seed(7);
+    let shoe_position = deck.last_dealt_index;
+    let shuffle_count = deck.shuffle_count;
+    let player_cards = vec![deck.deal(), deck.deal()];
+    let dealer_cards = vec![deck.deal(), deck.deal()];
+    let hit_card = deck.deal();
+
+    let mut hand_history = HandHistory::default();
+    hand_history.begin_round(&deck, shoe_position, shuffle_count, &Rules::default(), 2, 1000, &dealer_cards, &[(0, player_cards.clone(), 10), (1, Vec::new(), 5)]);
+    hand_history.record_action(0, HandAction::Hit, Some(&hit_card));
+    hand_history.record_action(0, HandAction::Stand, None);
+    hand_history.record_action(1, HandAction::Stand, None);
+    let record = hand_history.current.take().unwrap();
+    assert_eq!(record.seed, 7);
+    assert_eq!(record.seats[0].player_cards, vec![player_cards[0].code(), player_cards[1].code()]);
+    assert_eq!(record.seats[0].actions[0].card, Some(hit_card.code()));
+    assert_eq!(record.seats[1].actions.len(), 1);
+
+    let path = std::env::temp_dir().join(format!("blackjack_hand_history_{}.jsonl", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    append_hand_record(&path, &record).unwrap();
+    append_hand_record(&path, &record).unwrap();
+    let contents = std::fs::read_to_string(&path).unwrap();
+    let lines: Vec<&str> = contents.lines().collect();
+    assert_eq!(lines.len(), 2);
+    let parsed: HandRecord = serde_json::from_str(lines[1]).unwrap();
+    assert_eq!(parsed, record);
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_replay_steps_follow_recorded_round(){
+    use crate::game::events::RoundOutcome;
+    use crate::game::hand_history::{ActionRecord, HandAction, HandRecord, SeatRecord, HAND_RECORD_VERSION};
+    use crate::game::replay::replay_steps;
+    use crate::game::rules::Rules;
+
+    let codes = |codes: &[&str]| codes.iter().map(|code| code.to_string()).collect::<Vec<String>>();
+    let record = HandRecord{
+        version: HAND_RECORD_VERSION,
+        round_id: String::from("1-1"),
+        timestamp: 0,
+        seed: 1,
+        shuffle_count: 1,
+        shoe_position: 0,
+        rules: Rules::default(),
+        table_seats: 1,
+        starting_balance: 1000,
+        seats: vec![SeatRecord{
+            seat: 0,
+            player_cards: codes(&["10S", "2H"]),
+            bet: 10,
+            actions: vec![
+                ActionRecord{ action: HandAction::Hit, card: Some(String::from("5C")) },
+                ActionRecord{ action: HandAction::Stand, card: None },
+            ],
+            outcome: Some(RoundOutcome::Win),
+            wager: 10,
+            payout: 20,
+        }],
+        dealer_cards: codes(&["9C", "6D"]),
+        dealer_draws: codes(&["KH"]),
+        dealt: Vec::new(),
+        balance: 1010,
+    };
+
+    let steps = replay_steps(&record);
+    //deal, after hit, after stand, reveal, one dealer draw, result
+    assert_eq!(steps.len(), 6);
+    //hard 12 against a 6 is a stand, so the hit is flagged against basic strategy
+    assert_eq!(steps[0].optimal_action, Some(HandAction::Stand));
+    assert_eq!(steps[0].action_taken, Some(HandAction::Hit));
+    assert_eq!(steps[1].seat_cards[0].1.len(), 3);
+    assert_eq!(steps[1].optimal_action, Some(HandAction::Stand));
+    assert!(steps[2].dealer_hole_card_hidden);
+    assert!(!steps[3].dealer_hole_card_hidden);
+    assert_eq!(steps[4].dealer_cards.len(), 3);
+}
+
+#[test]
+fn test_companion_personalities(){
+    use crate::game::companions::Personality;
+    use crate::game::hand_history::HandAction;
+    use crate::game::rules::Rules;
+
+    let rules = Rules::default();
+
+    //hard 12 against a 2 is a hit for basic strategy, but never bust stands on it
+    assert_eq!(Personality::BasicStrategy.choose_action(&hand_of(&["10S", "2H"]), 2, &rules), HandAction::Hit);
+    assert_eq!(Personality::NeverBust.choose_action(&hand_of(&["10S", "2H"]), 2, &rules), HandAction::Stand);
+    //the mimic hits soft 17 like a dealer that hits soft 17
+    assert_eq!(Personality::MimicDealer.choose_action(&hand_of(&["AS", "6H"]), 10, &rules), HandAction::Hit);
+    assert_eq!(Personality::MimicDealer.choose_action(&hand_of(&["10S", "7H"]), 10, &rules), HandAction::Stand);
+    assert_eq!(Personality::Reckless.choose_action(&hand_of(&["10S", "2H"]), 10, &rules), HandAction::DoubleDown);
+    assert_eq!(Personality::Reckless.choose_action(&hand_of(&["10S", "7H"]), 10, &rules), HandAction::Hit);
+
+    //the counter ramps its bet with the true count, the others bet flat
+    assert_eq!(Personality::CardCounter.bet(-3, 26), Personality::CardCounter.bet(0, 52));
+    assert!(Personality::CardCounter.bet(6, 26) > Personality::CardCounter.bet(0, 52));
+    assert_eq!(Personality::BasicStrategy.bet(6, 26), Personality::BasicStrategy.bet(0, 52));
+    assert_eq!(Personality::CardCounter.next(), None);
+}
+
+///recv_until reads messages from a test client until one matches, failing the test if the server goes quiet
+#[cfg(test)]
+fn recv_until(
+    client: &mut crate::game::client::TableClient,
+    matches: impl Fn(&crate::game::protocol::ServerMessage) -> bool,
+) -> crate::game::protocol::ServerMessage {
+    loop {
+        let message = client.recv(Some(std::time::Duration::from_secs(5))).expect("the server stopped answering");
+        if matches(&message) {
+            return message;
+        }
+    }
+}
+
+#[cfg(test)]
+fn start_test_server(seats: usize) -> String {
+    use crate::game::components::Deck;
+    use crate::game::rules::Rules;
+    use crate::game::server::start_server;
+    use crate::game::table::Table;
+
+    let server = start_server("127.0.0.1:0", Table::new(Deck::with_seed(3), Rules::default(), seats, 1000)).unwrap();
+    server.address.to_string()
+}
+
+#[test]
+fn test_server_plays_a_round_with_several_clients(){
+    use std::io::{BufRead, BufReader, Write};
+    use crate::game::client::TableClient;
+    use crate::game::hand_history::HandAction;
+    use crate::game::protocol::{ClientMessage, ServerMessage, TablePhase, HIDDEN_CARD};
+
+    let address = start_test_server(3);
+
+    //a client speaking another version of the protocol is turned away
+    let mut stream = std::net::TcpStream::connect(&address).unwrap();
+    writeln!(stream, r#"{{"type":"hello","version":99,"name":"old","token":null}}"#).unwrap();
+    let mut line = String::new();
+    BufReader::new(stream).read_line(&mut line).unwrap();
+    assert!(line.contains(r#""type":"error""#));
+
+    let (mut alice, _) = TableClient::connect(&address, "alice", None).unwrap();
+    let (mut bob, _) = TableClient::connect(&address, "bob", None).unwrap();
+    assert_eq!((alice.seat, bob.seat), (Some(0), Some(1)));
+
+    alice.send(&ClientMessage::Bet { amount: 100 }).unwrap();
+    bob.send(&ClientMessage::Bet { amount: 2000 }).unwrap();
+    assert!(matches!(recv_until(&mut bob, |message| matches!(message, ServerMessage::Error { .. })), ServerMessage::Error { .. }));
+    bob.send(&ClientMessage::Bet { amount: 50 }).unwrap();
+
+    let dealt = |message: &ServerMessage| matches!(message, ServerMessage::Snapshot { snapshot } if snapshot.phase == TablePhase::PlayerTurns);
+    let ServerMessage::Snapshot { snapshot } = recv_until(&mut alice, dealt) else { unreachable!() };
+    assert_eq!(snapshot.active_seat, Some(0));
+    assert_eq!(snapshot.dealer_cards[0], HIDDEN_CARD);
+    assert!(snapshot.players.iter().all(|player| player.cards.len() == 2));
+    recv_until(&mut bob, dealt);
+
+    //bob has to wait for first base
+    bob.send(&ClientMessage::Action { action: HandAction::Stand }).unwrap();
+    recv_until(&mut bob, |message| matches!(message, ServerMessage::Error { .. }));
+    alice.send(&ClientMessage::Action { action: HandAction::Stand }).unwrap();
+    recv_until(&mut bob, |message| matches!(message, ServerMessage::Snapshot { snapshot } if snapshot.active_seat == Some(1)));
+    bob.send(&ClientMessage::Action { action: HandAction::Stand }).unwrap();
+
+    let ServerMessage::Settle { results } = recv_until(&mut alice, |message| matches!(message, ServerMessage::Settle { .. })) else { unreachable!() };
+    assert_eq!(results.len(), 2);
+    for (result, bet) in results.iter().zip([100, 50]) {
+        assert_eq!(result.wager, bet);
+        assert_eq!(result.bankroll, 1000 - bet + result.payout);
+    }
+}
+
+#[test]
+fn test_server_restores_a_seat_after_reconnecting(){
+    use crate::game::client::TableClient;
+    use crate::game::hand_history::HandAction;
+    use crate::game::protocol::{ClientMessage, ServerMessage, TablePhase};
+
+    let address = start_test_server(2);
+    let (mut alice, _) = TableClient::connect(&address, "alice", None).unwrap();
+    let (mut bob, _) = TableClient::connect(&address, "bob", None).unwrap();
+    alice.send(&ClientMessage::Bet { amount: 10 }).unwrap();
+    bob.send(&ClientMessage::Bet { amount: 10 }).unwrap();
+    recv_until(&mut bob, |message| matches!(message, ServerMessage::Snapshot { snapshot } if snapshot.phase == TablePhase::PlayerTurns));
+
+    //the seat cannot be taken over while alice is still connected
+    let token = alice.token.clone();
+    assert!(TableClient::connect(&address, "mallory", token.clone()).is_err());
+
+    //alice drops in the middle of her turn, her hand stands and the turn passes to bob
+    drop(alice);
+    recv_until(&mut bob, |message| matches!(message, ServerMessage::Snapshot { snapshot } if !snapshot.players[0].connected && snapshot.active_seat == Some(1)));
+
+    let (mut alice, snapshot) = TableClient::connect(&address, "alice", token).unwrap();
+    assert_eq!(alice.seat, Some(0));
+    assert_eq!(snapshot.phase, TablePhase::PlayerTurns);
+    assert_eq!(snapshot.active_seat, Some(1));
+    assert_eq!(snapshot.players[0].cards.len(), 2);
+    assert_eq!(snapshot.players[0].bankroll, 990);
+
+    bob.send(&ClientMessage::Action { action: HandAction::Stand }).unwrap();
+    recv_until(&mut alice, |message| matches!(message, ServerMessage::Settle { .. }));
+}
+
+#[test]
+fn test_spectator_joins_in_the_middle_of_a_round(){
+    use crate::game::client::TableClient;
+    use crate::game::hand_history::HandAction;
+    use crate::game::protocol::{ClientMessage, ServerMessage, TablePhase, HIDDEN_CARD};
+
+    let address = start_test_server(2);
+    let (mut alice, _) = TableClient::connect(&address, "alice", None).unwrap();
+    let (mut bob, _) = TableClient::connect(&address, "bob", None).unwrap();
+    alice.send(&ClientMessage::Bet { amount: 10 }).unwrap();
+    bob.send(&ClientMessage::Bet { amount: 20 }).unwrap();
+    recv_until(&mut bob, |message| matches!(message, ServerMessage::Snapshot { snapshot } if snapshot.phase == TablePhase::PlayerTurns));
+
+    //the spectator arrives in the middle of the round and is shown every seat, but not the hole card
+    let (mut spectator, snapshot) = TableClient::spectate(&address).unwrap();
+    assert_eq!(spectator.seat, None);
+    assert_eq!(spectator.token, None);
+    assert_eq!(snapshot.phase, TablePhase::PlayerTurns);
+    assert_eq!(snapshot.players.len(), 2);
+    assert_eq!(snapshot.players[1].bet, 20);
+    assert_eq!(snapshot.dealer_cards[0], HIDDEN_CARD);
+    assert_ne!(snapshot.dealer_cards[1], HIDDEN_CARD);
+
+    spectator.send(&ClientMessage::Action { action: HandAction::Stand }).unwrap();
+    recv_until(&mut spectator, |message| matches!(message, ServerMessage::Error { .. }));
+
+    alice.send(&ClientMessage::Action { action: HandAction::Stand }).unwrap();
+    recv_until(&mut spectator, |message| matches!(message, ServerMessage::Snapshot { snapshot } if snapshot.active_seat == Some(1)));
+    bob.send(&ClientMessage::Action { action: HandAction::Stand }).unwrap();
+    recv_until(&mut spectator, |message| matches!(message, ServerMessage::Settle { results } if results.len() == 2));
+    recv_until(&mut spectator, |message| matches!(message, ServerMessage::Snapshot { snapshot } if snapshot.dealer_cards.iter().all(|card| card != HIDDEN_CARD)));
+}
+
+#[test]
+fn test_reference_bot_answers_with_the_request_id(){
+    use crate::game::bot::{run_reference_bot, BotEnvelope, BotReply};
+    use crate::game::hand_history::HandAction;
+
+    let requests = concat!(
+        r#"{"type":"hello","version":2,"rules":{"dealer_hits_soft_17":true,"allow_double_down":true},"timeout_ms":2000}"#, "\n",
+        r#"{"id":1,"type":"bet","round":1,"bankroll":5,"min_bet":1,"max_bet":5}"#, "\n",
+        r#"{"id":2,"type":"action","round":1,"hand":{"cards":["6S","5H"],"total":11,"soft":false,"bet":5},"dealer_up_card":"6D","dealer_up_value":6,"bankroll":0,"legal_actions":["hit","stand"]}"#, "\n",
+        r#"{"type":"result","round":1,"outcome":"win","wager":5,"payout":10,"bankroll":10}"#, "\n",
+    );
+    let mut output = Vec::new();
+    run_reference_bot(requests.as_bytes(), &mut output).unwrap();
+
+    let replies: Vec<BotEnvelope<BotReply>> = String::from_utf8(output).unwrap().lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(replies, vec![
+        BotEnvelope { id: Some(1), message: BotReply::Bet { amount: 5 } },
+        //11 against a 6 doubles when it can, and hits when the bankroll does not cover the double
+        BotEnvelope { id: Some(2), message: BotReply::Action { action: HandAction::Hit } },
+    ]);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_simulator_plays_the_default_when_the_bot_misbehaves(){
+    use std::time::Duration;
+    use crate::game::bot::BotProcess;
+    use crate::game::components::Deck;
+    use crate::game::constants::BOT_DEFAULT_BET;
+    use crate::game::rules::Rules;
+    use crate::game::simulator::simulate;
+
+    //a bot that never answers times out on its bet and on its action, and the hand stands
+    let mut silent_bot = BotProcess::spawn("sleep 10", &Rules::default(), Duration::from_millis(50)).unwrap();
+    let report = simulate(&mut silent_bot, Deck::with_seed(1), Rules::default(), 1000, 1);
+    assert_eq!(report.rounds, 1);
+    assert_eq!(report.timeouts, 2);
+    assert_eq!(report.illegal_moves, 2);
+    assert!((report.bankroll - 1000).abs() <= BOT_DEFAULT_BET);
+
+    //a bot answering nonsense has every answer turned down but the rounds go on
+    let mut confused_bot = BotProcess::spawn("yes nonsense", &Rules::default(), Duration::from_millis(500)).unwrap();
+    let report = simulate(&mut confused_bot, Deck::with_seed(1), Rules::default(), 1000, 3);
+    assert_eq!(report.rounds, 3);
+    assert_eq!(report.timeouts, 0);
+    assert_eq!(report.illegal_moves, 6);
+    assert_eq!(report.stopped, None);
+}
+
+#[test]
+fn test_example_scripts(){
+    use std::path::Path;
+    use crate::game::events::RoundOutcome;
+    use crate::game::scripting::{BetInputs, BetProgress, HandInputs, Script};
+    use crate::game::strategy::basic_strategy;
+
+    //the basic strategy script plays the same chart as the replay viewer
+    let strategy = Script::load(Path::new("scripts/basic_strategy.rhai")).unwrap();
+    assert!(strategy.plays() && !strategy.bets());
+    for soft in [false, true] {
+        for total in if soft { 12..=21 } else { 4..=21 } {
+            for up_card in 2..=11 {
+                for can_double in [false, true] {
+                    let inputs = HandInputs { total, soft, pair: false, up_card, running_count: 0, bankroll: 100, can_double };
+                    assert_eq!(strategy.choose_action(&inputs).unwrap(), basic_strategy(total, soft, up_card, can_double), "{total} soft {soft} against {up_card}");
+                }
+            }
+        }
+    }
+
+    //each betting system is fed the same run of outcomes
+    let outcomes = [RoundOutcome::Win, RoundOutcome::Win, RoundOutcome::Win, RoundOutcome::Win, RoundOutcome::Loss, RoundOutcome::Loss, RoundOutcome::Push];
+    let bets_for = |name: &str| {
+        let script = Script::load(Path::new(&format!("scripts/{name}.rhai"))).unwrap();
+        let mut inputs = BetInputs { bankroll: 1000, running_count: 0, base_bet: 10, progress: BetProgress::default() };
+        let mut bets = Vec::new();
+        for outcome in outcomes {
+            let bet = script.bet(&inputs).unwrap();
+            inputs.progress.record(outcome, bet);
+            bets.push(bet);
+        }
+        bets
+    };
+    assert_eq!(bets_for("martingale"), vec![10, 10, 10, 10, 10, 20, 40]);
+    assert_eq!(bets_for("paroli"), vec![10, 20, 40, 10, 20, 10, 10]);
+    assert_eq!(bets_for("one_three_two_six"), vec![10, 30, 20, 60, 10, 10, 10]);
+}
+
+#[test]
+fn test_script_errors_are_reported(){
+    use crate::game::scripting::{BetInputs, HandInputs, Script};
+
+    let inputs = HandInputs { total: 12, soft: false, pair: false, up_card: 10, running_count: 0, bankroll: 100, can_double: false };
+    assert!(Script::compile("empty", "let x = 1;").is_err());
+    assert!(Script::compile("broken", "fn action(hand) {").is_err());
+    let unknown_action = Script::compile("unknown", r#"fn action(hand) { "split" }"#).unwrap();
+    assert!(unknown_action.choose_action(&inputs).is_err());
+    let cannot_double = Script::compile("double", r#"fn action(hand) { "double" }"#).unwrap();
+    assert!(cannot_double.choose_action(&inputs).is_err());
+    let endless = Script::compile("endless", "fn action(hand) { loop {} }").unwrap();
+    let error = endless.choose_action(&inputs).unwrap_err();
+    assert_eq!(error.script, "endless");
+
+    let too_much = Script::compile("too_much", "fn bet(table) { table.bankroll + 1 }").unwrap();
+    assert!(too_much.bet(&BetInputs { bankroll: 50, base_bet: 10, ..Default::default() }).is_err());
+}
+
+#[test]
+fn test_scripts_are_loaded_from_the_scripts_directory(){
+    use bevy::prelude::*;
+    use crate::game::scripting::{load_scripts, CompanionScripts, ScriptsDir};
+
+    let mut app = App::new();
+    app.insert_resource(ScriptsDir(std::path::PathBuf::from("scripts")))
+        .init_resource::<CompanionScripts>()
+        .add_systems(Update, load_scripts);
+    app.update();
+    assert_eq!(app.world().resource::<CompanionScripts>().scripts.len(), 4);
+
+    //a data directory without scripts has none
+    let mut app = App::new();
+    app.insert_resource(ScriptsDir(std::env::temp_dir().join(format!("blackjack_no_scripts_{}", std::process::id()))))
+        .init_resource::<CompanionScripts>()
+        .add_systems(Update, load_scripts);
+    app.update();
+    assert!(app.world().resource::<CompanionScripts>().scripts.is_empty());
+}
+
+#[test]
+fn test_autoplay_stops(){
+    use crate::game::autoplay::{Autoplay, AutoplayStrategy};
+    use crate::game::companions::Personality;
+    use crate::game::constants::{AUTOPLAY_HAND_LIMITS, AUTOPLAY_STOP_AMOUNTS};
+    use crate::game::events::RoundOutcome;
+    use crate::game::scripting::CompanionScripts;
+
+    let mut autoplay = Autoplay { stop_win: 1, stop_loss: 1, hand_limit: 1, ..Default::default() };
+    autoplay.start(1000);
+    autoplay.record(RoundOutcome::Win, 10, 1010);
+    assert!(autoplay.enabled);
+    autoplay.record(RoundOutcome::Win, 40, 1000 + AUTOPLAY_STOP_AMOUNTS[1]);
+    assert!(!autoplay.enabled);
+
+    autoplay.start(1000);
+    autoplay.record(RoundOutcome::Loss, 50, 1000 - AUTOPLAY_STOP_AMOUNTS[1]);
+    assert!(!autoplay.enabled);
+
+    autoplay.start(1000);
+    for _ in 0..AUTOPLAY_HAND_LIMITS[1] {
+        assert!(autoplay.enabled);
+        autoplay.record(RoundOutcome::Push, 10, 1000);
+    }
+    assert!(!autoplay.enabled);
+    assert_eq!(autoplay.hands_played, AUTOPLAY_HAND_LIMITS[1]);
+
+    //without scripts the strategies go round the personalities
+    let companion_scripts = CompanionScripts::default();
+    for _ in 0..Personality::ALL.len() {
+        autoplay.next_strategy(&companion_scripts);
+    }
+    assert_eq!(autoplay.strategy, AutoplayStrategy::Personality(Personality::ALL[0]));
+}
+
+#[test]
+fn test_player_actions_are_validated(){
+    use crate::game::events::{ActionKind, PlayerAction};
+    use crate::game::hand_history::HandAction;
+    use crate::game::player_systems::validate_action;
+    use crate::game::rules::Rules;
+
+    let hands = |codes: &[&str]| PlayerHands(vec![hand_of(codes)]);
+    let rules = Rules::default();
+    let two_cards = hands(&["5H", "6D"]);
+
+    assert_eq!(validate_action(&PlayerAction::new(ActionKind::Hit), &two_cards, 100, &rules), Ok(HandAction::Hit));
+    assert_eq!(validate_action(&PlayerAction::new(ActionKind::Double), &two_cards, 100, &rules), Ok(HandAction::DoubleDown));
+    //doubling needs the bet again and the first two cards
+    assert!(validate_action(&PlayerAction::new(ActionKind::Double), &two_cards, 5, &rules).is_err());
+    assert!(validate_action(&PlayerAction::new(ActionKind::Double), &hands(&["5H", "2D", "3C"]), 100, &rules).is_err());
+    //a bust hand, a hand that does not exist and the actions this table does not offer are turned down
+    assert!(validate_action(&PlayerAction::new(ActionKind::Stand), &hands(&["KH", "QD", "5C"]), 100, &rules).is_err());
+    assert!(validate_action(&PlayerAction { kind: ActionKind::Hit, hand: 1 }, &two_cards, 100, &rules).is_err());
+    for kind in [ActionKind::Split, ActionKind::Surrender, ActionKind::Insurance] {
+        assert!(validate_action(&PlayerAction::new(kind), &hands(&["8H", "8D"]), 100, &rules).is_err());
+    }
+}
+
+#[test]
+fn test_round_state_transitions(){
+    use bevy::prelude::*;
+    use bevy::ecs::system::RunSystemOnce;
+    use bevy::state::app::StatesPlugin;
+    use crate::game::constants::GameRoundState;
+    use crate::game::round_state::{exit_contract, phase_contract, RoundSnapshot, RoundTransition};
+
+    //every path a round can take from betting until the table is back to betting
+    fn paths_from(path: Vec<GameRoundState>, paths: &mut Vec<Vec<GameRoundState>>) {
+        let last = *path.last().unwrap();
+        for next in last.next_states() {
+            let mut path = path.clone();
+            path.push(*next);
+            if *next == GameRoundState::Betting {
+                paths.push(path);
+            } else {
+                paths_from(path, paths);
+            }
+        }
+    }
+    let mut paths = Vec::new();
+    paths_from(vec![GameRoundState::Betting], &mut paths);
+    use GameRoundState::*;
+    assert_eq!(paths, vec![
+        vec![Betting, Dealing, PlayerHand, DealerHand, Settlement, RoundEnd, Betting],
+        vec![Betting, Dealing, Betting],
+    ]);
+    assert_eq!(RoundStart.next_states(), &[Betting]);
+
+    //any other transition is turned down, except going back to the start which resets the round
+    for from in GameRoundState::ALL {
+        for to in GameRoundState::ALL {
+            let in_a_path = paths.iter().any(|path| path.windows(2).any(|step| step == [from, to])) || (from, to) == (RoundStart, Betting);
+            assert_eq!(from.can_transition_to(to), in_a_path || to == RoundStart, "{from:?} -> {to:?}");
+        }
+    }
+
+    let mut app = App::new();
+    app.add_plugins(StatesPlugin);
+    app.insert_state(Betting);
+    app.world_mut().run_system_once(|mut round_transition: RoundTransition| assert!(!round_transition.request(RoundEnd)));
+    app.update();
+    assert_eq!(*app.world().resource::<State<GameRoundState>>().get(), Betting);
+    app.world_mut().run_system_once(|mut round_transition: RoundTransition| assert!(round_transition.request(Dealing)));
+    app.update();
+    assert_eq!(*app.world().resource::<State<GameRoundState>>().get(), Dealing);
+
+    //the phase contracts
+    let dealt = RoundSnapshot { seat_cards: vec![2, 3], dealer_cards: 2, bet: 20, standing_seats: 2, dealer_total: 15 };
+    assert!(phase_contract(PlayerHand, &dealt).is_ok());
+    assert!(phase_contract(Betting, &dealt).is_err());
+    assert!(phase_contract(Settlement, &dealt).is_err());
+    assert!(phase_contract(PlayerHand, &RoundSnapshot { seat_cards: vec![1], dealer_cards: 2, bet: 10, ..RoundSnapshot::default() }).is_err());
+    assert!(phase_contract(Dealing, &RoundSnapshot::default()).is_ok());
+    assert!(phase_contract(RoundEnd, &RoundSnapshot { bet: 0, ..dealt.clone() }).is_ok());
+
+    //what a phase leaves behind when it ends
+    assert!(exit_contract(PlayerHand, DealerHand, &dealt).is_ok());
+    assert!(exit_contract(Betting, Dealing, &dealt).is_err());
+    assert!(exit_contract(Dealing, Betting, &dealt).is_err());
+    assert!(exit_contract(Dealing, Betting, &RoundSnapshot::default()).is_ok());
+    assert!(exit_contract(DealerHand, Settlement, &dealt).is_err());
+    assert!(exit_contract(DealerHand, Settlement, &RoundSnapshot { standing_seats: 0, ..dealt.clone() }).is_ok());
+    assert!(exit_contract(DealerHand, Settlement, &RoundSnapshot { dealer_cards: 3, dealer_total: 19, ..dealt.clone() }).is_ok());
+    assert!(exit_contract(RoundEnd, Betting, &dealt).is_err());
+    //a reset can leave any phase as it is
+    assert!(exit_contract(RoundEnd, RoundStart, &dealt).is_ok());
+}
+
+#[test]
+fn test_log_settings_and_rotation(){
+    use std::io::Write;
+    use bevy::log::Level;
+    use crate::game::logging::{log_file_path, LogSettings, RotatingLogFile};
+
+    let mut settings = LogSettings::default();
+    settings.set_levels("warn,bot=debug,blackjack::game::companions=trace").unwrap();
+    assert_eq!(settings.level, Level::WARN);
+    assert_eq!(settings.filter(), "wgpu=error,naga=warn,blackjack=WARN,blackjack::game::bot=DEBUG,blackjack::game::companions=TRACE");
+    assert!(settings.set_levels("loud").is_err());
+
+    //each start moves the last logs down by one, keeping five
+    let directory = std::env::temp_dir().join(format!("blackjack_logs_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&directory);
+    for session in 0..7 {
+        let mut log_file = RotatingLogFile::open(&directory).unwrap();
+        write!(log_file, "session {session}").unwrap();
+    }
+    assert_eq!(std::fs::read_to_string(log_file_path(&directory, 0)).unwrap(), "session 6");
+    assert_eq!(std::fs::read_to_string(log_file_path(&directory, 5)).unwrap(), "session 1");
+    assert!(!log_file_path(&directory, 6).exists());
+    std::fs::remove_dir_all(&directory).unwrap();
+}
+
+#[test]
+fn test_command_line(){
+    use std::time::Duration;
+    use crate::game::cli::{GameArgs, WindowMode};
+    use crate::game::constants::AppState;
+    use crate::game::rules::Rules;
+
+    let parse = |line: &str| GameArgs::parse(line.split_whitespace().map(String::from));
+
+    let args = parse("").unwrap();
+    assert_eq!(args.initial_app_state(), AppState::Start);
+    assert_eq!(args.window_mode, WindowMode::Windowed);
+    assert!(args.log.log_file);
+    assert!(!args.dev);
+    assert!(parse("--dev").unwrap().dev_tools());
+
+    let args = parse("--seed 7 --rules s17 --balance 250 --skip-title --fullscreen --scenario scenarios/split_aces_refused.json --no-log-file").unwrap();
+    assert_eq!(args.seed, Some(7));
+    assert_eq!(args.rules, Rules::preset("s17"));
+    assert_eq!(args.balance, Some(250));
+    assert!(args.scenario.is_some());
+    assert_eq!(args.window_mode, WindowMode::Fullscreen);
+    assert_eq!(args.initial_app_state(), AppState::InGame);
+    assert!(!args.log.log_file);
+    assert_eq!(args.profile, None);
+    assert_eq!(parse("--profile ann").unwrap().profile, Some(String::from("ann")));
+    assert_eq!(parse("--scripts scripts").unwrap().scripts, Some(std::path::PathBuf::from("scripts")));
+
+    let args = parse("--headless --bot ./bot --bot-timeout 50").unwrap();
+    assert_eq!(args.initial_app_state(), AppState::InGame);
+    assert_eq!(args.bot, Some((String::from("./bot"), Duration::from_millis(50))));
+    let args = parse("--spectate 10.0.0.1:7878").unwrap();
+    assert_eq!(args.initial_app_state(), AppState::Online);
+    assert!(parse("--help").unwrap().help);
+
+    //everything that cannot be used is turned down
+    for line in [
+        "--seed", "--seed abc", "--balance 0", "--balance -5", "--rules vegas", "--scenario missing.json",
+        "--windowed --fullscreen", "--headless --windowed", "--bot-timeout 10", "--name Ann",
+        "--connect a:1 --spectate b:2", "--connect a:1 --skip-title", "--log-level loud", "--deal",
+        "--profile ../ann", "--profile a/b", "--profile ..", "--scripts missing",
+    ] {
+        assert!(parse(line).is_err(), "{line} was accepted");
+    }
+}
+
+#[test]
+fn test_console_commands(){
+    use crate::game::components::Card;
+    use crate::game::console::{ConsoleCommand, ConsoleRule, ConsoleTarget};
+    use crate::game::constants::GameRoundState;
+
+    let give = |code: &str, target| Ok(ConsoleCommand::Give { card: Card::from_code(code).unwrap(), target });
+    assert_eq!(ConsoleCommand::parse("give AS"), give("AS", ConsoleTarget::ActiveSeat));
+    assert_eq!(ConsoleCommand::parse("give 10H dealer"), give("10H", ConsoleTarget::Dealer));
+    assert_eq!(ConsoleCommand::parse("give 7C seat 2"), give("7C", ConsoleTarget::Seat(1)));
+    assert_eq!(ConsoleCommand::parse("balance 5000"), Ok(ConsoleCommand::Balance(5000)));
+    assert_eq!(ConsoleCommand::parse("state dealer hand"), Ok(ConsoleCommand::State(GameRoundState::DealerHand)));
+    assert_eq!(ConsoleCommand::parse("state Settlement"), Ok(ConsoleCommand::State(GameRoundState::Settlement)));
+    assert_eq!(ConsoleCommand::parse("  seed "), Ok(ConsoleCommand::Seed(None)));
+    assert_eq!(ConsoleCommand::parse("seed 42"), Ok(ConsoleCommand::Seed(Some(42))));
+    assert_eq!(ConsoleCommand::parse("deck remaining"), Ok(ConsoleCommand::DeckRemaining));
+    assert_eq!(ConsoleCommand::parse("rules set h17 false"), Ok(ConsoleCommand::SetRule(ConsoleRule::H17, false)));
+
+    for line in ["", "give ZZ", "give AS seat 0", "balance lots", "state lunch", "rules set h17 maybe", "rules set surrender true", "fold"] {
+        assert!(ConsoleCommand::parse(line).is_err(), "{line} was accepted");
+    }
+}
+
+#[test]
+fn test_trainer_and_menu_focus(){
+    use crate::game::components::{Card, Deck, MenuEntry};
+    use crate::game::hand_history::HandAction;
+    use crate::game::main_menu_systems::next_menu_focus;
+    use crate::game::resources::LastSession;
+    use crate::game::rules::Rules;
+    use crate::game::trainer::Trainer;
+
+    //the focus skips Continue without a table to go back to, and wraps around
+    let no_session = LastSession::default();
+    assert_eq!(MenuEntry::ALL[next_menu_focus(0, 1, &no_session)], MenuEntry::Practice);
+    assert_eq!(MenuEntry::ALL[next_menu_focus(0, -1, &no_session)], MenuEntry::Quit);
+    assert_eq!(MenuEntry::ALL[next_menu_focus(0, 1, &LastSession { balance: Some(500) })], MenuEntry::Continue);
+
+    //hard 16 against a 10, then hard 11 against a 6
+    let mut trainer = Trainer::new(0);
+    let cards: Vec<Card> = ["10H", "6C", "10S", "5D", "6H", "6S"].iter().filter_map(|code| Card::from_code(code)).collect();
+    trainer.deck = Deck::rigged(&cards, 0);
+    trainer.hand = crate::game::trainer::TrainerHand::deal(&mut trainer.deck);
+    let rules = Rules::default();
+    assert!(!trainer.answer(HandAction::Stand, &rules));
+    assert_eq!(trainer.feedback, "Basic strategy plays Hit on hard 16 against a 10, not Stand");
+    assert!(trainer.answer(HandAction::DoubleDown, &rules));
+    assert_eq!((trainer.correct, trainer.answered), (1, 2));
+}
+
+#[test]
+fn test_settings_entries(){
+    use crate::game::settings::{CardBack, Language, Settings, SettingsEntry};
+
+    let mut settings = Settings::default();
+    //volumes stop at the ends, choices go around and switches are turned over
+    SettingsEntry::MasterVolume.change(&mut settings, 1);
+    assert_eq!(settings.master_volume, 1.0);
+    for _ in 0..3 {
+        SettingsEntry::MusicVolume.change(&mut settings, -1);
+    }
+    assert_eq!(settings.music_volume, 0.4);
+    SettingsEntry::AnimationSpeed.change(&mut settings, 1);
+    assert_eq!(settings.animation_speed, 1.5);
+    SettingsEntry::CardBack.change(&mut settings, -1);
+    assert_eq!(settings.card_back, CardBack::Green);
+    SettingsEntry::Hints.change(&mut settings, 1);
+    assert!(!settings.hints);
+    assert_eq!(SettingsEntry::MusicVolume.text(&settings), "Music volume: 40%");
+
+    SettingsEntry::Language.change(&mut settings, 1);
+    assert_eq!(settings.language, Language::Spanish);
+    assert_eq!(SettingsEntry::Hints.text(&settings), "Consejos: No");
+    assert_eq!(Language::Spanish.translate("Deal"), "Repartir");
+    assert_eq!(Language::Spanish.translate("Bet Amount:"), "Bet Amount:");
+
+    //saved settings are read back, and a setting missing from the file gets its default
+    let json = serde_json::to_string(&settings).unwrap();
+    assert_eq!(serde_json::from_str::<Settings>(&json).unwrap(), settings);
+    let partial: Settings = serde_json::from_str(r#"{"hints": false}"#).unwrap();
+    assert_eq!(partial, Settings { hints: false, ..Settings::default() });
+}
+
+#[test]
+fn test_leave_warning(){
+    use crate::game::constants::GameRoundState;
+    use crate::game::pause_menu_systems::leave_warning;
+
+    assert_eq!(leave_warning(25, 975, GameRoundState::Betting), "The $25 you have bet is not dealt yet, and is handed back if you leave now.\nContinue sits back down with $1000.");
+    assert_eq!(leave_warning(100, 0, GameRoundState::PlayerHand), "The round is not over: your $100 bet is forfeited, and the hand is recorded as lost.\nThere is no balance left to Continue with.");
+}
+
+#[test]
+fn test_a_reshuffle_in_the_middle_of_a_round_keeps_the_cards_on_the_table(){
+    use crate::game::components::Deck;
+    use crate::game::traits::Dealable;
+
+    //the round starts with 3 cards left in the shoe, the fourth card dealt comes from a new shuffle
+    let mut deck = Deck::with_seed(11);
+    for _ in 0..48 {
+        deck.deal();
+    }
+    deck.start_round();
+    let on_table: Vec<String> = (0..3).map(|_| deck.deal().code()).collect();
+    assert_eq!(deck.shuffle_count, 1);
+    let mut dealt_after: Vec<String> = Vec::new();
+    dealt_after.push(deck.deal().code());
+    assert_eq!(deck.shuffle_count, 2);
+    while deck.cards_remaining() > 0 {
+        dealt_after.push(deck.deal().code());
+    }
+    assert_eq!(dealt_after.len(), 51 - on_table.len());
+    assert!(dealt_after.iter().all(|code| !on_table.contains(code)), "{on_table:?} dealt again in {dealt_after:?}");
+    assert_eq!(deck.cards_dealt() + deck.cards_remaining(), deck.shoe_size());
+
+    //the next round takes the table's cards back into the shoe
+    deck.start_round();
+    let mut next_shoe: Vec<String> = (0..51).map(|_| deck.deal().code()).collect();
+    next_shoe.sort();
+    next_shoe.dedup();
+    assert_eq!(next_shoe.len(), 51);
+}
+
+#[test]
+fn test_hand_history_lines_without_seats_are_still_read(){
+    use crate::game::events::RoundOutcome;
+    use crate::game::hand_history::{parse_hand_record, HandAction, HAND_RECORD_VERSION};
+
+    //a line written before the table had seats
+    let line = r#"{"round_id":"1-1","timestamp":0,"seed":3,"shuffle_count":1,"shoe_position":4,"rules":{"dealer_hits_soft_17":true,"allow_double_down":true},"starting_balance":1000,"player_cards":["10S","2H"],"dealer_cards":["9C","6D"],"bet":10,"actions":[{"action":"hit","card":"5C"},{"action":"stand","card":null}],"dealer_draws":["KH"],"outcome":"win","wager":10,"payout":20,"balance":1010}"#;
+    let record = parse_hand_record(line).unwrap();
+    assert_eq!(record.version, HAND_RECORD_VERSION);
+    assert_eq!(record.table_seats, 1);
+    assert_eq!(record.seats.len(), 1);
+    assert_eq!(record.seats[0].player_cards, vec!["10S", "2H"]);
+    assert_eq!(record.seats[0].actions[0].action, HandAction::Hit);
+    assert_eq!(record.seats[0].outcome, Some(RoundOutcome::Win));
+    assert_eq!(record.seats[0].payout, 20);
+
+    //a line of version 1 has no dealt cards
+    let mut value = serde_json::to_value(&record).unwrap();
+    value["version"] = 1.into();
+    value.as_object_mut().unwrap().remove("dealt");
+    let record = parse_hand_record(&value.to_string()).unwrap();
+    assert_eq!(record.version, HAND_RECORD_VERSION);
+    assert!(record.dealt.is_empty());
+
+    //a record of the current version reads back as it was written
+    let written = serde_json::to_string(&record).unwrap();
+    assert_eq!(parse_hand_record(&written).unwrap(), record);
+}
+
+#[test]
+fn test_a_blackjack_beats_a_21_and_pays_3_to_2(){
+    use crate::game::events::RoundOutcome;
+    use crate::game::settlement::determine_win;
+
+    assert_eq!(determine_win(&hand_of(&["AH", "KC"]), &dealer_of(&["7D", "6S", "8C"])), RoundOutcome::Blackjack);
+    assert_eq!(determine_win(&hand_of(&["7H", "6C", "8S"]), &dealer_of(&["AD", "QS"])), RoundOutcome::Loss);
+    assert_eq!(determine_win(&hand_of(&["AH", "KC"]), &dealer_of(&["AD", "QS"])), RoundOutcome::Push);
+    assert_eq!(determine_win(&hand_of(&["10H", "9C"]), &dealer_of(&["10D", "8S"])), RoundOutcome::Win);
+
+    assert_eq!(RoundOutcome::Blackjack.net(50), 75);
+    assert_eq!(RoundOutcome::Win.net(50), 50);
+    assert_eq!(RoundOutcome::Loss.net(50), -50);
+    assert_eq!(RoundOutcome::Push.net(50), 0);
+}
+
+#[test]
+fn test_resetting_a_profile_keeps_the_session_and_the_other_profiles(){
+    use crate::game::events::{HandSettled, RoundOutcome};
+    use crate::game::statistics::{reset_statistics, statistics_path, Statistics, StatsCounters};
+    use crate::game::storage::{load_json, save_json, DataDir};
+
+    let data_dir = DataDir(std::env::temp_dir().join(format!("blackjack_profiles_{}", std::process::id())));
+    let player_hand = hand_of(&["10S", "9H"]);
+    let dealer_hand = dealer_of(&["10D", "7C"]);
+    let round = HandSettled::new(0, RoundOutcome::Win, 10, &player_hand, &dealer_hand);
+
+    let mut other = StatsCounters::default();
+    other.record(&round);
+    save_json(&statistics_path(&data_dir, "other"), &other).unwrap();
+
+    let mut statistics = Statistics::default();
+    statistics.lifetime.record(&round);
+    statistics.session.record(&round);
+    reset_statistics(&data_dir, "ann", &mut statistics);
+
+    assert_eq!(statistics.lifetime.hands_played, 0);
+    assert_eq!(statistics.session.hands_played, 1);
+    let saved: StatsCounters = load_json(&statistics_path(&data_dir, "ann")).unwrap().unwrap();
+    assert_eq!(saved.hands_played, 0);
+    let untouched: StatsCounters = load_json(&statistics_path(&data_dir, "other")).unwrap().unwrap();
+    assert_eq!(untouched.hands_played, 1);
+    let _ = std::fs::remove_dir_all(&data_dir.0);
+}
+
+#[test]
+fn test_bot_command_lines_are_split_like_a_shell(){
+    use crate::game::bot::split_command_line;
+
+    assert_eq!(split_command_line("./bot --fast").unwrap(), vec!["./bot", "--fast"]);
+    assert_eq!(split_command_line(r#""/my bots/bot" --name 'Lucky Lou'"#).unwrap(), vec!["/my bots/bot", "--name", "Lucky Lou"]);
+    assert_eq!(split_command_line(r"python3 my\ bot.py").unwrap(), vec!["python3", "my bot.py"]);
+    assert!(split_command_line(r#""./bot"#).is_err());
+    assert!(split_command_line("   ").unwrap().is_empty());
+}
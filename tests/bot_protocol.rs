@@ -0,0 +1,15 @@
+use std::time::Duration;
+use blackjack::game::bot::BotProcess;
+use blackjack::game::components::Deck;
+use blackjack::game::rules::Rules;
+use blackjack::game::simulator::simulate;
+
+#[test]
+fn test_reference_bot_plays_a_simulation(){
+    let mut bot = BotProcess::spawn(env!("CARGO_BIN_EXE_blackjack-bot"), &Rules::default(), Duration::from_secs(5)).unwrap();
+    let report = simulate(&mut bot, Deck::with_seed(7), Rules::default(), 1000, 200);
+    assert_eq!(report.rounds, 200);
+    assert_eq!(report.illegal_moves, 0);
+    assert_eq!(report.stopped, None);
+    assert_eq!(report.wins + report.losses + report.pushes, 200);
+}
@@ -0,0 +1,73 @@
+//embedding adds the game's plugins to an app of its own, the way another Bevy game would drop a table into its world
+
+use std::time::Duration;
+use bevy::prelude::*;
+use blackjack::game::components::PlayerHands;
+use blackjack::game::constants::{AppState, GameRoundState};
+use blackjack::game::errors::{GameError, GameErrors};
+use blackjack::game::events::{ActionKind, PlayerAction};
+use blackjack::game::resources::{BalanceValue, BetValue, StartingBalance};
+use blackjack::game::storage::DataDir;
+use blackjack::{BlackjackCorePlugin, BlackjackUiPlugin, HeadlessPlugin};
+
+#[test]
+fn test_the_table_can_be_added_to_another_app(){
+    let mut app = App::new();
+    app.insert_resource(DataDir(std::env::temp_dir().join(format!("blackjack_embedding_{}", std::process::id()))));
+    app.add_plugins((
+        HeadlessPlugin,
+        BlackjackCorePlugin::default()
+            .with_initial_state(AppState::InGame)
+            .with_starting_balance(250)
+            .with_seed(7),
+        //the app has a camera of its own
+        BlackjackUiPlugin::default().with_camera(false),
+    ))
+        .add_systems(Startup, |mut commands: Commands| {
+            commands.spawn(Camera2dBundle::default());
+        });
+
+    for _ in 0..10 {
+        app.update();
+    }
+    assert_eq!(*app.world().resource::<State<GameRoundState>>().get(), GameRoundState::Betting);
+    assert_eq!(app.world().resource::<BalanceValue>().value, 250);
+    assert_eq!(app.world().resource::<StartingBalance>().0, 250);
+    let cameras = app.world_mut().query::<&Camera2d>().iter(app.world()).count();
+    assert_eq!(cameras, 1);
+}
+
+#[test]
+fn test_the_core_plays_without_the_ui(){
+    let mut app = App::new();
+    app.insert_resource(DataDir(std::env::temp_dir().join(format!("blackjack_core_only_{}", std::process::id()))));
+    app.add_plugins((
+        HeadlessPlugin,
+        BlackjackCorePlugin::default()
+            .with_initial_state(AppState::InGame)
+            .with_seed(7)
+            .with_bot("blackjack-bot-that-does-not-exist", Duration::from_secs(1)),
+    ));
+    for _ in 0..10 {
+        app.update();
+    }
+    assert!(matches!(app.world().resource::<GameErrors>().last_error, Some(GameError::BotNotStarted(_))));
+    assert_eq!(*app.world().resource::<State<GameRoundState>>().get(), GameRoundState::Betting);
+
+    //the chips go on the seat as the chip buttons would put them, and the deal is sent without a button to press
+    let world = app.world_mut();
+    for mut player_hands in world.query::<&mut PlayerHands>().iter_mut(world) {
+        if let Some(player_hand) = player_hands.0.first_mut() {
+            player_hand.bet = 10;
+        }
+    }
+    world.resource_mut::<BetValue>().value = 10;
+    world.resource_mut::<BalanceValue>().value -= 10;
+    world.send_event(PlayerAction::new(ActionKind::Deal));
+    for _ in 0..10 {
+        app.update();
+    }
+    assert_ne!(*app.world().resource::<State<GameRoundState>>().get(), GameRoundState::Betting);
+    let dealt = app.world_mut().query::<&PlayerHands>().iter(app.world()).any(|player_hands| player_hands.0.first().is_some_and(|hand| hand.cards.len() >= 2));
+    assert!(dealt);
+}
@@ -0,0 +1,272 @@
+mod harness;
+
+use bevy::prelude::*;
+use blackjack::game::components::{DealerHand, Deck, InspectorOverlay, InspectorText, PlayerHands};
+use blackjack::game::console::DevConsole;
+use blackjack::game::constants::GameRoundState;
+use blackjack::game::errors::{GameError, GameErrors};
+use blackjack::game::events::{ActionTaken, CardDealt, CardHolder, DealerRevealed, HandSettled, RoundStarted, ShoeShuffled};
+use blackjack::game::hand_history::HandHistory;
+use blackjack::game::rules::Rules;
+use harness::Harness;
+
+#[test]
+fn test_player_bust_loses_the_bet(){
+    let mut harness = Harness::new();
+    harness.stack_shoe(&["10H", "7D", "6C", "10S", "KD"]);
+    harness.bet(50);
+    harness.assert_balance(950);
+    harness.deal();
+    harness.hit();
+    harness.step_until(GameRoundState::RoundEnd);
+    assert_eq!(harness.player_cards(), vec!["10H", "6C", "KD"]);
+    harness.assert_balance(950);
+    assert_eq!(harness.bet_on_table(), 0);
+}
+
+#[test]
+fn test_dealer_bust_pays_the_bet(){
+    let mut harness = Harness::new();
+    //the dealer has 16 and draws the king
+    harness.stack_shoe(&["10H", "6D", "8C", "10S", "KD"]);
+    harness.bet(50);
+    harness.deal();
+    harness.stand();
+    harness.step_until(GameRoundState::RoundEnd);
+    harness.assert_balance(1050);
+}
+
+#[test]
+fn test_a_blackjack_pays_3_to_2(){
+    let mut harness = Harness::new();
+    //the player is dealt a blackjack and the dealer stands on 17
+    harness.stack_shoe(&["AH", "7D", "KC", "10S"]);
+    harness.bet(50);
+    harness.deal();
+    harness.stand();
+    harness.step_until(GameRoundState::RoundEnd);
+    harness.assert_balance(1075);
+}
+
+#[test]
+fn test_double_down_doubles_the_bet_and_draws_one_card(){
+    let mut harness = Harness::new();
+    //11 against a dealer 17, the double draws a ten
+    harness.stack_shoe(&["5H", "10D", "6C", "7S", "10C"]);
+    harness.bet(50);
+    harness.deal();
+    harness.double_down();
+    harness.assert_balance(900);
+    harness.step_until(GameRoundState::RoundEnd);
+    assert_eq!(harness.player_cards().len(), 3);
+    harness.assert_balance(1100);
+
+    //a double the balance does not cover is turned down
+    harness.keep_playing();
+    harness.stack_shoe(&["5H", "10D", "6C", "7S", "10C"]);
+    harness.bet(600);
+    harness.deal();
+    harness.double_down();
+    assert_eq!(harness.round_state(), GameRoundState::PlayerHand);
+    assert_eq!(harness.player_cards().len(), 2);
+    harness.assert_balance(500);
+}
+
+#[test]
+fn test_home_resets_the_game(){
+    let mut harness = Harness::new();
+    harness.stack_shoe(&["10H", "7D", "6C", "10S"]);
+    harness.bet(100);
+    harness.deal();
+    harness.home();
+    harness.assert_balance(1000);
+    assert_eq!(harness.round_state(), GameRoundState::RoundStart);
+
+    harness.start_playing();
+    assert_eq!(harness.bet_on_table(), 0);
+    assert!(harness.player_cards().is_empty());
+    harness.stack_shoe(&["10H", "7D", "9C", "10S"]);
+    harness.bet(10);
+    harness.deal();
+    harness.stand();
+    harness.step_until(GameRoundState::RoundEnd);
+    harness.assert_balance(1010);
+}
+
+#[test]
+fn test_a_missing_dealer_calls_the_round_off(){
+    let mut harness = Harness::new();
+    harness.stack_shoe(&["10H", "7D", "9C", "10S"]);
+    harness.bet(50);
+    harness.deal();
+    let world = harness.app.world_mut();
+    let dealer = world.query_filtered::<Entity, With<DealerHand>>().single(world);
+    world.despawn(dealer);
+
+    //the dealer's turn cannot be played, the bet is handed back and the table goes back to betting
+    harness.stand();
+    harness.step_until(GameRoundState::Betting);
+    harness.assert_balance(1000);
+    assert_eq!(harness.bet_on_table(), 0);
+    assert!(harness.player_cards().is_empty());
+    assert_eq!(harness.app.world().resource::<GameErrors>().last_error, Some(GameError::NoDealer));
+}
+
+#[test]
+fn test_a_seat_without_a_hand_hands_the_chips_back(){
+    let mut harness = Harness::new();
+    let world = harness.app.world_mut();
+    for mut player_hands in world.query::<&mut PlayerHands>().iter_mut(world) {
+        player_hands.0.clear();
+    }
+
+    //the chip has nowhere to go, it is handed back
+    harness.bet(10);
+    harness.assert_balance(1000);
+    assert_eq!(harness.bet_on_table(), 0);
+    assert_eq!(harness.app.world().resource::<GameErrors>().last_error, Some(GameError::MissingHand(0)));
+}
+
+//TableEvents keeps the events the table sent, written as short lines, so a round can be checked as a whole
+#[derive(Resource, Default)]
+struct TableEvents(Vec<String>);
+
+fn record_table_events(
+    mut table_events: ResMut<TableEvents>,
+    mut round_started: EventReader<RoundStarted>,
+    mut card_dealt: EventReader<CardDealt>,
+    mut action_taken: EventReader<ActionTaken>,
+    mut dealer_revealed: EventReader<DealerRevealed>,
+    mut hand_settled: EventReader<HandSettled>,
+    mut shoe_shuffled: EventReader<ShoeShuffled>,
+) {
+    for shuffled in shoe_shuffled.read() {
+        table_events.0.push(format!("shuffle {}", shuffled.shuffle_count));
+    }
+    for started in round_started.read() {
+        table_events.0.push(format!("round {:?}", started.seats));
+    }
+    for dealt in card_dealt.read() {
+        let holder = match dealt.holder {
+            CardHolder::Seat(seat) => format!("seat {seat}"),
+            CardHolder::Dealer => String::from("dealer"),
+        };
+        table_events.0.push(format!("{} to {holder}{}", dealt.card.code(), if dealt.face_up { "" } else { " face down" }));
+    }
+    for taken in action_taken.read() {
+        table_events.0.push(format!("{:?} {}", taken.action, taken.card.as_ref().map_or_else(String::new, |card| card.code())));
+    }
+    for revealed in dealer_revealed.read() {
+        table_events.0.push(format!("revealed {} for {}", revealed.hole_card.code(), revealed.total));
+    }
+    for settled in hand_settled.read() {
+        table_events.0.push(format!("{:?} {}", settled.outcome, settled.payout()));
+    }
+}
+
+#[test]
+fn test_the_table_sends_the_round_as_events(){
+    let mut harness = Harness::new();
+    harness.app.init_resource::<TableEvents>().add_systems(Last, record_table_events);
+    harness.stack_shoe(&["10H", "7D", "6C", "10S", "4D"]);
+    harness.bet(50);
+    harness.deal();
+    harness.hit();
+    harness.stand();
+    harness.step_until(GameRoundState::RoundEnd);
+
+    assert_eq!(harness.app.world().resource::<TableEvents>().0, vec![
+        "round [0]", "10H to seat 0", "7D to dealer face down", "6C to seat 0", "10S to dealer",
+        "4D to seat 0", "Hit 4D", "Stand ", "revealed 7D for 17", "Win 100",
+    ]);
+}
+
+#[test]
+fn test_a_bust_hand_is_settled_before_the_dealer_plays(){
+    let mut harness = Harness::new();
+    harness.app.init_resource::<TableEvents>().add_systems(Last, record_table_events);
+    harness.stack_shoe(&["10H", "7D", "6C", "10S", "KD"]);
+    harness.bet(50);
+    harness.deal();
+    harness.hit();
+    //the bet is collected as soon as the hand goes bust
+    assert_eq!(harness.bet_on_table(), 0);
+    harness.step_until(GameRoundState::RoundEnd);
+
+    assert_eq!(harness.app.world().resource::<TableEvents>().0, vec![
+        "round [0]", "10H to seat 0", "7D to dealer face down", "6C to seat 0", "10S to dealer",
+        "KD to seat 0", "Hit KD", "Loss 0", "revealed 7D for 17",
+    ]);
+    harness.assert_balance(950);
+}
+
+#[test]
+fn test_the_shoe_running_out_in_the_middle_of_a_deal_sends_one_shuffle(){
+    let mut harness = Harness::new();
+    harness.app.init_resource::<TableEvents>().add_systems(Last, record_table_events);
+    harness.bet(50);
+    let shuffle_count = {
+        let mut deck = harness.app.world_mut().resource_mut::<Deck>();
+        //two cards are left, the third one of the deal reshuffles the shoe
+        deck.last_dealt_index = deck.cards.len() - 3;
+        deck.shuffle_count
+    };
+    harness.deal();
+
+    let table_events = &harness.app.world().resource::<TableEvents>().0;
+    let shuffles: Vec<&String> = table_events.iter().filter(|line| line.starts_with("shuffle")).collect();
+    assert_eq!(shuffles, vec![&format!("shuffle {}", shuffle_count + 1)]);
+
+    //the hand history knows which shuffle each card came from
+    let record = harness.app.world().resource::<HandHistory>().current.clone().unwrap();
+    assert_eq!(record.shuffle_count, shuffle_count);
+    let shuffles: Vec<u32> = record.dealt.iter().map(|dealt| dealt.shuffle_count).collect();
+    assert_eq!(shuffles, vec![shuffle_count, shuffle_count, shuffle_count + 1, shuffle_count + 1]);
+}
+
+#[test]
+fn test_the_console_changes_the_running_table(){
+    let mut harness = Harness::new();
+    harness.stack_shoe(&["10H", "7D", "6C", "10S"]);
+    harness.bet(50);
+    harness.deal();
+
+    let submit = |harness: &mut Harness, line: &str| {
+        harness.app.world_mut().resource_mut::<DevConsole>().submit(line);
+        harness.app.update();
+    };
+    submit(&mut harness, "give 5D");
+    assert_eq!(harness.player_cards(), vec!["10H", "6C", "5D"]);
+    submit(&mut harness, "balance 5000");
+    harness.assert_balance(5000);
+    submit(&mut harness, "rules set h17 false");
+    assert!(!harness.app.world().resource::<Rules>().dealer_hits_soft_17);
+
+    //the player's 21 stands against the dealer's 17, who no longer hits it
+    submit(&mut harness, "state dealer hand");
+    harness.step_until(GameRoundState::RoundEnd);
+    harness.assert_balance(5100);
+    let lines = &harness.app.world().resource::<DevConsole>().lines;
+    assert!(lines.contains(&String::from("Player Hand -> Dealer Hand")), "{lines:?}");
+}
+
+#[test]
+fn test_f3_shows_the_inspector(){
+    let mut harness = Harness::new();
+    harness.stack_shoe(&["10H", "7D", "6C", "10S", "4D"]);
+    harness.bet(50);
+    harness.deal();
+    harness.press_key(KeyCode::F3);
+    harness.app.update();
+
+    let world = harness.app.world_mut();
+    let text = world.query_filtered::<&Text, With<InspectorText>>().single(world).sections[0].value.clone();
+    for line in ["round Player Hand", "last dealt index 4", "next 4D", "balance 950, bet 50", "4 cards on screen", "PlayerCard(1) seat 0", "DealerCard(0)"] {
+        assert!(text.contains(line), "{line} is not in\n{text}");
+    }
+
+    harness.press_key(KeyCode::F3);
+    harness.app.update();
+    let world = harness.app.world_mut();
+    assert!(world.query_filtered::<Entity, With<InspectorOverlay>>().iter(world).next().is_none());
+}
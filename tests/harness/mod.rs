@@ -0,0 +1,229 @@
+//harness runs the real game app without a window and plays it the way a player would: pressing keys and buttons,
+//then stepping frames until the round gets where it is expected to
+#![allow(dead_code)]
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::input::ButtonState;
+use bevy::prelude::*;
+use blackjack::game::components::{Card, ChipButtonValue, Deck, LeaveChoice, PlayerButtonValues, PlayerHands, Seat};
+use blackjack::game::constants::{AppState, GameRoundState, PauseState};
+use blackjack::game::events::{ActionKind, PlayerAction};
+use blackjack::game::headless_app_with_data_dir;
+use blackjack::game::resources::{BalanceValue, BetValue};
+use blackjack::game::scenario::Scenario;
+
+//any phase of a round is reached well within this many frames
+const MAX_FRAMES: usize = 100;
+
+//every harness saves into a directory of its own, the tests of a file run side by side
+static NEXT_HARNESS: AtomicUsize = AtomicUsize::new(0);
+
+pub struct Harness {
+    pub app: App,
+    pub data_dir: PathBuf,
+}
+
+impl Harness {
+    ///new starts the game on the start screen and presses enter to sit at the table, saving into a temporary directory
+    pub fn new() -> Self {
+        Self::start(None)
+    }
+
+    ///with_scenario sits at the table as `--scenario` does, with the scenario's shoe, rules and balance
+    pub fn with_scenario(scenario: Scenario) -> Self {
+        Self::start(Some(scenario))
+    }
+
+    fn start(scenario: Option<Scenario>) -> Self {
+        let data_dir = std::env::temp_dir().join(format!("blackjack_harness_{}_{}", std::process::id(), NEXT_HARNESS.fetch_add(1, Ordering::Relaxed)));
+        let _ = std::fs::remove_dir_all(&data_dir);
+        let mut harness = Harness { app: headless_app_with_data_dir(data_dir.clone()), data_dir };
+        if let Some(scenario) = scenario {
+            harness.app.insert_resource(scenario);
+        }
+        harness.app.update();
+        harness.start_playing();
+        harness
+    }
+
+    ///start_playing presses enter on the start screen and waits for the bets
+    pub fn start_playing(&mut self) {
+        self.step_until_app_state(AppState::Start);
+        self.press_key(KeyCode::Enter);
+        self.step_until(GameRoundState::Betting);
+    }
+
+    ///stack_shoe puts cards on top of the shoe, dealt in this order for a single seat: the player's first card, the
+    /// dealer's hole card, the player's second card, the dealer's up card, then every card drawn
+    pub fn stack_shoe(&mut self, codes: &[&str]) {
+        let cards: Vec<Card> = codes.iter().map(|code| Card::from_code(code).unwrap_or_else(|| panic!("{code} is not a card"))).collect();
+        *self.app.world_mut().resource_mut::<Deck>() = Deck::rigged(&cards, 0);
+    }
+
+    ///play makes one of the plays of a scenario: "bet <amount>", "deal", "hit", "stand", "double", "split" or "keep_playing"
+    pub fn play(&mut self, play: &str) {
+        match play.split_whitespace().collect::<Vec<_>>().as_slice() {
+            ["bet", amount] => self.bet(amount.parse().unwrap_or_else(|_| panic!("cannot bet {amount}"))),
+            ["deal"] => self.deal(),
+            ["hit"] => self.hit(),
+            ["stand"] => self.stand(),
+            ["double"] => self.double_down(),
+            ["split"] => self.send_action(ActionKind::Split),
+            ["keep_playing"] => {
+                self.step_until(GameRoundState::RoundEnd);
+                self.keep_playing();
+            }
+            _ => panic!("unknown play {play}"),
+        }
+    }
+
+    ///send_action sends an action the way the keyboard or a gamepad does, for the actions without a button
+    pub fn send_action(&mut self, kind: ActionKind) {
+        self.app.world_mut().send_event(PlayerAction::new(kind));
+        self.app.update();
+    }
+
+    ///bet puts the amount on the selected seat with the fewest chips
+    pub fn bet(&mut self, amount: i32) {
+        let mut left = amount;
+        for (chip, value) in [(ChipButtonValue::Fifty, 50), (ChipButtonValue::Ten, 10), (ChipButtonValue::Five, 5), (ChipButtonValue::One, 1)] {
+            while left >= value {
+                self.press(chip);
+                left -= value;
+            }
+        }
+    }
+
+    ///deal presses deal and waits for the player's turn
+    pub fn deal(&mut self) {
+        self.press(PlayerButtonValues::Deal);
+        self.step_until(GameRoundState::PlayerHand);
+    }
+
+    pub fn hit(&mut self) {
+        self.press(PlayerButtonValues::Hit);
+    }
+
+    pub fn stand(&mut self) {
+        self.press(PlayerButtonValues::Stand);
+    }
+
+    pub fn double_down(&mut self) {
+        self.press(PlayerButtonValues::DoubleDown);
+    }
+
+    ///keep_playing clears the settled round and waits for the next bets
+    pub fn keep_playing(&mut self) {
+        self.press(PlayerButtonValues::KeepPlaying);
+        self.step_until(GameRoundState::Betting);
+    }
+
+    ///home goes back to the start screen and waits for the round to be reset, leaving when asked to confirm it
+    pub fn home(&mut self) {
+        self.press(PlayerButtonValues::Home);
+        self.app.update();
+        if self.pause_state() == PauseState::ConfirmLeave {
+            self.press(LeaveChoice::Leave);
+        }
+        self.step_until_app_state(AppState::Start);
+        self.step_until(GameRoundState::RoundStart);
+    }
+
+    ///step_until runs frames until the round is in the given phase, failing the test if it never gets there
+    pub fn step_until(&mut self, state: GameRoundState) {
+        for _ in 0..MAX_FRAMES {
+            if self.round_state() == state {
+                return;
+            }
+            self.app.update();
+        }
+        panic!("the round is stuck in {:?} instead of reaching {:?}", self.round_state(), state);
+    }
+
+    ///step_until_app_state runs frames until the game shows the given screen
+    pub fn step_until_app_state(&mut self, state: AppState) {
+        for _ in 0..MAX_FRAMES {
+            if *self.app.world().resource::<State<AppState>>().get() == state {
+                return;
+            }
+            self.app.update();
+        }
+        panic!("the game never got to {:?}", state);
+    }
+
+    pub fn pause_state(&self) -> PauseState {
+        *self.app.world().resource::<State<PauseState>>().get()
+    }
+
+    pub fn round_state(&self) -> GameRoundState {
+        *self.app.world().resource::<State<GameRoundState>>().get()
+    }
+
+    pub fn balance(&self) -> i32 {
+        self.app.world().resource::<BalanceValue>().value
+    }
+
+    pub fn bet_on_table(&self) -> i32 {
+        self.app.world().resource::<BetValue>().value
+    }
+
+    #[track_caller]
+    pub fn assert_balance(&self, expected: i32) {
+        assert_eq!(self.balance(), expected, "balance in {:?}", self.round_state());
+    }
+
+    ///player_cards returns the codes of the cards of the first seat
+    pub fn player_cards(&mut self) -> Vec<String> {
+        let world = self.app.world_mut();
+        let mut query = world.query::<(&Seat, &PlayerHands)>();
+        query.iter(world)
+            .find(|(seat, _)| seat.0 == 0)
+            .map(|(_, player_hands)| player_hands.0[0].cards.iter().map(Card::code).collect())
+            .unwrap_or_default()
+    }
+
+    ///press presses a button the way a click does and runs the frame that reads it, then lets go of it
+    pub fn press<T: Component + PartialEq>(&mut self, button: T) {
+        let world = self.app.world_mut();
+        let mut query = world.query::<(Entity, &T)>();
+        let entity = query.iter(world)
+            .find(|(_, value)| **value == button)
+            .map(|(entity, _)| entity)
+            .expect("the button is not on screen");
+        set_interaction(world, entity, Interaction::Pressed);
+        self.app.update();
+        set_interaction(self.app.world_mut(), entity, Interaction::None);
+    }
+
+    ///press_key presses and releases a key, the frame in between reading it
+    pub fn press_key(&mut self, key_code: KeyCode) {
+        self.send_key(key_code, ButtonState::Pressed);
+        self.app.update();
+        self.send_key(key_code, ButtonState::Released);
+    }
+
+    fn send_key(&mut self, key_code: KeyCode, state: ButtonState) {
+        self.app.world_mut().send_event(KeyboardInput {
+            key_code,
+            logical_key: Key::Unidentified(bevy::input::keyboard::NativeKey::Unidentified),
+            state,
+            window: Entity::PLACEHOLDER,
+        });
+    }
+}
+
+fn set_interaction(world: &mut World, entity: Entity, interaction: Interaction) {
+    if let Some(mut current) = world.get_mut::<Interaction>(entity) {
+        if *current != interaction {
+            *current = interaction;
+        }
+    }
+}
+
+impl Drop for Harness {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.data_dir);
+    }
+}
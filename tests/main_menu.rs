@@ -0,0 +1,70 @@
+mod harness;
+
+use bevy::prelude::*;
+use blackjack::game::components::MenuEntry;
+use blackjack::game::constants::{AppState, GameRoundState};
+use blackjack::game::headless_app_with_data_dir;
+use blackjack::game::resources::{LastSession, MenuFocus};
+use blackjack::game::trainer::Trainer;
+use harness::Harness;
+
+fn app_state(harness: &Harness) -> AppState {
+    *harness.app.world().resource::<State<AppState>>().get()
+}
+
+#[test]
+fn test_continue_sits_back_down_with_the_last_balance(){
+    let mut harness = Harness::new();
+    harness.stack_shoe(&["10H", "7D", "6C", "10S"]);
+    harness.bet(100);
+    harness.deal();
+    //the bet on the table is lost when going home
+    harness.home();
+    harness.assert_balance(1000);
+
+    harness.press_key(KeyCode::ArrowDown);
+    assert_eq!(MenuEntry::ALL[harness.app.world().resource::<MenuFocus>().0], MenuEntry::Continue);
+    harness.press_key(KeyCode::Enter);
+    harness.step_until(GameRoundState::Betting);
+    assert_eq!(app_state(&harness), AppState::InGame);
+    harness.assert_balance(900);
+}
+
+#[test]
+fn test_the_last_session_is_there_after_a_restart(){
+    let mut harness = Harness::new();
+    harness.bet(100);
+    harness.deal();
+    harness.home();
+
+    //the game is started again on the same data directory
+    let mut restarted = headless_app_with_data_dir(harness.data_dir.clone());
+    restarted.update();
+    assert_eq!(restarted.world().resource::<LastSession>().balance, Some(900));
+}
+
+#[test]
+fn test_the_menu_goes_to_each_screen(){
+    let mut harness = Harness::new();
+    harness.home();
+
+    harness.press_key(KeyCode::ArrowDown);
+    harness.press_key(KeyCode::ArrowDown);
+    assert_eq!(MenuEntry::ALL[harness.app.world().resource::<MenuFocus>().0], MenuEntry::Practice);
+    harness.press_key(KeyCode::Enter);
+    harness.step_until_app_state(AppState::Practice);
+    harness.press_key(KeyCode::KeyS);
+    assert_eq!(harness.app.world().resource::<Trainer>().answered, 1);
+    harness.press_key(KeyCode::Escape);
+    harness.step_until_app_state(AppState::Start);
+
+    for (entry, state) in [(MenuEntry::Statistics, AppState::Stats), (MenuEntry::Settings, AppState::Settings), (MenuEntry::Rules, AppState::Rules)] {
+        harness.press(entry);
+        harness.step_until_app_state(state);
+        harness.press_key(KeyCode::Escape);
+        harness.step_until_app_state(AppState::Start);
+    }
+
+    harness.press(MenuEntry::Quit);
+    assert!(!harness.app.world().resource::<Events<AppExit>>().is_empty());
+}
@@ -0,0 +1,124 @@
+mod harness;
+
+use bevy::prelude::*;
+use blackjack::game::components::{LeaveChoice, LeaveDialogOverlay, PauseEntry, SettingsScreen};
+use blackjack::game::constants::{AppState, GameRoundState, PauseState};
+use blackjack::game::events::RoundOutcome;
+use blackjack::game::hand_history::hand_history_path;
+use blackjack::game::replay::load_hand_records;
+use blackjack::game::resources::LastSession;
+use blackjack::game::statistics::Statistics;
+use blackjack::game::storage::DataDir;
+use harness::Harness;
+
+fn app_state(harness: &Harness) -> AppState {
+    *harness.app.world().resource::<State<AppState>>().get()
+}
+
+fn time_paused(harness: &Harness) -> bool {
+    harness.app.world().resource::<Time<Virtual>>().is_paused()
+}
+
+fn on_screen<T: Component>(harness: &mut Harness) -> usize {
+    let world = harness.app.world_mut();
+    world.query_filtered::<(), With<T>>().iter(world).count()
+}
+
+#[test]
+fn test_esc_pauses_the_table(){
+    let mut harness = Harness::new();
+    harness.stack_shoe(&["10H", "7D", "6C", "10S", "2H"]);
+    harness.bet(100);
+    harness.deal();
+
+    harness.press_key(KeyCode::Escape);
+    harness.app.update();
+    assert_eq!(harness.pause_state(), PauseState::Paused);
+    assert!(time_paused(&harness));
+    //the table's keys are not read while it is paused
+    harness.press_key(KeyCode::KeyH);
+    assert_eq!(harness.player_cards(), vec!["10H", "6C"]);
+
+    //the settings open over the table and come back to the pause menu
+    harness.press(PauseEntry::Settings);
+    harness.app.update();
+    assert_eq!(harness.pause_state(), PauseState::Settings);
+    assert_eq!(on_screen::<SettingsScreen>(&mut harness), 1);
+    harness.press_key(KeyCode::Escape);
+    harness.app.update();
+    assert_eq!(harness.pause_state(), PauseState::Paused);
+    assert_eq!(on_screen::<SettingsScreen>(&mut harness), 0);
+
+    harness.press_key(KeyCode::Escape);
+    harness.app.update();
+    assert_eq!(harness.pause_state(), PauseState::Running);
+    assert!(!time_paused(&harness));
+    assert_eq!((app_state(&harness), harness.round_state()), (AppState::InGame, GameRoundState::PlayerHand));
+    harness.press_key(KeyCode::KeyH);
+    assert_eq!(harness.player_cards(), vec!["10H", "6C", "2H"]);
+}
+
+#[test]
+fn test_leaving_with_a_bet_asks_first(){
+    let mut harness = Harness::new();
+    harness.stack_shoe(&["10H", "7D", "6C", "10S"]);
+    harness.bet(100);
+    harness.deal();
+
+    //Home asks, and staying goes back to the table
+    harness.press(blackjack::game::components::PlayerButtonValues::Home);
+    harness.app.update();
+    assert_eq!(harness.pause_state(), PauseState::ConfirmLeave);
+    assert_eq!(on_screen::<LeaveDialogOverlay>(&mut harness), 1);
+    harness.press(LeaveChoice::Stay);
+    harness.app.update();
+    assert_eq!(harness.pause_state(), PauseState::Running);
+    assert_eq!(on_screen::<LeaveDialogOverlay>(&mut harness), 0);
+
+    //Quit to Menu asks too, Esc stays on the pause menu and Leave forfeits the bet
+    harness.press_key(KeyCode::Escape);
+    for _ in 0..3 {
+        harness.press_key(KeyCode::ArrowDown);
+    }
+    harness.press_key(KeyCode::Enter);
+    harness.app.update();
+    assert_eq!(harness.pause_state(), PauseState::ConfirmLeave);
+    harness.press_key(KeyCode::Escape);
+    harness.app.update();
+    assert_eq!(harness.pause_state(), PauseState::Paused);
+    harness.press(PauseEntry::QuitToMenu);
+    harness.app.update();
+    harness.press(LeaveChoice::Leave);
+    harness.step_until_app_state(AppState::Start);
+    harness.app.update();
+    assert_eq!(harness.pause_state(), PauseState::Running);
+    assert!(!time_paused(&harness));
+    assert_eq!(harness.app.world().resource::<LastSession>().balance, Some(900));
+}
+
+#[test]
+fn test_leaving_before_the_deal_hands_the_bet_back(){
+    let mut harness = Harness::new();
+    harness.bet(100);
+    harness.home();
+    assert_eq!(harness.app.world().resource::<LastSession>().balance, Some(1000));
+    assert_eq!(harness.app.world().resource::<Statistics>().session.hands_played, 0);
+}
+
+#[test]
+fn test_leaving_a_dealt_hand_records_it_as_lost(){
+    let mut harness = Harness::new();
+    harness.stack_shoe(&["10H", "7D", "6C", "10S"]);
+    harness.bet(100);
+    harness.deal();
+    harness.home();
+    harness.app.update();
+
+    assert_eq!(harness.app.world().resource::<LastSession>().balance, Some(900));
+    let statistics = harness.app.world().resource::<Statistics>();
+    assert_eq!((statistics.session.hands_played, statistics.session.losses), (1, 1));
+    let records = load_hand_records(&hand_history_path(&DataDir(harness.data_dir.clone()), "default")).unwrap();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].seats[0].outcome, Some(RoundOutcome::Loss));
+    assert_eq!((records[0].seats[0].wager, records[0].seats[0].payout, records[0].balance), (100, 0, 900));
+}
@@ -0,0 +1,202 @@
+mod harness;
+
+use std::collections::{HashMap, HashSet};
+use bevy::prelude::*;
+use proptest::prelude::*;
+use blackjack::game::components::{Card, DealerHand, Deck, Decks, PlayerHand, PlayerHands};
+use blackjack::game::constants::GameRoundState;
+use blackjack::game::events::HandSettled;
+use blackjack::game::settlement::dealer_should_hit;
+use blackjack::game::resources::{BalanceValue, BetValue};
+use blackjack::game::traits::{Dealable, Scorable};
+use harness::Harness;
+
+//hi-lo value of a card, as the running count adds it up
+fn hi_lo(card: &Card) -> i32 {
+    match card.value.1 {
+        2..=6 => 1,
+        10 | 11 => -1,
+        _ => 0,
+    }
+}
+
+fn all_codes() -> Vec<String> {
+    let deck = Deck::with_seed(0);
+    let mut codes: Vec<String> = deck.cards.iter().map(Card::code).collect();
+    codes.sort();
+    codes
+}
+
+fn cards(codes: &[String]) -> Vec<Card> {
+    codes.iter().map(|code| Card::from_code(code).unwrap()).collect()
+}
+
+proptest! {
+    #[test]
+    fn test_a_shoe_never_deals_a_card_twice(seed: u64, deals in 1usize..200) {
+        let mut deck = Deck::with_seed(seed);
+        let mut shuffle_count = deck.shuffle_count;
+        let mut dealt_in_shoe = HashSet::new();
+        let mut running_count = 0;
+        for _ in 0..deals {
+            let card = deck.deal();
+            if deck.shuffle_count != shuffle_count {
+                shuffle_count = deck.shuffle_count;
+                dealt_in_shoe.clear();
+                running_count = 0;
+            }
+            running_count += hi_lo(&card);
+            prop_assert!(dealt_in_shoe.insert(card.code()), "{} dealt twice", card.code());
+            prop_assert_eq!(deck.cards_dealt(), dealt_in_shoe.len());
+            prop_assert_eq!(deck.cards_dealt() + deck.cards_remaining(), deck.shoe_size());
+            prop_assert_eq!(deck.running_count(), running_count);
+        }
+    }
+
+    #[test]
+    fn test_a_rigged_deck_deals_its_cards_first(codes in Just(all_codes()).prop_shuffle().prop_flat_map(|codes| (0usize..=51).prop_map(move |count| codes[..count].to_vec())), seed: u64) {
+        let mut deck = Deck::rigged(&cards(&codes), seed);
+        //listing distinct cards leaves a full deck
+        let mut deck_codes: Vec<String> = deck.cards.iter().map(Card::code).collect();
+        deck_codes.sort();
+        prop_assert_eq!(deck_codes, all_codes());
+        for code in &codes {
+            prop_assert_eq!(&deck.deal().code(), code);
+        }
+    }
+
+    #[test]
+    fn test_decks_deal_each_card_once_per_deck(seed: u64, number_of_decks in 1u8..=4, deals in 1usize..=51) {
+        let mut decks = Decks {
+            number_of_decks,
+            decks: (0..number_of_decks as u64).map(|i| Deck::with_seed(seed.wrapping_add(i))).collect(),
+        };
+        let mut dealt: HashMap<String, u8> = HashMap::new();
+        for _ in 0..deals {
+            *dealt.entry(decks.deal().code()).or_default() += 1;
+        }
+        prop_assert!(dealt.values().all(|count| *count <= number_of_decks));
+        prop_assert_eq!(decks.cards_remaining() + deals, number_of_decks as usize * 51);
+    }
+
+    #[test]
+    fn test_hand_totals_stay_in_bounds(codes in Just(all_codes()).prop_shuffle().prop_flat_map(|codes| (1usize..=11).prop_map(move |count| codes[..count].to_vec()))) {
+        let hand = PlayerHand { cards: cards(&codes), bet: 10 };
+        let hard_total: u8 = hand.cards.iter().map(|card| card.value.0).sum();
+        let total = hand.best_total();
+        prop_assert!(total == hard_total || total == hard_total + 10);
+        prop_assert_eq!(hand.is_soft(), total == hard_total + 10);
+        if hand.is_soft() {
+            prop_assert!(total <= 21);
+        }
+        prop_assert_eq!(hand.is_bust(), hard_total > 21);
+    }
+
+    #[test]
+    fn test_the_dealer_ends_between_17_and_26(seed: u64, hits_soft_17: bool) {
+        let mut deck = Deck::with_seed(seed);
+        let mut dealer_hand = DealerHand { cards: vec![deck.deal(), deck.deal()] };
+        while dealer_should_hit(&dealer_hand, hits_soft_17) {
+            dealer_hand.cards.push(deck.deal());
+        }
+        prop_assert!((17..=26).contains(&dealer_hand.best_total()));
+    }
+}
+
+//Violations collects every broken invariant seen while a session is played
+#[derive(Resource, Default)]
+struct Violations(Vec<String>);
+
+//watch_money checks after every frame that the balance never goes negative, that money only leaves the balance for the
+//table (bets and doubles) and that the player's money as a whole only changes when a hand is settled, at the end of the
+//round or as soon as it goes bust
+fn watch_money(
+    game_state: Res<State<GameRoundState>>,
+    balance_value: Res<BalanceValue>,
+    bet_value: Res<BetValue>,
+    mut last: Local<Option<(i32, i32)>>,
+    mut violations: ResMut<Violations>,
+    player_query: Query<&PlayerHands>,
+    dealer_query: Query<&DealerHand>,
+    mut hand_settled: EventReader<HandSettled>,
+) {
+    let state = *game_state.get();
+    let settled = hand_settled.read().count() > 0;
+    let (balance, bet) = (balance_value.value, bet_value.value);
+    if balance < 0 {
+        violations.0.push(format!("negative balance {balance} in {state:?}"));
+    }
+    if let Some((last_balance, last_bet)) = *last {
+        if balance != last_balance && !matches!(state, GameRoundState::Betting | GameRoundState::PlayerHand | GameRoundState::Settlement) {
+            violations.0.push(format!("balance changed from {last_balance} to {balance} in {state:?}"));
+        }
+        if balance + bet != last_balance + last_bet && state != GameRoundState::Settlement && !settled {
+            violations.0.push(format!("money changed from {} to {} in {state:?}", last_balance + last_bet, balance + bet));
+        }
+    }
+    *last = Some((balance, bet));
+
+    //a hand can be hit up to 21, so the most it reaches is 31; the dealer stops drawing at 17
+    for player_hands in player_query.iter() {
+        if player_hands.0.iter().any(|hand| hand.best_total() > 31) {
+            violations.0.push(format!("player hand over 31 in {state:?}"));
+        }
+    }
+    if dealer_query.iter().any(|dealer_hand| dealer_hand.best_total() > 26) {
+        violations.0.push(format!("dealer hand over 26 in {state:?}"));
+    }
+}
+
+//an action of the player's, as generated for a round
+#[derive(Debug, Clone, Copy)]
+enum Play {
+    Hit,
+    Stand,
+    Double,
+}
+
+fn play() -> impl Strategy<Value = Play> {
+    prop_oneof![Just(Play::Hit), Just(Play::Stand), Just(Play::Double)]
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(16))]
+
+    #[test]
+    fn test_sessions_conserve_money(seed: u64, rounds in prop::collection::vec((1i32..400, prop::collection::vec(play(), 0..5)), 1..6)) {
+        let mut harness = Harness::new();
+        harness.app.insert_resource(Deck::with_seed(seed));
+        harness.app.init_resource::<Violations>();
+        harness.app.add_systems(Last, watch_money);
+
+        for (i, (bet, plays)) in rounds.iter().enumerate() {
+            if i > 0 {
+                harness.keep_playing();
+            }
+            let bet = (*bet).min(harness.balance());
+            if bet == 0 {
+                break;
+            }
+            harness.bet(bet);
+            harness.deal();
+            for play in plays {
+                if harness.round_state() != GameRoundState::PlayerHand {
+                    break;
+                }
+                match play {
+                    Play::Hit => harness.hit(),
+                    Play::Stand => harness.stand(),
+                    Play::Double => harness.double_down(),
+                }
+            }
+            if harness.round_state() == GameRoundState::PlayerHand {
+                harness.stand();
+            }
+            //every round ends up settled
+            harness.step_until(GameRoundState::RoundEnd);
+            prop_assert_eq!(harness.bet_on_table(), 0);
+        }
+        let violations = &harness.app.world().resource::<Violations>().0;
+        prop_assert!(violations.is_empty(), "{:?}", violations);
+    }
+}
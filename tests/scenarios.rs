@@ -0,0 +1,34 @@
+mod harness;
+
+use std::fs;
+use std::path::PathBuf;
+use blackjack::game::constants::GameRoundState;
+use blackjack::game::scenario::{Scenario, SCENARIOS_DIRECTORY};
+use harness::Harness;
+
+#[test]
+fn test_scenario_corpus(){
+    let mut paths: Vec<PathBuf> = fs::read_dir(SCENARIOS_DIRECTORY).unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|extension| extension == "json"))
+        .collect();
+    paths.sort();
+    assert!(paths.len() >= 4);
+
+    for path in paths {
+        let scenario = Scenario::load(&path).unwrap();
+        let mut harness = Harness::with_scenario(scenario.clone());
+        for play in &scenario.plays {
+            harness.play(play);
+        }
+        harness.step_until(GameRoundState::RoundEnd);
+        assert_eq!(Some(harness.balance()), scenario.expected_balance, "{}: {}", path.display(), scenario.description);
+    }
+}
+
+#[test]
+fn test_scenario_files_are_checked(){
+    assert!(Scenario::parse(r#"{"cards": "AS KH 8C 8D"}"#).is_ok());
+    assert!(Scenario::parse(r#"{"cards": "AS KX"}"#).is_err());
+    assert!(Scenario::parse(r#"{"balance": 100}"#).is_err());
+}
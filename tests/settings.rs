@@ -0,0 +1,60 @@
+mod harness;
+
+use bevy::prelude::*;
+use blackjack::game::components::{MenuEntry, TextComponents};
+use blackjack::game::constants::{AppState, GameRoundState, COMPANION_ACTION_DELAY};
+use blackjack::game::resources::CompanionTimer;
+use blackjack::game::settings::{settings_path, Settings, TableFelt};
+use blackjack::game::storage::{load_json, DataDir};
+use harness::Harness;
+
+fn text(harness: &mut Harness, component: TextComponents) -> String {
+    let mut query = harness.app.world_mut().query::<(&TextComponents, &Text)>();
+    query.iter(harness.app.world())
+        .find(|(text_component, _)| std::mem::discriminant(*text_component) == std::mem::discriminant(&component))
+        .map(|(_, text)| text.sections[0].value.clone())
+        .unwrap_or_default()
+}
+
+#[test]
+fn test_settings_are_saved_and_applied_live(){
+    let mut harness = Harness::new();
+    harness.home();
+    harness.press(MenuEntry::Settings);
+    harness.step_until_app_state(AppState::Settings);
+
+    //animation speed is the fourth row, the table felt the sixth and hints the tenth
+    for _ in 0..3 {
+        harness.press_key(KeyCode::ArrowDown);
+    }
+    harness.press_key(KeyCode::ArrowRight);
+    harness.press_key(KeyCode::ArrowDown);
+    harness.press_key(KeyCode::ArrowDown);
+    harness.press_key(KeyCode::ArrowLeft);
+    for _ in 0..4 {
+        harness.press_key(KeyCode::ArrowDown);
+    }
+    harness.press_key(KeyCode::Enter);
+    //the settings are saved on the frame after they change, at the latest
+    harness.app.update();
+
+    let settings = harness.app.world().resource::<Settings>().clone();
+    assert_eq!((settings.animation_speed, settings.table_felt, settings.hints), (1.5, TableFelt::Red, false));
+    assert_eq!(load_json::<Settings>(&settings_path(&DataDir(harness.data_dir.clone()))).unwrap(), Some(settings));
+    let companion_delay = harness.app.world().resource::<CompanionTimer>().0.duration().as_secs_f32();
+    assert!((companion_delay - COMPANION_ACTION_DELAY / 1.5).abs() < 0.001);
+
+    //hints are off at the table, and back on when turned on again
+    harness.press_key(KeyCode::Escape);
+    harness.step_until_app_state(AppState::Start);
+    harness.press(MenuEntry::NewGame);
+    harness.step_until(GameRoundState::Betting);
+    harness.stack_shoe(&["10H", "7D", "6C", "10S"]);
+    harness.bet(100);
+    harness.deal();
+    harness.app.update();
+    assert_eq!(text(&mut harness, TextComponents::Hint), "");
+    harness.app.world_mut().resource_mut::<Settings>().hints = true;
+    harness.app.update();
+    assert_eq!(text(&mut harness, TextComponents::Hint), "Basic strategy: Hit");
+}